@@ -0,0 +1,97 @@
+//! The launcher with a fortune cookie mode, built against the library instead of a
+//! fork. Run it with `cargo run --example fortune`, then type `fortune ` to list the
+//! cookies baked so far. Enter eats the selected one and Alt+B bakes another, from the
+//! `fortune` command if it's installed or else from a built-in jar.
+
+use std::{io, process::Command};
+
+use launcher::{Action, Item, Outcome, Source};
+
+const JAR: [&str; 6] = [
+    "A journey of a thousand miles begins with a single keystroke.",
+    "You will find what you are looking for by typing less of it.",
+    "The bug you seek is in the code you trust.",
+    "Today is a good day to read the manual.",
+    "An empty query matches everything.",
+    "Your next release will be on time.",
+];
+
+#[derive(Debug)]
+struct Fortune {
+    /// The cookies not eaten yet with their numbers, oldest first.
+    cookies: Vec<(usize, String)>,
+    /// How many were baked, so each gets its own id.
+    baked: usize,
+}
+
+impl Fortune {
+    fn new() -> Self {
+        let mut fortune = Self {
+            cookies: Vec::new(),
+            baked: 0,
+        };
+        for _ in 0..3 {
+            fortune.bake();
+        }
+        fortune
+    }
+
+    fn bake(&mut self) {
+        let cookie = Command::new("fortune")
+            .arg("-s")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| {
+                let cookie = String::from_utf8_lossy(&output.stdout);
+                cookie.split_whitespace().collect::<Vec<&str>>().join(" ")
+            })
+            .filter(|cookie| !cookie.is_empty())
+            .unwrap_or_else(|| JAR[self.baked % JAR.len()].to_string());
+        self.cookies.push((self.baked, cookie));
+        self.baked += 1;
+    }
+}
+
+impl Source for Fortune {
+    fn keyword(&self) -> &str {
+        "fortune"
+    }
+
+    fn title(&self) -> &str {
+        "Fortune"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.cookies
+            .iter()
+            .map(|(number, cookie)| {
+                let mut item = Item::new(number.to_string(), cookie, "🥠");
+                item.detail = Some(format!("#{}", number));
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        let mut bake = Action::new("bake");
+        bake.key = Some('b');
+        bake.bulk = true;
+        vec![Action::new("eat"), bake]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match (action, item) {
+            ("eat", Some(item)) => self
+                .cookies
+                .retain(|(number, _)| number.to_string() != item.id),
+            ("bake", _) => self.bake(),
+            _ => {}
+        }
+        Ok(Outcome::Refresh)
+    }
+}
+
+fn main() -> io::Result<()> {
+    launcher::run(vec![Box::new(Fortune::new())])
+}
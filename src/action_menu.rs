@@ -0,0 +1,91 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    widgets::{Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{
+    desktop_entry::{DesktopAction, DesktopEntry},
+    theme,
+};
+
+/// What a key press did to the action menu.
+#[derive(Debug, Clone)]
+pub enum MenuAnswer {
+    Chosen(DesktopAction),
+    Cancelled,
+    Pending,
+}
+
+/// A modal listing the extra actions of an entry.
+#[derive(Debug)]
+pub struct ActionMenu {
+    pub entry: DesktopEntry,
+    actions: Vec<DesktopAction>,
+    state: ListState,
+}
+
+impl ActionMenu {
+    pub fn new(entry: DesktopEntry, actions: Vec<DesktopAction>) -> Self {
+        Self {
+            entry,
+            actions,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> MenuAnswer {
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.actions.len().saturating_sub(1);
+                self.state
+                    .select(self.state.selected().map(|i| (i + 1).min(last)));
+            }
+            KeyCode::Enter => {
+                return self
+                    .state
+                    .selected()
+                    .and_then(|i| self.actions.get(i))
+                    .map_or(MenuAnswer::Cancelled, |action| {
+                        MenuAnswer::Chosen(action.clone())
+                    })
+            }
+            KeyCode::Esc => return MenuAnswer::Cancelled,
+            _ => {}
+        }
+        MenuAnswer::Pending
+    }
+}
+
+impl Widget for &ActionMenu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let height = self.actions.len() as u16 + 2;
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let theme = theme::get();
+        let list = List::new(
+            self.actions
+                .iter()
+                .map(|action| format!(" {}", action.name)),
+        )
+        .block(theme.block().title(self.entry.name.clone()))
+        .style(theme.text)
+        .highlight_style(theme.selected)
+        .highlight_symbol(&theme.selector);
+        let mut state = self.state.clone();
+        Clear.render(area, buf);
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+}
@@ -0,0 +1,80 @@
+use std::time::Duration;
+
+use ratatui::style::Color;
+
+use crate::theme;
+
+/// How often frames are drawn while something moves.
+pub const FRAME_INTERVAL: Duration = Duration::from_millis(16);
+
+/// How long each frame of the spinner is shown.
+const SPINNER_INTERVAL: Duration = Duration::from_millis(80);
+
+/// Background of the selected row in the frames after the selection moved, fading in
+/// to white.
+const HIGHLIGHT_RAMP: [Color; 3] = [
+    Color::Indexed(244),
+    Color::Indexed(248),
+    Color::Indexed(252),
+];
+
+/// The frame of the theme's spinner `elapsed` after it started, shown while results
+/// are still arriving.
+pub fn spinner(elapsed: Duration) -> char {
+    let frames = &theme::get().spinner;
+    let frame = elapsed.as_millis() / SPINNER_INTERVAL.as_millis();
+    frames
+        .get(frame as usize % frames.len().max(1))
+        .copied()
+        .unwrap_or(' ')
+}
+
+/// Smooth scrolling of the list and a fade-in of the selection, advanced once per frame.
+#[derive(Debug, Default)]
+pub struct Animation {
+    /// The offset drawn, which eases towards the offset the selection needs.
+    shown: usize,
+    target: usize,
+    selected: Option<usize>,
+    /// Frames drawn since the selection moved.
+    highlight_frame: usize,
+}
+
+impl Animation {
+    /// Moves the drawn offset halfway to `target`, but never further than `page` rows
+    /// behind it, and returns it.
+    pub fn scroll(&mut self, target: usize, page: usize) -> usize {
+        self.target = target;
+        self.shown = self.shown.clamp(target.saturating_sub(page), target + page);
+        let step = self.shown.abs_diff(target).div_ceil(2);
+        if self.shown < target {
+            self.shown += step;
+        } else {
+            self.shown -= step;
+        }
+        self.shown
+    }
+
+    /// The background of the `selected` row in this frame.
+    pub fn highlight(&mut self, selected: usize) -> Color {
+        if self.selected != Some(selected) {
+            self.selected = Some(selected);
+            self.highlight_frame = 0;
+        }
+        let color = HIGHLIGHT_RAMP
+            .get(self.highlight_frame)
+            .copied()
+            .unwrap_or(Color::White);
+        self.highlight_frame += 1;
+        color
+    }
+
+    /// Drops any movement in progress, e.g. when the results changed.
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.shown != self.target || self.highlight_frame <= HIGHLIGHT_RAMP.len()
+    }
+}
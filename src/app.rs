@@ -1,273 +1,2427 @@
 use std::{
-    env, fs,
+    cmp::Reverse,
+    collections::HashSet,
+    env,
     io::{self},
-    os::unix::process::CommandExt,
-    path::Path,
+    ops::Range,
+    path::{Path, PathBuf},
     process::Command,
+    sync::mpsc::{Receiver, TryRecvError},
+    thread,
+    time::{Duration, Instant},
 };
 
 use fork::{fork, Fork};
 
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::{
+    event::{self, Event, KeyCode, KeyEventKind, KeyModifiers},
+    execute,
+    terminal::{self, EnterAlternateScreen},
+};
 use ratatui::{
+    backend::Backend,
     buffer::Buffer,
     layout::{Constraint, Layout, Position, Rect},
-    style::{Color, Style, Stylize},
+    style::{Color, Style},
+    text::{Line, Span, Text},
     widgets::{
-        Block, List, ListDirection, ListState, Paragraph, Scrollbar, ScrollbarOrientation,
-        ScrollbarState, StatefulWidget, Widget,
+        Block, Borders, List, ListDirection, ListItem, ListState, Paragraph, Scrollbar,
+        ScrollbarOrientation, ScrollbarState, StatefulWidget, Widget,
     },
-    DefaultTerminal, Frame,
+    Frame, Terminal,
+};
+
+use crate::{
+    action_menu::{ActionMenu, MenuAnswer},
+    animation::{self, Animation},
+    applications,
+    audit::AuditRecord,
+    badge::Badge,
+    compositor,
+    config::{Config, SortOrder},
+    confirm::{Answer, ConfirmDialog},
+    daemon::{self, Daemon},
+    desktop_entry::{self, DesktopAction, DesktopEntry},
+    digraph,
+    entry_editor::{EditorAnswer, EntryEditor},
+    first_seen::{self, FirstSeen},
+    frecency::Frecency,
+    history::QueryHistory,
+    hooks::{self, Hooks},
+    host_terminal::{self, ProcFs},
+    hud::Metrics,
+    input::InputState,
+    keybindings::{self, KeyAction, KeyBinding},
+    launch,
+    locale::{tr, tr_with},
+    log,
+    matcher::Matcher,
+    modal::{Confirmation, Modal, ModalStack, PendingAction},
+    mode_state::{ModeState, ModeStates},
+    names::Names,
+    open,
+    overrides::{self, Override},
+    paths, perf,
+    placement::Placement,
+    preview::Preview,
+    recent_files, release_check,
+    result_cache::ResultCache,
+    row::{Match, Row},
+    running::{self, Processes},
+    screen::Screen,
+    settings::{self, SettingsAnswer, SettingsMenu},
+    signals::Signals,
+    sources::{self, dmenu, Action, Item, Outcome, Source},
+    theme, watcher,
 };
 
-use crate::{config::Config, desktop_entry::DesktopEntry};
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LaunchMode {
+    Normal,
+    Unsandboxed,
+    Elevated,
+}
+
+/// How often running apps are rescanned for the running indicators.
+const PROCESS_SCAN_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Items from which typing only filters once no key was pressed for `FILTER_DEBOUNCE`.
+const DEBOUNCE_ITEMS: usize = 5_000;
+
+const FILTER_DEBOUNCE: Duration = Duration::from_millis(40);
+
+/// How often a daemon checks for clients while idle.
+const DAEMON_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// The widget that receives keys without a global binding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Focus {
+    Input,
+    List,
+}
+
+/// Characters of an app's name shown in the recently launched strip.
+const STRIP_NAME_LENGTH: usize = 12;
+
+/// Rows materialized above and below the visible part of the list.
+const RENDER_MARGIN: usize = 20;
+
+/// A rendered list row, kept until the match positions it was built from change.
+#[derive(Debug)]
+struct CachedLine {
+    positions: Vec<usize>,
+    line: Line<'static>,
+}
 
 #[derive(Debug)]
 pub struct App {
     config: Config,
+    config_path: Option<PathBuf>,
     entries: Vec<DesktopEntry>,
-    filter: String,
-    cursor_index: usize,
+    sources: Vec<Box<dyn Source>>,
+    /// The source whose keyword prefixes the filter, or `None` for applications.
+    mode: Option<usize>,
+    /// Whether `mode` was picked because the source detected the filter, not by keyword.
+    detected: bool,
+    /// Skips detection until the filter is cleared, forced with Alt+P.
+    plain_search: bool,
+    mode_states: ModeStates,
+    items: Vec<Item>,
+    /// The names of `items`, for matching.
+    names: Names,
+    /// Batches of items still arriving from a streaming source.
+    batches: Option<Receiver<Vec<Item>>>,
+    rows: Vec<Row>,
+    /// The entry whose actions are listed below it.
+    expanded: Option<usize>,
+    cache: ResultCache,
+    lines: Vec<Option<CachedLine>>,
+    input: InputState,
+    /// When the filter was last edited without the list being filtered yet, with long
+    /// lists.
+    pending_filter: Option<Instant>,
+    /// Set by Ctrl+K until the digraph's two characters are typed, holding the first.
+    digraph: Option<Option<char>>,
+    /// Arguments for a terminal app typed after its name, with `pass_query_args`.
+    query_args: Option<String>,
+    focus: Focus,
     list_state: ListState,
+    /// The rows the list showed when last drawn, which Page Up and Down move by.
+    page_height: usize,
+    animation: Animation,
     scrollbar_state: ScrollbarState,
+    modals: ModalStack,
+    status: Option<String>,
+    history: QueryHistory,
+    launches: QueryHistory,
+    first_seen: FirstSeen,
+    frecency: Frecency,
+    processes: Processes,
+    process_updates: Option<Receiver<Processes>>,
+    updates: Option<Receiver<PathBuf>>,
+    /// Newer releases found by a daemon's release check.
+    releases: Option<Receiver<String>>,
+    signals: Option<Signals>,
+    /// Set when started with `--daemon`, closing hides the launcher instead.
+    daemon: Option<Daemon>,
+    /// Set while a daemon is hidden, which draws nothing.
+    hidden: bool,
+    needs_redraw: bool,
+    /// Set when something else drew on the terminal, so the next frame is drawn whole.
+    needs_clear: bool,
+    screen: Screen,
+    metrics: Metrics,
+    /// Draws the performance overlay, toggled with F12.
+    show_hud: bool,
     should_exit: bool,
+    /// The app picked in the recently launched strip, cleared by any other key.
+    strip_selection: Option<usize>,
+    /// Set while the items were streamed for the query, so they aren't matched against it.
+    unfiltered: bool,
+    /// When the current batches started arriving, to animate the spinner.
+    batches_started: Instant,
+    /// Batches of desktop entries still arriving from the startup scan.
+    scan: Option<Receiver<Vec<DesktopEntry>>>,
+    scan_started: Instant,
+    /// The output previewing the selected item, if the source has a preview for it.
+    preview: Preview,
+    /// Latencies measured with `perf_telemetry`.
+    perf: Option<perf::Recorder>,
+    /// Set until the item a source preselects arrives or a key is pressed.
+    preselecting: bool,
+    /// Set while Alt+Enter launches the selection without closing the launcher.
+    keep_open: bool,
+    /// Set by Alt+I or `privacy.incognito`, recording no launches or queries.
+    incognito: bool,
+    /// Set while launching a desktop action listed under `privacy.actions`.
+    unrecorded: bool,
+    /// Set while Alt+W launches the selection on a workspace.
+    workspace: Option<String>,
 }
 
 impl App {
-    pub fn new(config: Config) -> Self {
-        let entries = Self::get_desktop_entries();
-        Self {
+    /// An app with the built-in sources followed by `extra`, from a program using the
+    /// launcher as a library.
+    pub fn new(config: Config, config_path: Option<&Path>, extra: Vec<Box<dyn Source>>) -> Self {
+        let started = Instant::now();
+        // Only stdin is listed in dmenu mode, so the applications aren't needed.
+        let dirs = applications::dirs();
+        let updates = config
+            .dmenu
+            .is_none()
+            .then(|| watcher::watch(&dirs))
+            .flatten();
+        let scan = config
+            .dmenu
+            .is_none()
+            .then(|| applications::scan_in_background(dirs));
+        let perf = config.perf_telemetry.then(|| perf::Recorder::new(started));
+        let signals = Signals::register()
+            .inspect_err(|error| log::info(&format!("listening for signals failed: {}", error)))
+            .ok();
+        let first_seen = FirstSeen::load();
+        let frecency = if config.frecency {
+            Frecency::load(config.frecency_half_life)
+        } else {
+            Frecency::default()
+        };
+        let mode_states = ModeStates::load(config.remember_modes);
+        let show_hud = config.debug;
+        let incognito = config.privacy.incognito;
+        let screen = Screen::detect(&config);
+        let process_updates = (config.show_running || config.reuse_windows)
+            .then(|| running::watch(PROCESS_SCAN_INTERVAL));
+        let mode = config.dmenu.as_ref().map(|_| 0);
+        let mut app = Self {
+            sources: sources::all(&config, extra),
             config,
-            entries,
-            filter: String::new(),
-            cursor_index: 0,
+            config_path: config_path.map(Path::to_path_buf),
+            entries: Vec::new(),
+            mode,
+            detected: false,
+            plain_search: false,
+            mode_states,
+            items: Vec::new(),
+            batches: None,
+            unfiltered: false,
+            batches_started: Instant::now(),
+            scan,
+            scan_started: Instant::now(),
+            rows: Vec::new(),
+            expanded: None,
+            cache: ResultCache::default(),
+            names: Names::default(),
+            lines: Vec::new(),
+            input: InputState::default(),
+            pending_filter: None,
+            digraph: None,
+            query_args: None,
+            focus: Focus::Input,
             list_state: ListState::default(),
+            page_height: 1,
+            animation: Animation::default(),
             scrollbar_state: ScrollbarState::default(),
+            modals: ModalStack::default(),
+            status: None,
+            history: QueryHistory::load(),
+            launches: QueryHistory::launches(),
+            first_seen,
+            frecency,
+            processes: Processes::default(),
+            process_updates,
+            updates,
+            releases: None,
+            signals,
+            daemon: None,
+            hidden: false,
+            needs_redraw: true,
+            needs_clear: false,
+            screen,
+            metrics: Metrics::default(),
+            show_hud,
             should_exit: false,
+            strip_selection: None,
+            preview: Preview::default(),
+            perf,
+            preselecting: true,
+            keep_open: false,
+            incognito,
+            unrecorded: false,
+            workspace: None,
+        };
+        app.reload_items();
+        if app.config.dmenu.is_none() {
+            app.restore_mode_state(None);
+        } else {
+            app.update_filter();
         }
+        app
     }
 
-    pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+    /// Keeps running after closing, checking for releases if `check_updates` is set.
+    pub fn with_daemon(mut self, daemon: Option<Daemon>) -> Self {
+        self.releases = daemon
+            .as_ref()
+            .filter(|_| self.config.check_updates)
+            .map(|_| release_check::spawn());
+        self.daemon = daemon;
+        self
+    }
+
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> io::Result<()> {
         while !self.should_exit {
-            terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events()?;
+            if self.needs_clear {
+                terminal.clear()?;
+                self.needs_clear = false;
+            }
+            if self.needs_redraw && !self.hidden {
+                let start = Instant::now();
+                terminal.draw(|frame| self.draw(frame))?;
+                self.metrics.frame_time = start.elapsed();
+                self.needs_redraw = false;
+                if let Some(perf) = &mut self.perf {
+                    perf.frame_drawn();
+                }
+            }
+            self.apply_scan();
+            self.apply_updates();
+            self.apply_processes();
+            self.apply_batches();
+            self.apply_signals();
+            self.apply_daemon_commands();
+            self.apply_releases();
+            self.apply_filter_edit();
+            self.update_preview();
+            let animating = self.config.animates() && self.animation.is_running();
+            let loading = self.loading_since().is_some();
+            // The spinner is the only thing moving while loading; arrivals redraw anyway.
+            let spinning = loading && !self.config.reduced_motion;
+            self.needs_redraw |= self.modals.is_animating() || animating || spinning;
+            let timeout = if let Some(edited) = self.pending_filter {
+                FILTER_DEBOUNCE.saturating_sub(edited.elapsed())
+            } else if animating || loading {
+                animation::FRAME_INTERVAL
+            } else if self.daemon.is_some() {
+                DAEMON_POLL_INTERVAL
+            } else {
+                Duration::from_millis(250)
+            };
+            if event::poll(timeout)? {
+                self.handle_events()?;
+            }
+        }
+        if self.config.dmenu.is_none() {
+            self.save_mode_state();
+            if !self.incognito {
+                self.mode_states.save();
+            }
+        }
+        if let Some(Err(error)) = self.perf.as_ref().map(perf::Recorder::save) {
+            log::info(&format!("saving perf telemetry failed: {}", error));
         }
         Ok(())
     }
 
-    fn select_entry(&mut self) {
-        if let Some(i) = self.list_state.selected() {
-            let entry = &self.get_filtered_entries()[i];
-            let shell = env::var("SHELL").expect("unable to read $SHELL env");
-            if entry.terminal {
-                ratatui::restore();
-                let _ = Command::new(&entry.exec).exec();
+    /// Previews the selected item if the source has a preview for it, redrawing once the
+    /// output arrived.
+    fn update_preview(&mut self) {
+        let preview = self
+            .mode
+            .zip(self.selected_index())
+            .and_then(|(source, index)| {
+                let item = &self.items[index];
+                Some((item.id.clone(), self.sources[source].preview(item)?))
+            });
+        match preview {
+            Some((id, command)) => self.preview.show(&id, command),
+            None => self.preview.hide(),
+        }
+        self.needs_redraw |= self.preview.apply();
+    }
+
+    /// Reloads on SIGUSR1 and closes on SIGUSR2, or toggles a daemon.
+    fn apply_signals(&mut self) {
+        let Some(signals) = &self.signals else {
+            return;
+        };
+        let (toggle, reload) = (signals.take_toggle(), signals.take_reload());
+        if toggle {
+            self.toggle();
+        }
+        if reload {
+            if let Err(error) = self.reload_config() {
+                self.status = Some(tr_with(
+                    "reloading the config failed: {error}",
+                    &[("error", &error.to_string())],
+                ));
+            }
+            self.entries = scan_entries(&self.config);
+            self.first_seen.observe(&self.entries, first_seen::now());
+            self.reload_items();
+            self.update_filter();
+            self.needs_redraw = true;
+            log::info(&format!("reloaded {} entries", self.entries.len()));
+        }
+    }
+
+    fn apply_daemon_commands(&mut self) {
+        let Some(daemon) = &self.daemon else {
+            return;
+        };
+        for command in daemon.take_commands() {
+            match command {
+                daemon::Command::Toggle => self.toggle(),
+                daemon::Command::Show => self.show(),
+                daemon::Command::Hide if !self.hidden => self.close(),
+                daemon::Command::Hide => {}
+                daemon::Command::Quit => self.should_exit = true,
+            }
+        }
+    }
+
+    /// Announces a newer release in the status line, until the launcher is closed.
+    fn apply_releases(&mut self) {
+        let Some(version) = self
+            .releases
+            .as_ref()
+            .and_then(|releases| releases.try_recv().ok())
+        else {
+            return;
+        };
+        self.status = Some(tr_with(
+            "launcher {version} is available",
+            &[("version", &version)],
+        ));
+        self.needs_redraw = true;
+    }
+
+    fn toggle(&mut self) {
+        if self.hidden {
+            self.show();
+        } else {
+            self.close();
+        }
+    }
+
+    fn show(&mut self) {
+        launch::refresh_session();
+        self.hidden = false;
+        self.needs_redraw = true;
+    }
+
+    /// Exits, or hides a daemon after resetting it to how it started: in the
+    /// applications mode with the remembered query, or none unless `remember_modes`.
+    fn close(&mut self) {
+        if self.daemon.is_none() {
+            self.should_exit = true;
+            return;
+        }
+        self.save_mode_state();
+        if !self.incognito {
+            self.mode_states.save();
+        }
+        self.mode_states = ModeStates::load(self.config.remember_modes);
+        self.incognito = self.config.privacy.incognito;
+        self.modals = ModalStack::default();
+        self.focus = Focus::Input;
+        self.status = None;
+        self.strip_selection = None;
+        self.digraph = None;
+        self.preselecting = true;
+        self.restore_mode_state(None);
+        self.hidden = true;
+        self.needs_clear = true;
+    }
+
+    /// Reads the config file again, staying restricted if started with `--restricted`.
+    fn reload_config(&mut self) -> io::Result<()> {
+        let toml = paths::read_config(self.config_path.as_deref())?;
+        let mut config = toml::from_str::<Config>(&toml).map_err(io::Error::other)?;
+        config.restricted |= self.config.restricted;
+        self.config = config;
+        theme::init(&self.config);
+        self.status = None;
+        Ok(())
+    }
+
+    /// Adds the desktop entries the startup scan parsed since the last frame, keeping the
+    /// selection unless nothing was pressed yet.
+    fn apply_scan(&mut self) {
+        let Some(scan) = &self.scan else {
+            return;
+        };
+        let mut arrived = Vec::new();
+        loop {
+            match scan.try_recv() {
+                Ok(batch) => arrived.extend(batch),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.scan = None;
+                    self.needs_redraw = true;
+                    if let Some(perf) = &mut self.perf {
+                        perf.scanned(self.scan_started.elapsed());
+                    }
+                    break;
+                }
+            }
+        }
+        // The watcher may have added some of them already.
+        arrived.retain(|entry| {
+            (!self.config.restricted || self.config.allowed_entries.contains(&entry.id))
+                && !self.entries.iter().any(|other| other.id == entry.id)
+        });
+        if arrived.is_empty() {
+            return;
+        }
+        overrides::apply(&mut arrived);
+        self.first_seen.observe_scanned(&arrived);
+        self.entries.extend(arrived);
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        if self.mode.is_some() {
+            return;
+        }
+        let selected = if self.preselecting {
+            self.mode_states.get(&self.keyword(None)).selected
+        } else {
+            self.selected_index().map(|i| self.items[i].id.clone())
+        };
+        self.reload_items();
+        self.update_filter();
+        self.select_item(selected.as_deref());
+        self.needs_redraw = true;
+    }
+
+    /// Since when the list has been waiting for the scan or a streaming source.
+    fn loading_since(&self) -> Option<Instant> {
+        match self.mode {
+            Some(_) => self.batches.as_ref().map(|_| self.batches_started),
+            None => self.scan.as_ref().map(|_| self.scan_started),
+        }
+    }
+
+    /// Reparses desktop files reported by the watcher and patches them into the index.
+    fn apply_updates(&mut self) {
+        let Some(updates) = &self.updates else {
+            return;
+        };
+        let paths = updates.try_iter().collect::<Vec<PathBuf>>();
+        if paths.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+        let dirs = applications::dirs();
+        // Each changed file's ID is resolved again like a rescan would, so removing or
+        // hiding a file brings back the one it shadowed.
+        let mut ids = HashSet::new();
+        for path in &paths {
+            if path.extension().and_then(|s| s.to_str()) == Some("desktop") {
+                ids.extend(applications::id_in(&dirs, path));
             } else {
-                let output = Command::new(&shell)
-                    .args(&[
-                        "-c",
-                        format!("ps -o ppid= -p {}", std::process::id()).as_str(),
-                    ])
-                    .output()
-                    .expect("unable to get ppid");
-                match fork() {
-                    Ok(Fork::Child) => {
-                        let ppid = String::from_utf8_lossy(&output.stdout);
-                        let _ = Command::new(&shell)
-                            .args(&["-c", "sleep .1"])
-                            .output()
-                            .expect("...");
-                        ratatui::restore();
-                        let _ = Command::new(&shell)
-                            .args(&["-c", format!("kill -9 {}", ppid).as_str()])
+                // A removed directory, whose files may have shadowed others.
+                ids.extend(
+                    self.entries
+                        .iter()
+                        .filter(|entry| entry.path().starts_with(path))
+                        .map(|entry| entry.id.clone()),
+                );
+                ids.extend(applications::ids_in_dir(&dirs, path));
+            }
+        }
+        for id in &ids {
+            self.entries.retain(|entry| entry.id != *id);
+            let Some(entry) = applications::resolve(&dirs, id) else {
+                continue;
+            };
+            if !self.config.restricted || self.config.allowed_entries.contains(&entry.id) {
+                self.entries.push(entry);
+            }
+        }
+        overrides::apply(&mut self.entries);
+        self.entries.sort_by(|a, b| a.name.cmp(&b.name));
+        self.first_seen.observe(&self.entries, first_seen::now());
+        if self.mode.is_none() {
+            self.reload_items();
+            self.update_filter();
+            self.needs_redraw = true;
+        }
+        log::info(&format!(
+            "reparsed {} changed desktop entries in {:.2?}",
+            ids.len(),
+            start.elapsed()
+        ));
+    }
+
+    /// Refreshes the running indicators from the latest background process scan,
+    /// keeping the filter and selection as they are.
+    fn apply_processes(&mut self) {
+        let Some(processes) = self
+            .process_updates
+            .as_ref()
+            .and_then(|updates| updates.try_iter().last())
+        else {
+            return;
+        };
+        self.processes = processes;
+        if self.mode.is_some() {
+            return;
+        }
+        let mut changed = false;
+        for (item, entry) in self.items.iter_mut().zip(&self.entries) {
+            let running = self.config.show_running && self.processes.find(entry).is_some();
+            let badges = Badge::for_entry(entry, &self.config, &self.first_seen, &self.processes);
+            if item.running != running || item.badges != badges {
+                item.running = running;
+                item.badges = badges;
+                changed = true;
+            }
+        }
+        if changed {
+            self.lines = self.items.iter().map(|_| None).collect();
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Adds the items a streaming source sent since the last frame, inserting the
+    /// matching ones into the list without moving the selection.
+    fn apply_batches(&mut self) {
+        let Some(batches) = &self.batches else {
+            return;
+        };
+        let mut arrived = Vec::new();
+        loop {
+            match batches.try_recv() {
+                Ok(batch) => arrived.extend(batch),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    self.batches = None;
+                    self.needs_redraw = true;
+                    break;
+                }
+            }
+        }
+        if arrived.is_empty() {
+            return;
+        }
+        self.cache.clear();
+        let selected = self.selected_index();
+        let query = if self.unfiltered {
+            String::new()
+        } else {
+            self.query().to_string()
+        };
+        let first = self.items.len();
+        for item in arrived {
+            self.names.push(&item.name);
+            self.items.push(item);
+            self.lines.push(None);
+            self.insert_row(self.items.len() - 1, &query);
+        }
+        let preselected = self.mode.filter(|_| self.preselecting).and_then(|source| {
+            (first..self.items.len()).find(|&index| {
+                !self.items[index].disabled && self.sources[source].preselects(&self.items[index])
+            })
+        });
+        if preselected.is_some() {
+            self.preselecting = false;
+        }
+        let row = match preselected.or(selected) {
+            Some(selected) => self
+                .rows
+                .iter()
+                .position(|row| row.as_match().is_some_and(|m| m.index == selected)),
+            None => (0..self.rows.len()).find(|&i| self.is_selectable(i)),
+        };
+        self.list_state.select(row);
+        self.record_results();
+        self.needs_redraw = true;
+    }
+
+    /// Inserts the item at `index` after the rows ranking at least as high, if it matches.
+    fn insert_row(&mut self, index: usize, query: &str) {
+        if query.is_empty() {
+            self.rows.push(Row::entry(index));
+            return;
+        }
+        let Some(score) = self.matcher().score(self.names.get(index), query) else {
+            return;
+        };
+        let m = Match { index, score };
+        let rank = self.rank(&m);
+        let position = self
+            .rows
+            .partition_point(|row| row.as_match().is_some_and(|other| self.rank(other) <= rank));
+        self.rows.insert(position, Row::Entry(m));
+    }
+
+    /// Rebuilds the items of the current mode, dropping all cached rows.
+    fn reload_items(&mut self) {
+        self.batches = self.mode.and_then(|source| self.sources[source].stream());
+        self.batches_started = Instant::now();
+        self.unfiltered = false;
+        self.items = match self.mode {
+            Some(_) if self.batches.is_some() => Vec::new(),
+            Some(source) => self.sources[source].items(),
+            None => self
+                .entries
+                .iter()
+                .map(|entry| {
+                    let mut item = Item::new(&entry.id, &entry.name, entry.icon.clone());
+                    item.comment = entry.comment.clone();
+                    item.path = Some(entry.path().into());
+                    item.running = self.config.show_running && self.processes.find(entry).is_some();
+                    item.badges =
+                        Badge::for_entry(entry, &self.config, &self.first_seen, &self.processes);
+                    item
+                })
+                .collect(),
+        };
+        self.names = Names::new(&self.items);
+        self.lines = self.items.iter().map(|_| None).collect();
+        self.cache.clear();
+    }
+
+    fn keyword(&self, mode: Option<usize>) -> String {
+        mode.map(|source| self.sources[source].keyword().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Detected queries are kept as the application query they were typed as. Modes
+    /// under `privacy.modes` are left empty.
+    fn save_mode_state(&mut self) {
+        let mode = self.mode.filter(|_| !self.detected);
+        let keyword = self.keyword(mode);
+        let state = if self.config.privacy.excludes_mode(&keyword) {
+            ModeState::default()
+        } else {
+            ModeState {
+                query: self.query().to_string(),
+                selected: self.selected_index().map(|i| self.items[i].id.clone()),
+            }
+        };
+        self.mode_states.set(&keyword, state);
+    }
+
+    /// Enters `mode` with the query and selection it was last left with.
+    fn restore_mode_state(&mut self, mode: Option<usize>) {
+        let state = self.mode_states.get(&self.keyword(mode));
+        self.plain_search = false;
+        self.input.set(match mode {
+            Some(_) => format!("{} {}", self.keyword(mode), state.query),
+            None => state.query,
+        });
+        self.update_filter();
+        self.select_item(state.selected.as_deref());
+    }
+
+    /// Selects the row of the item `id` if it's listed.
+    fn select_item(&mut self, id: Option<&str>) {
+        let selected = self.rows.iter().position(|row| {
+            row.as_match()
+                .is_some_and(|m| Some(self.items[m.index].id.as_str()) == id)
+        });
+        if selected.is_some() {
+            self.list_state.select(selected);
+        }
+    }
+
+    /// Cycles through applications and the sources, keeping each one's query.
+    fn switch_mode(&mut self, forward: bool) {
+        if self.config.dmenu.is_some() {
+            return;
+        }
+        self.save_mode_state();
+        let modes = self.sources.len() + 1;
+        let current = self.mode.map_or(0, |source| source + 1);
+        let next = if forward {
+            (current + 1) % modes
+        } else {
+            (current + modes - 1) % modes
+        };
+        self.restore_mode_state(next.checked_sub(1));
+    }
+
+    /// Switches to the source with `keyword`, keeping the current mode's query.
+    fn enter_mode(&mut self, keyword: &str) {
+        if self.config.dmenu.is_some() {
+            return;
+        }
+        let Some(source) = self.sources.iter().position(|s| s.keyword() == keyword) else {
+            return;
+        };
+        self.save_mode_state();
+        self.restore_mode_state(Some(source));
+    }
+
+    /// Switches to the source whose keyword and a space prefix the filter, or else to
+    /// the first source that detects the filter as its kind of query.
+    fn route(&mut self) {
+        if self.config.dmenu.is_some() {
+            return;
+        }
+        if self.input.text.is_empty() {
+            self.plain_search = false;
+        }
+        let keyword = self.sources.iter().position(|source| {
+            self.input
+                .text
+                .strip_prefix(source.keyword())
+                .is_some_and(|rest| rest.starts_with(' '))
+        });
+        let detected = keyword.is_none() && !self.plain_search;
+        let mode = keyword.or_else(|| {
+            self.sources
+                .iter()
+                .position(|source| source.detects(&self.input.text))
+                .filter(|_| detected)
+        });
+        self.detected = detected && mode.is_some();
+        if mode != self.mode {
+            self.mode = mode;
+            self.status = None;
+            self.reload_items();
+        }
+    }
+
+    /// The filter without the keyword of the current mode.
+    fn query(&self) -> &str {
+        match self.mode {
+            Some(_) if self.detected || self.config.dmenu.is_some() => &self.input.text,
+            Some(source) => self
+                .input
+                .text
+                .strip_prefix(self.sources[source].keyword())
+                .unwrap_or_default()
+                .trim_start(),
+            None => &self.input.text,
+        }
+    }
+
+    /// What the source of the current mode completes the selected item to.
+    fn completion(&self) -> Option<String> {
+        let source = self.mode?;
+        self.sources[source].complete(&self.items[self.selected_index()?])
+    }
+
+    /// Replaces the query with the completion of the selected item, keeping the keyword
+    /// it was typed after.
+    fn complete(&mut self) {
+        let Some(completion) = self.completion() else {
+            return;
+        };
+        self.input.set(match self.mode.filter(|_| !self.detected) {
+            Some(_) => format!("{} {}", self.keyword(self.mode), completion),
+            None => completion,
+        });
+        self.update_filter();
+    }
+
+    /// Searches applications for a filter a source detected, e.g. `/usr` or `2048`.
+    fn force_plain_search(&mut self) {
+        if self.detected {
+            self.plain_search = true;
+            self.update_filter();
+        }
+    }
+
+    fn selected_index(&self) -> Option<usize> {
+        self.list_state
+            .selected()
+            .and_then(|i| self.rows.get(i))
+            .and_then(Row::as_match)
+            .map(|m| m.index)
+    }
+
+    /// The selected item, or the selected answer of a source.
+    fn selected_item(&self) -> Option<Item> {
+        match self.list_state.selected().and_then(|i| self.rows.get(i)) {
+            Some(Row::Answer { item, .. }) => Some(item.clone()),
+            _ => self.selected_index().map(|i| self.items[i].clone()),
+        }
+    }
+
+    fn selected_entry(&self) -> Option<DesktopEntry> {
+        self.selected_index()
+            .filter(|_| self.mode.is_none())
+            .map(|i| self.entries[i].clone())
+    }
+
+    fn request_elevation(&mut self) {
+        if self.config.restricted {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let argv = match self.launch_argv(&entry, LaunchMode::Elevated) {
+            Ok(argv) => argv,
+            Err(status) => {
+                self.status = Some(status);
+                return;
+            }
+        };
+        let dialog = ConfirmDialog::new(
+            tr_with("Launch {name} elevated?", &[("name", &entry.name)]),
+            launch::join(&argv),
+            &self.config.confirm,
+        )
+        .with_countdown(Duration::from_secs(self.config.confirm.countdown));
+        self.modals.push(Modal::Confirm(Confirmation {
+            dialog,
+            action: PendingAction::Elevate(entry, argv),
+        }));
+    }
+
+    fn open_action_menu(&mut self) {
+        if self.config.restricted {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let mut actions = self.config.entry_actions(&entry);
+        for file in recent_files::for_entry(&entry, self.config.recent_files) {
+            let name = file.path.file_name().unwrap_or_default().to_string_lossy();
+            let path = file.path.to_string_lossy();
+            actions.push(DesktopAction {
+                name: format!("󰈔 {}", name),
+                exec: desktop_entry::exec_with_file(&entry.exec, &path),
+            });
+        }
+        if !actions.is_empty() {
+            self.modals
+                .push(Modal::Actions(ActionMenu::new(entry, actions)));
+        }
+    }
+
+    /// Lists the compositor's workspaces to launch the selected entry on.
+    fn open_workspace_menu(&mut self) {
+        if self.config.restricted {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let workspaces = match compositor::detect().map(|compositor| compositor.workspaces()) {
+            Some(Ok(workspaces)) => workspaces,
+            Some(Err(error)) => {
+                self.status = Some(tr_with(
+                    "listing workspaces failed: {error}",
+                    &[("error", &error.to_string())],
+                ));
+                return;
+            }
+            None => {
+                self.status = Some(tr("no compositor integration").to_string());
+                return;
+            }
+        };
+        let actions = workspaces
+            .into_iter()
+            .map(|workspace| DesktopAction {
+                name: workspace.name,
+                exec: entry.exec.clone(),
+            })
+            .collect();
+        self.modals
+            .push(Modal::Workspaces(ActionMenu::new(entry, actions)));
+    }
+
+    /// Edits the name and icon the selected entry is shown with.
+    fn open_entry_editor(&mut self) {
+        if self.config.restricted {
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        let current = overrides::load().remove(&entry.id).unwrap_or_default();
+        self.modals
+            .push(Modal::Editor(EntryEditor::new(&entry, current)));
+    }
+
+    fn save_override(&mut self, id: &str, value: Override) {
+        match overrides::save(id, value) {
+            Ok(()) => {
+                self.entries = scan_entries(&self.config);
+                self.reload_items();
+                self.update_filter();
+            }
+            Err(error) => {
+                self.status = Some(tr_with(
+                    "saving the override failed: {error}",
+                    &[("error", &error.to_string())],
+                ))
+            }
+        }
+    }
+
+    /// Shows the file behind the selected item in the file manager.
+    fn open_containing_directory(&mut self) {
+        if self.config.restricted {
+            return;
+        }
+        let Some(item) = self.selected_index().map(|i| &self.items[i]) else {
+            return;
+        };
+        let Some(dir) = item.path.as_ref().and_then(|path| path.parent()) else {
+            return;
+        };
+        let dir = dir.display().to_string();
+        match self.config.file_manager.clone() {
+            Some(template) => match launch::expand(&template, &[("path", dir.clone())]) {
+                Some(argv) => {
+                    self.execute(&dir, &dir, argv, false);
+                }
+                None => {
+                    self.status = Some(tr_with(
+                        "unbalanced quotes in {arguments}",
+                        &[("arguments", &template)],
+                    ))
+                }
+            },
+            None => match open::open(&dir) {
+                Ok(()) => self.close(),
+                Err(error) => {
+                    self.status = Some(tr_with(
+                        "opening {path} failed: {error}",
+                        &[("path", &dir), ("error", &error.to_string())],
+                    ))
+                }
+            },
+        }
+    }
+
+    fn select_entry(&mut self, mode: LaunchMode) {
+        if let Some(source) = self.mode {
+            let keyword = self.sources[source].keyword();
+            if let Some(config) = self.config.sources.get(keyword).cloned() {
+                if let Some(template) = config.activate {
+                    self.activate(source, &template, config.terminal);
+                    return;
+                }
+            }
+            self.request_default_action(source);
+            return;
+        }
+        if let Some(&Row::Answer { source, .. }) =
+            self.list_state.selected().and_then(|i| self.rows.get(i))
+        {
+            self.request_default_action(source);
+            return;
+        }
+        if let Some(Row::Action { entry, action }) =
+            self.list_state.selected().and_then(|i| self.rows.get(i))
+        {
+            let mut entry = self.entries[*entry].clone();
+            entry.exec = action.exec.clone();
+            self.launch(entry, mode);
+            return;
+        }
+        let Some(entry) = self.selected_entry() else {
+            return;
+        };
+        self.launch(entry, mode);
+    }
+
+    /// Lists the actions of the selected entry below it, returning whether it has any.
+    fn expand(&mut self) -> bool {
+        if self.config.restricted {
+            return false;
+        }
+        let Some((row, index)) = self
+            .list_state
+            .selected()
+            .zip(self.selected_index())
+            .filter(|_| self.mode.is_none())
+        else {
+            return false;
+        };
+        let actions = self.config.entry_actions(&self.entries[index]);
+        if actions.is_empty() {
+            return false;
+        }
+        if self.expanded == Some(index) {
+            return true;
+        }
+        let row = row - self.collapse_rows(row);
+        let rows = actions.into_iter().map(|action| Row::Action {
+            entry: index,
+            action,
+        });
+        self.rows.splice(row + 1..row + 1, rows);
+        self.expanded = Some(index);
+        self.list_state.select(Some(row + 1));
+        true
+    }
+
+    /// Hides the listed actions again and selects their entry, returning whether any
+    /// were listed.
+    fn collapse(&mut self) -> bool {
+        let first = self
+            .rows
+            .iter()
+            .position(|row| matches!(row, Row::Action { .. }));
+        self.collapse_rows(0);
+        if let Some(first) = first {
+            self.list_state.select(Some(first - 1));
+        }
+        first.is_some()
+    }
+
+    /// Removes the action rows, returning how many of them were above `row`.
+    fn collapse_rows(&mut self, row: usize) -> usize {
+        self.expanded = None;
+        let above = self.rows[..row]
+            .iter()
+            .filter(|row| matches!(row, Row::Action { .. }))
+            .count();
+        self.rows.retain(|row| !matches!(row, Row::Action { .. }));
+        above
+    }
+
+    /// Runs a configured `activate` template on the selected item of `source`.
+    fn activate(&mut self, source: usize, template: &str, terminal: bool) {
+        let Some(item) = self.selected_index().map(|i| &self.items[i]) else {
+            return;
+        };
+        let mut values = vec![("id", item.id.clone()), ("name", item.name.clone())];
+        values.extend(self.sources[source].placeholders(item));
+        let Some(argv) = launch::expand(template, &values) else {
+            self.status = Some(tr_with(
+                "unbalanced quotes in {arguments}",
+                &[("arguments", template)],
+            ));
+            return;
+        };
+        let id = format!("{}:{}", self.sources[source].keyword(), item.id);
+        let name = item.name.clone();
+        self.execute(&id, &name, argv, terminal);
+    }
+
+    /// Requests the action of `source` bound to Enter.
+    fn request_default_action(&mut self, source: usize) {
+        let action = self.sources[source]
+            .actions()
+            .into_iter()
+            .find(|action| action.key.is_none());
+        if let Some(action) = action {
+            self.request_source_action(source, action);
+        }
+    }
+
+    fn request_source_action(&mut self, source: usize, action: Action) {
+        let item = self.selected_item();
+        if item.is_none() && !action.bulk {
+            return;
+        }
+        if action.confirm {
+            let message = match &item {
+                Some(item) if !action.bulk => item.name.clone(),
+                _ => tr_with(
+                    "All {count} items",
+                    &[("count", &self.items.len().to_string())],
+                ),
+            };
+            let dialog = ConfirmDialog::new(
+                format!("{}?", capitalize(tr(action.name))),
+                message,
+                &self.config.confirm,
+            )
+            .with_countdown(Duration::from_secs(self.config.confirm.countdown));
+            self.modals.push(Modal::Confirm(Confirmation {
+                dialog,
+                action: PendingAction::Source(source, action, item),
+            }));
+        } else {
+            self.run_source_action(source, action, item);
+        }
+    }
+
+    fn run_source_action(&mut self, source: usize, action: Action, item: Option<Item>) {
+        let item = item.filter(|_| !action.bulk);
+        match self.sources[source].run(action.name, item.as_ref()) {
+            Ok(Outcome::Refresh) => {
+                self.status = None;
+                self.reload_items();
+                self.update_filter();
+            }
+            Ok(Outcome::Exit) => self.close(),
+            Ok(Outcome::Terminal(argv)) => {
+                let keyword = self.sources[source].keyword();
+                let (id, name) = match &item {
+                    Some(item) => (format!("{}:{}", keyword, item.id), item.name.clone()),
+                    None => (keyword.to_string(), keyword.to_string()),
+                };
+                self.execute(&id, &name, argv, true);
+            }
+            Err(error) => {
+                self.status = Some(tr_with(
+                    "{action} failed: {error}",
+                    &[("action", tr(action.name)), ("error", &error.to_string())],
+                ))
+            }
+        }
+    }
+
+    fn confirm(&mut self, confirmation: Confirmation) {
+        match confirmation.action {
+            PendingAction::Elevate(entry, argv) => self.run_entry(entry, argv),
+            PendingAction::Source(source, action, item) => {
+                self.run_source_action(source, action, item)
+            }
+        }
+    }
+
+    fn launch(&mut self, entry: DesktopEntry, mode: LaunchMode) {
+        if mode == LaunchMode::Normal && self.focus_running(&entry) {
+            if let Some(query) = self.recorded_query(&entry) {
+                self.record_launch(&entry, &query);
+            }
+            if !self.keeps_open() {
+                self.close();
+            }
+            return;
+        }
+        match self.launch_argv(&entry, mode) {
+            Ok(argv) => self.run_entry(entry, argv),
+            Err(status) => self.status = Some(status),
+        }
+    }
+
+    /// The query to record a launch of `entry` under, unless its launches go unrecorded.
+    fn recorded_query(&self, entry: &DesktopEntry) -> Option<String> {
+        (!self.incognito && !self.unrecorded && !self.config.privacy.excludes_entry(entry))
+            .then(|| self.input.text.clone())
+    }
+
+    fn record_launch(&mut self, entry: &DesktopEntry, query: &str) {
+        self.history.record(query);
+        self.launches.record(&entry.name);
+        if self.config.frecency {
+            self.frecency.record(&entry.id);
+        }
+    }
+
+    /// The argv launching `entry` in `mode` runs: its command through `$SHELL`, the
+    /// query's arguments for terminal apps, then the sandbox and elevation wrappers.
+    /// Fails with the status to show when a command line doesn't parse.
+    fn launch_argv(&self, entry: &DesktopEntry, mode: LaunchMode) -> Result<Vec<String>, String> {
+        let mut argv = self.build_argv(entry).ok_or_else(|| {
+            tr_with(
+                "unbalanced quotes in the command of {name}",
+                &[("name", &entry.name)],
+            )
+        })?;
+        if let Some(args) = self.query_args.as_deref().filter(|_| entry.terminal) {
+            let args = launch::split(args).ok_or_else(|| {
+                tr_with("unbalanced quotes in {arguments}", &[("arguments", args)])
+            })?;
+            argv.extend(args);
+        }
+        if let Some(rule) = self.config.sandbox_rule(entry) {
+            if mode != LaunchMode::Unsandboxed || self.config.restricted {
+                argv = rule.wrap(argv).ok_or_else(|| {
+                    tr_with(
+                        "unbalanced quotes in {arguments}",
+                        &[("arguments", &rule.command)],
+                    )
+                })?;
+            }
+        }
+        if mode == LaunchMode::Elevated {
+            let command = &self.config.elevate_command;
+            argv = launch::wrap(command, argv).ok_or_else(|| {
+                tr_with(
+                    "unbalanced quotes in {arguments}",
+                    &[("arguments", command)],
+                )
+            })?;
+        }
+        Ok(argv)
+    }
+
+    /// Runs `argv` for `entry`, on the workspace it is assigned to, and records the launch
+    /// once it succeeded.
+    fn run_entry(&mut self, entry: DesktopEntry, argv: Vec<String>) {
+        // Taken before launching, which closes the launcher and clears the query.
+        let query = self.recorded_query(&entry);
+        let workspace = self
+            .workspace
+            .clone()
+            .or_else(|| self.config.workspace_for(&entry).map(String::from))
+            .filter(|_| !entry.terminal);
+        let placement = workspace.and_then(|workspace| {
+            Placement::prepare(&entry, &workspace)
+                .inspect_err(|error| {
+                    log::info(&format!("placing {} failed: {}", entry.name, error))
+                })
+                .ok()
+        });
+        let launched = self.execute(&entry.id, &entry.name, argv, entry.terminal);
+        if let Some(query) = query.filter(|_| launched) {
+            self.record_launch(&entry, &query);
+        }
+        if let Some(placement) = placement.filter(|_| launched) {
+            if let Err(error) = placement.start() {
+                log::info(&format!("placing {} failed: {}", entry.name, error));
+            }
+        }
+    }
+
+    /// Raises the window of `entry` if it reuses windows and was running at the last scan.
+    fn focus_running(&self, entry: &DesktopEntry) -> bool {
+        if !self.config.reuses_window(entry) {
+            return false;
+        }
+        let Some(pid) = self.processes.find(entry) else {
+            return false;
+        };
+        match running::focus(self.config.focus_command.as_deref(), pid) {
+            Ok(()) => true,
+            Err(error) => {
+                log::info(&format!("focusing {} failed: {}", entry.name, error));
+                false
+            }
+        }
+    }
+
+    /// Runs `argv` in this terminal or detached from it between the launch hooks, then
+    /// exits unless a detached app was launched to keep the launcher open. Returns whether
+    /// it was launched.
+    fn execute(&mut self, id: &str, name: &str, argv: Vec<String>, terminal: bool) -> bool {
+        let values = [
+            ("id", id.to_string()),
+            ("name", name.to_string()),
+            ("cmd", launch::join(&argv)),
+        ];
+        // Hooks run through `sh`, which a restricted launcher never does.
+        let hooks = if self.config.restricted {
+            Hooks::default()
+        } else {
+            self.config.hooks.clone()
+        };
+        hooks::run(hooks.pre.as_deref(), &values);
+        // A daemon's terminal is never closed, it hides instead.
+        if !terminal && (self.keeps_open() || self.daemon.is_some()) {
+            let child = launch::spawn_detached(&argv);
+            self.audit(id, &argv, child.is_ok());
+            hooks::run(hooks.post.as_deref(), &values);
+            match &child {
+                Ok(()) => self.status = None,
+                Err(error) => self.status = Some(launch_failed(name, error)),
+            }
+            if child.is_ok() && !self.keeps_open() {
+                self.close();
+            }
+            child.is_ok()
+        } else if terminal {
+            ratatui::restore();
+            let child = launch::spawn_in_terminal(&argv);
+            self.audit(id, &argv, child.is_ok());
+            hooks::run(hooks.post.as_deref(), &values);
+            let launched = match child {
+                Ok(mut child) => {
+                    let _ = child.wait();
+                    true
+                }
+                Err(_) => false,
+            };
+            if self.daemon.is_some() {
+                self.take_back_terminal();
+            }
+            self.close();
+            launched
+        } else {
+            // Launched before closing the terminal, so an app failing right away leaves
+            // the launcher open to say why.
+            let child = launch::spawn_detached(&argv);
+            self.audit(id, &argv, child.is_ok());
+            hooks::run(hooks.post.as_deref(), &values);
+            if let Err(error) = child {
+                self.status = Some(launch_failed(name, &error));
+                return false;
+            }
+            let target = host_terminal::process_to_close(&ProcFs, std::process::id());
+            match fork() {
+                Ok(Fork::Child) => {
+                    thread::sleep(Duration::from_millis(100));
+                    ratatui::restore();
+                    if let Some(pid) = target {
+                        let _ = Command::new("kill")
+                            .args(["-9", &pid.to_string()])
                             .status()
                             .expect("unable to kill terminal process");
                     }
-                    Ok(Fork::Parent(_)) => {
-                        let _ = Command::new(&shell)
-                            .args(&["-c", format!("{} & disown", &entry.exec).as_str()])
-                            .exec();
-                    }
-                    Err(_) => panic!("fork failed"),
+                    std::process::exit(0);
                 }
+                Ok(Fork::Parent(_)) => self.should_exit = true,
+                Err(_) => panic!("fork failed"),
+            }
+            true
+        }
+    }
+
+    /// Sets the terminal up again after an app ran in it, for a daemon to stay in it.
+    fn take_back_terminal(&mut self) {
+        let _ = terminal::enable_raw_mode();
+        let _ = execute!(io::stdout(), EnterAlternateScreen);
+        self.needs_clear = true;
+    }
+
+    /// Whether launching leaves the launcher open instead of closing its terminal.
+    fn keeps_open(&self) -> bool {
+        self.config.keep_open || self.keep_open
+    }
+
+    /// In restricted mode the Exec line is run directly instead of through `$SHELL`.
+    fn build_argv(&self, entry: &DesktopEntry) -> Option<Vec<String>> {
+        let argv = entry.argv(&[])?;
+        if self.config.restricted || entry.terminal {
+            Some(argv)
+        } else {
+            let shell = env::var("SHELL").expect("unable to read $SHELL env");
+            Some(vec![shell, "-c".to_string(), launch::join(&argv)])
+        }
+    }
+
+    fn audit(&self, id: &str, argv: &[String], success: bool) {
+        if let Some(path) = &self.config.audit_log {
+            let _ = AuditRecord::new(id, argv, success).append_to(path);
+        }
+    }
+
+    /// Filters for an edit right away, or with long lists once typing paused, so keys
+    /// pressed in a burst are filtered for once. The mode follows every edit either way.
+    fn filter_edited(&mut self) {
+        self.route();
+        if self.items.len() < DEBOUNCE_ITEMS {
+            self.update_filter();
+        } else {
+            self.pending_filter = Some(Instant::now());
+        }
+    }
+
+    fn apply_filter_edit(&mut self) {
+        if self
+            .pending_filter
+            .is_some_and(|edited| edited.elapsed() >= FILTER_DEBOUNCE)
+        {
+            self.update_filter();
+            self.needs_redraw = true;
+        }
+    }
+
+    /// Ranks matching items by score, preferring more frecent and then shorter names among
+    /// equal scores.
+    fn update_filter(&mut self) {
+        let start = Instant::now();
+        self.pending_filter = None;
+        self.route();
+        let query = self.split_query_args();
+        if query.is_empty() && self.mode.is_none() {
+            let mut sections = Vec::new();
+            if self.config.show_recent {
+                let recent = applications::recent(&self.entries, self.launches.recent());
+                sections.push((tr("Recent"), recent));
             }
+            if self.config.show_recently_installed {
+                let days = self.config.new_badge_days;
+                let new = (0..self.entries.len())
+                    .filter(|&i| self.first_seen.is_new(&self.entries[i], days))
+                    .collect();
+                sections.push((tr("Recently installed"), new));
+            }
+            let mut all = (0..self.entries.len()).collect::<Vec<usize>>();
+            if self.config.sort == SortOrder::Relevance {
+                all.sort_by_cached_key(|&i| {
+                    Reverse((self.frecency.score(&self.entries[i].id) * 1000.0) as i64)
+                });
+            }
+            self.rows = applications::sections(all, sections);
+        } else if let Some(batches) = self
+            .mode
+            .and_then(|source| self.sources[source].stream_query(&query))
+        {
+            self.items.clear();
+            self.names.clear();
+            self.lines.clear();
+            self.cache.clear();
+            self.rows.clear();
+            self.batches = Some(batches);
+            self.batches_started = Instant::now();
+            self.unfiltered = true;
+        } else if let Some(items) = self
+            .mode
+            .and_then(|source| self.sources[source].evaluate(&query))
+        {
+            self.items = items;
+            self.names = Names::new(&self.items);
+            self.lines = self.items.iter().map(|_| None).collect();
+            self.cache.clear();
+            self.rows = (0..self.items.len()).map(Row::entry).collect();
+        } else if query.is_empty() {
+            self.rows = (0..self.items.len()).map(Row::entry).collect();
+        } else {
+            let matches = match self.cache.get(&query) {
+                Some(matches) => matches.to_vec(),
+                None => {
+                    let narrowed = self
+                        .cache
+                        .superset(&query)
+                        .filter(|_| self.matcher().narrows());
+                    let candidates = match narrowed {
+                        Some(matches) => matches.iter().map(|m| m.index).collect(),
+                        None => (0..self.items.len()).collect::<Vec<usize>>(),
+                    };
+                    let mut matches = Vec::new();
+                    for index in candidates {
+                        if let Some(score) = self.matcher().score(self.names.get(index), &query) {
+                            matches.push(Match { index, score });
+                        }
+                    }
+                    matches.sort_by_cached_key(|m| self.rank(m));
+                    self.cache.insert(&query, matches.clone());
+                    matches
+                }
+            };
+            self.rows = matches.into_iter().map(Row::Entry).collect();
+        }
+        if self.mode.is_none() && !query.is_empty() {
+            let query = self.input.text.clone();
+            let answers = (0..self.sources.len())
+                .filter_map(|source| {
+                    let item = self.sources[source].answer(&query)?;
+                    Some(Row::Answer { source, item })
+                })
+                .collect::<Vec<Row>>();
+            self.rows.splice(0..0, answers);
         }
+        self.expanded = None;
+        let first = (0..self.rows.len()).find(|&i| self.is_selectable(i));
+        self.list_state.select(first);
+        *self.list_state.offset_mut() = 0;
+        self.animation.reset();
+        self.metrics.match_time = start.elapsed();
+        self.record_results();
+    }
+
+    /// The current mode's matcher, or else the one configured in `[matcher]`.
+    fn matcher(&self) -> &dyn Matcher {
+        self.mode
+            .and_then(|source| self.sources[source].matcher())
+            .unwrap_or(&self.config.matcher)
     }
 
-    fn get_filtered_entries(&self) -> Vec<DesktopEntry> {
-        let mut filtered_entries = self
-            .entries
+    fn record_results(&mut self) {
+        let mode = match self.mode {
+            Some(source) => self.sources[source].keyword().to_string(),
+            None => "apps".to_string(),
+        };
+        let count = self.rows.iter().filter(|row| row.is_selectable()).count();
+        self.metrics.results.insert(mode, count);
+    }
+
+    /// The query to match applications with, without any arguments for a terminal app.
+    fn split_query_args(&mut self) -> String {
+        let query = self.query().to_string();
+        self.query_args = None;
+        if self.mode.is_some() || !self.config.pass_query_args {
+            return query;
+        }
+        let Some((name, args)) = query.split_once(char::is_whitespace) else {
+            return query;
+        };
+        let matches_whole = self
+            .items
+            .iter()
+            .any(|item| self.config.matcher.score(&item.name, &query).is_some());
+        if matches_whole {
+            return query;
+        }
+        self.query_args = Some(args.trim().to_string());
+        name.to_string()
+    }
+
+    /// Orders matches by score, then frecency and name length or, when sorting by name,
+    /// by their position in the alphabetical list.
+    fn rank(&self, m: &Match) -> (Reverse<i64>, Reverse<i64>, usize) {
+        if self.config.sort == SortOrder::Name {
+            return (Reverse(m.score.score), Reverse(0), m.index);
+        }
+        let item = &self.items[m.index];
+        let frecency = match self.mode {
+            None => (self.frecency.score(&item.id) * 1000.0) as i64,
+            Some(_) => 0,
+        };
+        (
+            Reverse(m.score.score),
+            Reverse(frecency),
+            item.name.chars().count(),
+        )
+    }
+
+    /// The glyph configured for the current mode, under `[sources.apps]` for applications.
+    fn gutter(&self) -> Option<Span<'static>> {
+        let keyword = self
+            .mode
+            .map_or("apps", |source| self.sources[source].keyword());
+        let config = self.config.sources.get(keyword)?;
+        if config.glyph.is_none() && config.accent.is_none() {
+            return None;
+        }
+        let glyph = config
+            .glyph
             .clone()
-            .into_iter()
-            .filter(|entry| {
-                entry
-                    .name
-                    .to_lowercase()
-                    .contains(&self.filter.to_lowercase())
-            })
-            .collect::<Vec<DesktopEntry>>();
-        filtered_entries.sort_by_key(|entry| entry.name.clone());
-        filtered_entries
+            .unwrap_or_else(|| theme::get().gutter.clone());
+        let style = Style::new().fg(config.accent.unwrap_or(Color::Reset));
+        Some(Span::styled(glyph, style))
+    }
+
+    /// Builds the rows for `range` of the filtered items, reusing cached rows whose
+    /// highlight did not change.
+    fn window_lines(&mut self, range: Range<usize>, width: usize) -> Vec<Line<'static>> {
+        let theme = theme::get();
+        let icons = self.config.show_icons && theme.icons;
+        let mut lines = Vec::with_capacity(range.len());
+        let gutter = self.gutter();
+        for row in &self.rows[range] {
+            let m = match row {
+                Row::Header(title) => {
+                    lines.push(Line::styled(format!(" {} ", title), theme.header));
+                    continue;
+                }
+                Row::Separator => {
+                    lines.push(Line::styled(format!(" {}", theme.separator), theme.dim));
+                    continue;
+                }
+                Row::Action { action, .. } => {
+                    let indent = if icons { "   " } else { " " };
+                    let mut line = Line::from(format!(
+                        "{}  {} {}",
+                        indent, theme.action_arrow, action.name
+                    ));
+                    if let Some(gutter) = &gutter {
+                        line.spans.insert(0, gutter.clone());
+                    }
+                    lines.push(line);
+                    continue;
+                }
+                Row::Answer { item, .. } => {
+                    let mut line = item.get_highlighted_name(&[], icons);
+                    if let Some(gutter) = &gutter {
+                        line.spans.insert(0, gutter.clone());
+                    }
+                    lines.push(line);
+                    continue;
+                }
+                Row::Entry(m) => m,
+            };
+            let positions = &m.score.positions;
+            let cached = self.lines[m.index]
+                .as_ref()
+                .filter(|cached| &cached.positions == positions);
+            let item = &self.items[m.index];
+            let mut line = match cached {
+                Some(cached) => cached.line.clone(),
+                None => {
+                    let line = item.get_highlighted_name(positions, icons);
+                    self.lines[m.index] = Some(CachedLine {
+                        positions: positions.clone(),
+                        line: line.clone(),
+                    });
+                    line
+                }
+            };
+            if let Some(gutter) = &gutter {
+                line.spans.insert(0, gutter.clone());
+            }
+            let mut trailing = Vec::new();
+            if let Some(detail) = &item.detail {
+                trailing.push(Span::styled(detail.clone(), theme.dim));
+            }
+            trailing.extend(item.badges.iter().map(Badge::span));
+            if !trailing.is_empty() {
+                let trailing_width =
+                    trailing.iter().map(Span::width).sum::<usize>() + trailing.len();
+                let padding = width.saturating_sub(line.width() + trailing_width);
+                line.push_span(" ".repeat(padding));
+                for span in trailing {
+                    line.push_span(" ");
+                    line.push_span(span);
+                }
+            }
+            lines.push(line);
+        }
+        lines
     }
 
     fn draw(&mut self, frame: &mut Frame) {
-        let index = self.cursor_index.clone();
+        let index = self.input.column() as u16;
+        let focus = self.focus;
+        let compact = self.screen.compact;
         frame.render_widget(self, frame.area());
-        frame.set_cursor_position(Position::new(index as u16 + 1, 1));
+        if focus == Focus::Input {
+            let position = if compact {
+                Position::new(index + 2, 0)
+            } else {
+                Position::new(index + 1, 1)
+            };
+            frame.set_cursor_position(position);
+        }
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if let Event::Key(key) = event::read()? {
-            if key.kind == KeyEventKind::Press {
+        match event::read()? {
+            // A hidden daemon whose terminal is still in front shows itself on any key.
+            Event::Key(key) if key.kind == KeyEventKind::Press && self.hidden => self.show(),
+            Event::Key(key) if key.kind == KeyEventKind::Press => {
+                self.needs_redraw = true;
+                if let Some(perf) = &mut self.perf {
+                    perf.key_pressed();
+                }
+                if self.modals.top().is_some() {
+                    self.handle_modal_key(key.code);
+                    return Ok(());
+                }
+                let edits = matches!(
+                    key.code,
+                    KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete
+                ) && !key
+                    .modifiers
+                    .intersects(KeyModifiers::CONTROL | KeyModifiers::ALT);
+                // Anything but typing acts on the list, which must be filtered by then.
+                if !edits && self.pending_filter.is_some() {
+                    self.update_filter();
+                }
+                if let Some(first) = self.digraph.take() {
+                    self.handle_digraph_key(first, key.code);
+                    return Ok(());
+                }
+                let expected = self
+                    .config
+                    .dmenu
+                    .as_ref()
+                    .and_then(|options| options.expected(&key));
+                if let Some(index) = expected.filter(|_| self.selected_index().is_some()) {
+                    dmenu::accept_with(index);
+                    self.select_entry(LaunchMode::Normal);
+                    return Ok(());
+                }
+                if let Some(binding) = keybindings::find(&self.config.keybindings, &key)
+                    .filter(|_| !self.config.restricted)
+                {
+                    self.run_binding(binding.clone());
+                    return Ok(());
+                }
+                let alt = key.modifiers.contains(KeyModifiers::ALT);
+                let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+                let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+                let strip_selection = self.strip_selection.take();
+                self.preselecting = false;
+                // Keys that only mean something in context come before the configured ones.
                 match key.code {
-                    KeyCode::Char(to_insert) => self.enter_char(to_insert),
-                    KeyCode::Backspace => self.delete_char(),
-                    KeyCode::Delete => self.right_delete_char(),
-                    KeyCode::Left => self.move_cursor_left(),
-                    KeyCode::Right => self.move_cursor_right(),
-                    KeyCode::Up | KeyCode::BackTab => self.select_previous(),
-                    KeyCode::Down | KeyCode::Tab => self.select_next(),
-                    KeyCode::Enter => self.select_entry(),
-                    KeyCode::Esc => self.should_exit = true,
-                    _ => {}
+                    KeyCode::Right if ctrl && self.shows_strip() => {
+                        let last = self.strip().len() - 1;
+                        self.strip_selection =
+                            Some(strip_selection.map_or(0, |i| (i + 1).min(last)));
+                    }
+                    KeyCode::Left if ctrl && self.shows_strip() => {
+                        self.strip_selection =
+                            Some(strip_selection.map_or(0, |i| i.saturating_sub(1)));
+                    }
+                    KeyCode::Char(c @ '1'..='5') if alt && self.shows_strip() => {
+                        self.launch_from_strip(c as usize - '1' as usize);
+                    }
+                    KeyCode::Enter if strip_selection.is_some() => {
+                        self.launch_from_strip(strip_selection.unwrap_or_default());
+                    }
+                    KeyCode::Char('p') if alt && self.detected => self.force_plain_search(),
+                    KeyCode::Tab if !shift && self.completion().is_some() => self.complete(),
+                    KeyCode::Char(c) if alt && self.source_has_key(c) => self.source_key(c),
+                    KeyCode::Up if shift && self.preview.is_shown() => self.preview.scroll(-1),
+                    KeyCode::Down if shift && self.preview.is_shown() => self.preview.scroll(1),
+                    code => match self.config.keys.action(&key) {
+                        Some(action) => self.run_key_action(action),
+                        None => match self.focus {
+                            Focus::Input => self.handle_input_key(code),
+                            Focus::List => self.handle_list_key(code),
+                        },
+                    },
                 }
             }
+            Event::Resize(_, _) => self.needs_redraw = true,
+            _ => {}
         }
         Ok(())
     }
 
-    fn move_cursor_left(&mut self) {
-        let cursor_moved_right = self.cursor_index.saturating_sub(1);
-        self.cursor_index = self.clamp_cursor(cursor_moved_right);
+    fn run_key_action(&mut self, action: KeyAction) {
+        match action {
+            KeyAction::SelectNext => self.select_next(),
+            KeyAction::SelectPrev => self.select_previous(),
+            KeyAction::SelectFirst => self.select_first(),
+            KeyAction::SelectLast => self.select_last(),
+            KeyAction::PageDown => self.select_page(true),
+            KeyAction::PageUp => self.select_page(false),
+            KeyAction::Launch => self.select_entry(LaunchMode::Normal),
+            KeyAction::LaunchKeepOpen => {
+                self.keep_open = self.mode.is_none();
+                self.select_entry(LaunchMode::Normal);
+                self.keep_open = false;
+            }
+            KeyAction::LaunchUnsandboxed => self.select_entry(LaunchMode::Unsandboxed),
+            KeyAction::Elevate => self.request_elevation(),
+            KeyAction::Quit => self.close(),
+            KeyAction::ClearFilter => self.clear_filter(),
+            KeyAction::CycleFocus => self.cycle_focus(),
+            KeyAction::NextMode => self.switch_mode(true),
+            KeyAction::PrevMode => self.switch_mode(false),
+            KeyAction::ActionMenu => self.open_action_menu(),
+            KeyAction::EditEntry => self.open_entry_editor(),
+            KeyAction::WorkspaceMenu => self.open_workspace_menu(),
+            KeyAction::OpenDirectory => self.open_containing_directory(),
+            KeyAction::Settings => self.open_settings(),
+            KeyAction::Hud => self.show_hud = !self.show_hud,
+            KeyAction::Incognito => self.incognito = !self.incognito,
+            KeyAction::SshMode => self.enter_mode("ssh"),
+            KeyAction::Digraph => {
+                self.focus = Focus::Input;
+                self.digraph = Some(None);
+            }
+        }
     }
 
-    fn move_cursor_right(&mut self) {
-        let cursor_moved_left = self.cursor_index.saturating_add(1);
-        self.cursor_index = self.clamp_cursor(cursor_moved_left);
+    /// Inserts the character composed by the two typed after Ctrl+K, or the second one
+    /// if they aren't a digraph. Any other key cancels.
+    fn handle_digraph_key(&mut self, first: Option<char>, key: KeyCode) {
+        let KeyCode::Char(c) = key else {
+            return;
+        };
+        match first {
+            None => self.digraph = Some(Some(c)),
+            Some(first) => self.enter_char(digraph::compose(first, c).unwrap_or(c)),
+        }
     }
 
-    fn enter_char(&mut self, new_char: char) {
-        let index = self.byte_index();
-        self.filter.insert(index, new_char);
-        self.move_cursor_right();
+    /// Clears the query, staying in the mode whose keyword it was typed after.
+    fn clear_filter(&mut self) {
+        let keep = self.input.text.len() - self.query().len();
+        self.input.text.truncate(keep);
+        self.input.move_to_end();
+        self.filter_edited();
     }
 
-    fn byte_index(&self) -> usize {
-        self.filter
-            .char_indices()
-            .map(|(i, _)| i)
-            .nth(self.cursor_index)
-            .unwrap_or(self.filter.len())
+    /// Whether the source of the current mode has an action bound to Alt+`key`.
+    fn source_has_key(&self, key: char) -> bool {
+        self.mode.is_some_and(|source| {
+            self.sources[source]
+                .actions()
+                .iter()
+                .any(|action| action.key == Some(key))
+        })
     }
 
-    fn delete_char(&mut self) {
-        let is_cursor_leftmost = self.cursor_index == 0;
-        if is_cursor_leftmost {
+    /// Whether the recently launched strip is shown, which takes app mode and a history.
+    fn shows_strip(&self) -> bool {
+        self.config.show_recent_strip && self.mode.is_none() && !self.strip().is_empty()
+    }
+
+    /// The entries in the recently launched strip, newest first.
+    fn strip(&self) -> Vec<usize> {
+        applications::recent(&self.entries, self.launches.recent())
+    }
+
+    fn launch_from_strip(&mut self, position: usize) {
+        if let Some(&i) = self.strip().get(position) {
+            self.launch(self.entries[i].clone(), LaunchMode::Normal);
+        }
+    }
+
+    /// Draws the recently launched apps as ` 1 󰈹 Firefox ` chips in a single row.
+    fn render_strip(&self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::get();
+        let mut spans = Vec::new();
+        for (position, &i) in self.strip().iter().enumerate() {
+            let entry = &self.entries[i];
+            let name = entry
+                .name
+                .chars()
+                .take(STRIP_NAME_LENGTH)
+                .collect::<String>();
+            let chip = if theme.icons {
+                format!(" {} {} {} ", position + 1, entry.icon, name)
+            } else {
+                format!(" {} {} ", position + 1, name)
+            };
+            spans.push(if self.strip_selection == Some(position) {
+                Span::styled(chip, theme.selected)
+            } else {
+                Span::styled(chip, theme.dim)
+            });
+            spans.push(Span::raw(" "));
+        }
+        Line::from(spans).render(area, buf);
+    }
+
+    /// Runs a bound command on the query and the selection, reporting failures in the
+    /// status line.
+    fn run_binding(&mut self, binding: KeyBinding) {
+        let mut values = vec![("query", self.query().to_string())];
+        let mut argv = None;
+        if let Some(i) = self.selected_index() {
+            let item = &self.items[i];
+            values.push(("id", item.id.clone()));
+            values.push(("name", item.name.clone()));
+            match self.mode {
+                Some(source) => values.extend(self.sources[source].placeholders(item)),
+                None => argv = Some(self.entries[i].argv(&[]).unwrap_or_default()),
+            }
+        }
+        // `{exec}` is a command line rather than a value, its arguments quoted one by one.
+        let command = launch::fill_with(binding.command(), |name| match (name, &argv) {
+            ("exec", Some(argv)) => Some(launch::join(argv)),
+            _ => values
+                .iter()
+                .find(|(key, _)| *key == name)
+                .map(|(_, value)| launch::quote(value)),
+        });
+        // Detached without waiting, so slow commands and ones leaving a daemon behind
+        // like `wl-copy` don't block the launcher.
+        match launch::spawn_background(&["sh".to_string(), "-c".to_string(), command.clone()]) {
+            Ok(()) => {
+                self.status = None;
+                if binding.close() {
+                    self.close();
+                }
+            }
+            Err(error) => {
+                self.status = Some(tr_with(
+                    "`{command}` failed: {error}",
+                    &[("command", &command), ("error", &error.to_string())],
+                ))
+            }
+        }
+    }
+
+    fn cycle_focus(&mut self) {
+        self.focus = match self.focus {
+            Focus::Input => Focus::List,
+            Focus::List => Focus::Input,
+        };
+    }
+
+    fn handle_input_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char(to_insert) => self.enter_char(to_insert),
+            KeyCode::Backspace => self.delete_char(),
+            KeyCode::Delete => self.right_delete_char(),
+            KeyCode::Left if self.collapse() => {}
+            KeyCode::Left => self.input.move_left(),
+            KeyCode::Right if self.input.at_end() => {
+                if !self.accept_ghost() {
+                    self.expand();
+                }
+            }
+            KeyCode::Right => self.input.move_right(),
+            _ => self.needs_redraw = false,
+        }
+    }
+
+    /// Vim-style navigation; any other typing goes back to the input.
+    fn handle_list_key(&mut self, key: KeyCode) {
+        match key {
+            KeyCode::Char('j') => self.select_next(),
+            KeyCode::Char('k') => self.select_previous(),
+            KeyCode::Char('g') | KeyCode::Home => self.select_first(),
+            KeyCode::Char('G') | KeyCode::End => self.select_last(),
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.expand();
+            }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.collapse();
+            }
+            KeyCode::Char(_) | KeyCode::Backspace | KeyCode::Delete => {
+                self.focus = Focus::Input;
+                self.handle_input_key(key);
+            }
+            _ => self.needs_redraw = false,
+        }
+    }
+
+    fn handle_modal_key(&mut self, key: KeyCode) {
+        match self.modals.top_mut() {
+            Some(Modal::Confirm(confirmation)) => match confirmation.dialog.handle_key(key) {
+                Answer::Confirmed => {
+                    if let Some(Modal::Confirm(confirmation)) = self.modals.pop() {
+                        self.confirm(confirmation);
+                    }
+                }
+                Answer::Cancelled => {
+                    self.modals.pop();
+                }
+                Answer::Pending => {}
+            },
+            Some(Modal::Actions(menu)) => match menu.handle_key(key) {
+                MenuAnswer::Chosen(action) => {
+                    if let Some(Modal::Actions(menu)) = self.modals.pop() {
+                        self.unrecorded = self.config.privacy.excludes_action(&action);
+                        let mut entry = menu.entry;
+                        entry.exec = action.exec;
+                        self.launch(entry, LaunchMode::Normal);
+                        self.unrecorded = false;
+                    }
+                }
+                MenuAnswer::Cancelled => {
+                    self.modals.pop();
+                }
+                MenuAnswer::Pending => {}
+            },
+            Some(Modal::Workspaces(menu)) => match menu.handle_key(key) {
+                MenuAnswer::Chosen(action) => {
+                    if let Some(Modal::Workspaces(menu)) = self.modals.pop() {
+                        self.workspace = Some(action.name);
+                        self.launch(menu.entry, LaunchMode::Normal);
+                        self.workspace = None;
+                    }
+                }
+                MenuAnswer::Cancelled => {
+                    self.modals.pop();
+                }
+                MenuAnswer::Pending => {}
+            },
+            Some(Modal::Editor(editor)) => match editor.handle_key(key) {
+                EditorAnswer::Saved(value) => {
+                    if let Some(Modal::Editor(editor)) = self.modals.pop() {
+                        self.save_override(&editor.id, value);
+                    }
+                }
+                EditorAnswer::Cancelled => {
+                    self.modals.pop();
+                }
+                EditorAnswer::Pending => {}
+            },
+            Some(Modal::Settings(menu)) => match menu.handle_key(key) {
+                SettingsAnswer::Changed(key, value) => self.change_setting(key, &value),
+                SettingsAnswer::Closed => {
+                    self.modals.pop();
+                }
+                SettingsAnswer::Pending => {}
+            },
+            None => {}
+        }
+    }
+
+    fn open_settings(&mut self) {
+        if !self.config.restricted {
+            self.modals
+                .push(Modal::Settings(SettingsMenu::new(&self.config)));
+        }
+    }
+
+    /// Writes a changed setting to the config file and applies the file as it is now.
+    fn change_setting(&mut self, key: &str, value: &str) {
+        let written = self
+            .config_path
+            .as_deref()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))
+            .and_then(|path| settings::write(path, key, value));
+        match written.and_then(|()| self.reload_config()) {
+            Ok(()) => {
+                self.reload_items();
+                self.update_filter();
+            }
+            Err(error) => {
+                self.status = Some(tr_with(
+                    "saving {setting} failed: {error}",
+                    &[("setting", key), ("error", &error.to_string())],
+                ))
+            }
+        }
+    }
+
+    fn source_key(&mut self, key: char) {
+        let Some(source) = self.mode else {
             return;
+        };
+        let action = self.sources[source]
+            .actions()
+            .into_iter()
+            .find(|action| action.key == Some(key));
+        if let Some(action) = action {
+            self.request_source_action(source, action);
+        }
+    }
+
+    /// Completion for the filter from the query history.
+    fn ghost_text(&self) -> Option<String> {
+        if self.mode.is_some() {
+            return None;
+        }
+        let suggestion = self.history.suggest(&self.input.text)?;
+        suggestion
+            .get(self.input.text.len()..)
+            .filter(|_| !self.input.text.is_empty())
+            .map(String::from)
+    }
+
+    fn accept_ghost(&mut self) -> bool {
+        let Some(ghost) = self.ghost_text() else {
+            return false;
+        };
+        self.input.text.push_str(&ghost);
+        self.input.move_to_end();
+        self.filter_edited();
+        true
+    }
+
+    fn enter_char(&mut self, new_char: char) {
+        self.input.insert(new_char);
+        self.filter_edited();
+    }
+
+    fn delete_char(&mut self) {
+        if self.input.delete_left() {
+            self.filter_edited();
         }
-        let current_index = self.cursor_index;
-        let from_left_to_current_index = current_index - 1;
-        let before_char_to_delete = self.filter.chars().take(from_left_to_current_index);
-        let after_char_to_delete = self.filter.chars().skip(current_index);
-        self.filter = before_char_to_delete.chain(after_char_to_delete).collect();
-        self.move_cursor_left();
     }
 
     fn right_delete_char(&mut self) {
-        let is_cursor_rightmost = self.cursor_index == self.filter.len();
-        if is_cursor_rightmost {
-            return;
+        if self.input.delete_right() {
+            self.filter_edited();
         }
-        let cursor_index = self.cursor_index;
-        self.filter.remove(cursor_index);
     }
 
-    fn clamp_cursor(&self, new_cursor_pos: usize) -> usize {
-        new_cursor_pos.clamp(0, self.filter.chars().count())
+    /// Whether the row at `i` is an entry whose item isn't disabled.
+    fn is_selectable(&self, i: usize) -> bool {
+        match &self.rows[i] {
+            Row::Entry(m) => !self.items[m.index].disabled,
+            row => row.is_selectable(),
+        }
     }
 
     fn select_previous(&mut self) {
-        self.list_state.select_previous();
+        let current = self.list_state.selected().unwrap_or(0);
+        if let Some(i) = (0..current).rev().find(|&i| self.is_selectable(i)) {
+            self.list_state.select(Some(i));
+        } else if self.config.wrap_selection {
+            self.select_last();
+        }
+    }
+
+    fn select_first(&mut self) {
+        let first = (0..self.rows.len()).find(|&i| self.is_selectable(i));
+        self.list_state.select(first);
+    }
+
+    fn select_last(&mut self) {
+        let last = (0..self.rows.len()).rfind(|&i| self.is_selectable(i));
+        self.list_state.select(last);
     }
 
     fn select_next(&mut self) {
-        self.list_state.select_next();
+        let current = self.list_state.selected().unwrap_or(0);
+        if let Some(i) = (current + 1..self.rows.len()).find(|&i| self.is_selectable(i)) {
+            self.list_state.select(Some(i));
+        } else if self.config.wrap_selection {
+            self.select_first();
+        }
     }
 
-    fn get_desktop_entries() -> Vec<DesktopEntry> {
-        let mut apps = Vec::new();
-        let home = env::var("HOME").expect("unable to read $HOME env");
-        let dirs = vec![
-            "/usr/share/applications/".to_string(),
-            format!("{}/.local/share/applications/", home),
-        ];
-        for dir in dirs {
-            let path = Path::new(&dir);
-            if path.exists() && path.is_dir() {
-                for entry in fs::read_dir(path).expect("unable to read target directory") {
-                    let entry = entry.expect("unable to read entry");
-                    let path = entry.path();
-                    if path.is_file()
-                        && path.extension().and_then(|s| s.to_str()) == Some("desktop")
-                    {
-                        match DesktopEntry::from_file(path.to_str().unwrap()) {
-                            Some(app) => apps.push(app),
-                            None => continue,
-                        }
-                    }
-                }
-            }
+    /// Moves the selection a list height down, or up unless `forward`, without wrapping.
+    fn select_page(&mut self, forward: bool) {
+        let Some(last) = self.rows.len().checked_sub(1) else {
+            return;
+        };
+        let current = self.list_state.selected().unwrap_or(0);
+        let page = self.page_height.max(1);
+        let row = if forward {
+            let target = (current + page).min(last);
+            (target..=last)
+                .find(|&i| self.is_selectable(i))
+                .or_else(|| (current..target).rev().find(|&i| self.is_selectable(i)))
+        } else {
+            let target = current.saturating_sub(page);
+            (0..=target)
+                .rev()
+                .find(|&i| self.is_selectable(i))
+                .or_else(|| (target + 1..=current).find(|&i| self.is_selectable(i)))
+        };
+        if row.is_some() {
+            self.list_state.select(row);
         }
-        apps
     }
 }
 
+/// The desktop entries that can be launched with `config`, sorted by name.
+fn scan_entries(config: &Config) -> Vec<DesktopEntry> {
+    let mut entries = applications::scan(&applications::dirs());
+    if config.restricted {
+        entries.retain(|entry| config.allowed_entries.contains(&entry.id));
+    }
+    overrides::apply(&mut entries);
+    entries.sort_by(|a, b| a.name.cmp(&b.name));
+    entries
+}
+
+/// Dims the border of widgets without focus. Compact blocks only draw a top rule.
+fn focus_block(focused: bool, compact: bool) -> Block<'static> {
+    let theme = theme::get();
+    let block = if compact {
+        Block::new()
+            .borders(Borders::TOP)
+            .border_set(theme.border_set)
+    } else {
+        theme.block()
+    };
+    if focused {
+        block.border_style(theme.border)
+    } else {
+        block.border_style(theme.border_unfocused)
+    }
+}
+
+fn launch_failed(name: &str, error: &io::Error) -> String {
+    tr_with(
+        "launching {name} failed: {error}",
+        &[("name", name), ("error", &error.to_string())],
+    )
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
 impl Widget for &mut App {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let [filter_area, list_area] =
-            Layout::vertical([Constraint::Length(3), Constraint::Min(1)]).areas(area);
-        let [_, scrollbar_area] = Layout::horizontal([Constraint::Min(1), Constraint::Max(1)])
-            .margin(1)
-            .areas(list_area);
-        let input = Paragraph::new(self.filter.clone()).block(Block::bordered().title("Filter"));
-
-        let mut highlighted_and_filtered_entries = Vec::new();
-        let filtered_entries = self.get_filtered_entries();
-        for entry in &filtered_entries {
-            let highlighted_name = entry.get_highlighted_name(self.filter.clone());
-            highlighted_and_filtered_entries.push(highlighted_name);
-        }
-
-        let list = List::new(highlighted_and_filtered_entries)
-            .block(Block::bordered().title("Apps"))
-            .style(Style::new().fg(Color::White))
-            .highlight_style(
-                Style::new()
-                    .fg(Color::Black)
-                    .bg(Color::White)
-                    .not_reversed(),
-            )
-            .direction(ListDirection::TopToBottom);
+        let theme = theme::get();
+        let compact = self.screen.compact;
+        let list_height = match self.screen.max_results {
+            Some(max) => Constraint::Max(max as u16 + 2),
+            None => Constraint::Min(1),
+        };
+        let strip_height = u16::from(self.shows_strip());
+        let [filter_area, strip_area, results_area, _] = Layout::vertical([
+            Constraint::Length(if compact { 1 } else { 3 }),
+            Constraint::Length(strip_height),
+            list_height,
+            Constraint::Min(0),
+        ])
+        .areas(area);
+        if strip_height > 0 {
+            self.render_strip(strip_area, buf);
+        }
+        let [list_area, preview_area] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Fill(u16::from(self.preview.is_shown())),
+        ])
+        .areas(results_area);
+        if self.preview.is_shown() {
+            let block = focus_block(false, compact).title(tr("preview"));
+            self.preview.render(block, preview_area, buf);
+        }
+        let title = match self.mode {
+            Some(source) => tr(self.sources[source].title()),
+            None => self.config.entries_label.as_str(),
+        };
+        let title = match self.loading_since() {
+            Some(_) if self.config.reduced_motion => format!("{} ({})", title, tr("loading")),
+            Some(since) => format!("{} {}", title, animation::spinner(since.elapsed())),
+            None => title.to_string(),
+        };
+        let mut block = focus_block(self.focus == Focus::List, compact).title(title);
+        if let Some(status) = &self.status {
+            block = block.title_bottom(Line::styled(status.clone(), theme.error));
+        } else if let Some(args) = &self.query_args {
+            let hint = tr_with(
+                "arguments for terminal apps: {arguments}",
+                &[("arguments", args)],
+            );
+            block = block.title_bottom(Line::styled(hint, theme.dim));
+        } else if self.mode.is_none() && self.scan.is_some() {
+            block = block.title_bottom(Line::styled(tr("loading applications"), theme.dim));
+        } else if let Some(source) = self.mode {
+            let mut hints = self.sources[source]
+                .actions()
+                .iter()
+                .map(|action| match action.key {
+                    Some(key) => format!("Alt+{} {}", key.to_ascii_uppercase(), tr(action.name)),
+                    None => format!("Enter {}", tr(action.name)),
+                })
+                .collect::<Vec<String>>();
+            if self.detected {
+                hints.push(format!("Alt+P {}", tr("search apps")));
+            }
+            block = block.title_bottom(Line::styled(hints.join(&theme.hint_separator), theme.dim));
+        }
+        let inner_area = block.inner(list_area);
+        let [_, scrollbar_area] =
+            Layout::horizontal([Constraint::Min(1), Constraint::Max(1)]).areas(inner_area);
+        // A pending digraph shows at the cursor, as `?` until its first character is typed.
+        let mut input_line = match self.digraph {
+            Some(first) => {
+                let (before, after) = self.input.text.split_at(self.input.byte_index());
+                Line::from(vec![
+                    Span::raw(before.to_string()),
+                    Span::styled(first.unwrap_or('?').to_string(), theme.placeholder),
+                    Span::raw(after.to_string()),
+                ])
+            }
+            None => Line::from(self.input.text.clone()),
+        };
+        if compact {
+            input_line
+                .spans
+                .insert(0, Span::styled(theme.prompt.clone(), theme.dim));
+        }
+        if self.input.at_end() && self.digraph.is_none() {
+            if let Some(ghost) = self.ghost_text() {
+                input_line.push_span(Span::styled(ghost, theme.placeholder));
+            }
+        }
+        let mut input_block = if compact {
+            Block::new()
+        } else {
+            focus_block(self.focus == Focus::Input, false).title(self.config.filter_label.clone())
+        };
+        if let Some(source) = self.mode.filter(|_| self.detected && !compact) {
+            let indicator = format!(" {} ", tr(self.sources[source].title()));
+            input_block = input_block.title(Line::styled(indicator, theme.accent).right_aligned());
+        }
+        if self.incognito && !compact {
+            let indicator = format!(" {} ", tr("incognito"));
+            input_block = input_block.title(Line::styled(indicator, theme.dim).right_aligned());
+        }
+        let input = Paragraph::new(input_line).block(input_block);
 
-        if let None = self.list_state.selected() {
-            self.list_state.select_first();
+        let selected = self
+            .list_state
+            .selected()
+            .unwrap_or(0)
+            .min(self.rows.len().saturating_sub(1));
+        let description = self
+            .rows
+            .get(selected)
+            .and_then(Row::as_match)
+            .and_then(|m| self.items[m.index].comment.clone())
+            .filter(|_| self.config.show_description);
+        let extra_rows = usize::from(description.is_some());
+        let height = (inner_area.height as usize)
+            .saturating_sub(extra_rows)
+            .max(1);
+        self.page_height = height;
+        let mut offset = self.list_state.offset().min(selected);
+        if selected >= offset + height {
+            offset = selected + 1 - height;
         }
+        self.list_state.select(Some(selected));
+        *self.list_state.offset_mut() = offset;
+
+        let (shown, highlight) = if self.config.animates() {
+            let shown = self.animation.scroll(offset, height);
+            let fade = self.animation.highlight(selected);
+            match theme.fade {
+                true => (shown, theme.selected.bg(fade)),
+                false => (shown, theme.selected),
+            }
+        } else {
+            (offset, theme.selected)
+        };
+
+        let start = offset.min(shown).saturating_sub(RENDER_MARGIN);
+        let end = (offset.max(shown) + height + RENDER_MARGIN).min(self.rows.len());
+        let items = self
+            .window_lines(start..end, inner_area.width.saturating_sub(1) as usize)
+            .into_iter()
+            .enumerate()
+            .map(|(i, line)| match &description {
+                Some(description) if i == selected - start => ListItem::new(Text::from(vec![
+                    line,
+                    Line::styled(format!("    {}", description), theme.dim),
+                ])),
+                _ => ListItem::new(line),
+            })
+            .collect::<Vec<ListItem>>();
+        // Left unselected while scrolled out of view, as the list would jump to it.
+        let visible = (shown..shown + height).contains(&selected);
+        let mut window_state = ListState::default()
+            .with_selected(visible.then(|| selected - start))
+            .with_offset(shown - start);
+
+        let list = List::new(items)
+            .block(block)
+            .style(theme.text)
+            .highlight_style(highlight)
+            .highlight_symbol(&theme.selector)
+            .direction(ListDirection::TopToBottom);
 
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
             .begin_symbol(None)
             .end_symbol(None)
             .track_symbol(None)
-            .thumb_symbol("┃");
+            .thumb_symbol(&theme.scrollbar_thumb);
 
-        let scrollable_range = (filtered_entries.len() as i16 - area.height as i16 + 3).max(0);
+        let scrollable_range = self.rows.len().saturating_sub(height);
 
         self.scrollbar_state = self
             .scrollbar_state
-            .content_length(scrollable_range as usize)
+            .content_length(scrollable_range)
             .position(self.list_state.offset());
 
         Widget::render(input, filter_area, buf);
-        StatefulWidget::render(list, list_area, buf, &mut self.list_state);
-        StatefulWidget::render(scrollbar, scrollbar_area, buf, &mut self.scrollbar_state);
+        StatefulWidget::render(list, list_area, buf, &mut window_state);
+        if self.screen.scrollbar {
+            StatefulWidget::render(scrollbar, scrollbar_area, buf, &mut self.scrollbar_state);
+        }
+
+        if self.show_hud {
+            self.metrics.measure_index(&self.entries, &self.items);
+            self.metrics.render(results_area, buf);
+        }
+        self.modals.render(results_area, buf);
+        theme::strip_colors(area, buf);
     }
 }
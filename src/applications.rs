@@ -0,0 +1,187 @@
+use std::{
+    collections::HashSet,
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    desktop_entry::DesktopEntry, entry_cache::EntryCache, locale::tr, log, paths, row::Row,
+};
+
+const SECTION_LIMIT: usize = 5;
+
+/// Entries sent at once by `scan_in_background`.
+const BATCH_SIZE: usize = 64;
+
+/// The `applications` directories by precedence: the user's own in `$XDG_DATA_HOME`,
+/// those of `$XDG_DATA_DIRS`, then flatpak's exports unless listed already.
+pub fn dirs() -> Vec<PathBuf> {
+    let mut dirs = paths::data_home().into_iter().collect::<Vec<PathBuf>>();
+    dirs.extend(paths::data_dirs());
+    let flatpak = [
+        paths::data_home().map(|dir| dir.join("flatpak/exports/share")),
+        Some(PathBuf::from("/var/lib/flatpak/exports/share")),
+    ];
+    for dir in flatpak.into_iter().flatten() {
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+    dirs.into_iter()
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+/// The entries in `dirs`, where a desktop file shadows those with the same ID in later
+/// directories, even when it's hidden.
+pub fn scan(dirs: &[PathBuf]) -> Vec<DesktopEntry> {
+    let mut apps = Vec::new();
+    scan_batches(dirs, |batch| apps.extend(batch));
+    apps
+}
+
+/// Scans `dirs` like `scan` on a background thread, sending the entries in batches as
+/// they are parsed. The scan is complete once the sender is dropped.
+pub fn scan_in_background(dirs: Vec<PathBuf>) -> Receiver<Vec<DesktopEntry>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        scan_batches(&dirs, |batch| {
+            let _ = sender.send(batch);
+        })
+    });
+    receiver
+}
+
+fn scan_batches(dirs: &[PathBuf], mut send: impl FnMut(Vec<DesktopEntry>)) {
+    let start = Instant::now();
+    let mut cache = EntryCache::load();
+    let mut seen = HashSet::new();
+    let mut apps = Vec::new();
+    let mut count = 0;
+    for dir in dirs {
+        for path in desktop_files(dir) {
+            let Some(id) = entry_id(dir, &path) else {
+                continue;
+            };
+            if !seen.insert(id.clone()) {
+                continue;
+            }
+            if let Some(mut app) = cache.entry(&path) {
+                app.id = id;
+                apps.push(app);
+                count += 1;
+            }
+            if apps.len() == BATCH_SIZE {
+                send(mem::take(&mut apps));
+            }
+        }
+    }
+    if !apps.is_empty() {
+        send(apps);
+    }
+    log::info(&format!(
+        "scanned {} desktop entries in {:.2?}, {} parsed",
+        count,
+        start.elapsed(),
+        cache.parsed
+    ));
+    cache.save();
+}
+
+/// The `.desktop` files in `dir` and its subdirectories.
+pub fn desktop_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            files.extend(desktop_files(&path));
+        } else if path.extension().and_then(|s| s.to_str()) == Some("desktop") {
+            files.push(path);
+        }
+    }
+    files
+}
+
+/// The desktop file ID of `path`, its path within `dir` with slashes turned into dashes,
+/// e.g. `kde4-kate.desktop`.
+fn entry_id(dir: &Path, path: &Path) -> Option<String> {
+    let relative = path.strip_prefix(dir).ok()?;
+    Some(relative.to_string_lossy().replace('/', "-"))
+}
+
+/// The desktop file ID of `path` within the first of `dirs` it is in.
+pub fn id_in(dirs: &[PathBuf], path: &Path) -> Option<String> {
+    dirs.iter().find_map(|dir| entry_id(dir, path))
+}
+
+/// The IDs of the desktop files in `dirs` that a file in `dir`, which may be gone, would
+/// share its ID with.
+pub fn ids_in_dir(dirs: &[PathBuf], dir: &Path) -> Vec<String> {
+    let prefix = match id_in(dirs, dir) {
+        Some(id) if !id.is_empty() => id + "-",
+        _ => String::new(),
+    };
+    dirs.iter()
+        .flat_map(|dir| {
+            desktop_files(dir)
+                .into_iter()
+                .filter_map(|path| entry_id(dir, &path))
+        })
+        .filter(|id| id.starts_with(&prefix))
+        .collect()
+}
+
+/// The entry with the desktop file ID `id` as `scan` would find it: from the file with
+/// that ID in the first of `dirs` having one, unless that file is hidden or invalid.
+pub fn resolve(dirs: &[PathBuf], id: &str) -> Option<DesktopEntry> {
+    let path = dirs.iter().find_map(|dir| file_with_id(dir, id))?;
+    let (mut entry, visibility) = path.to_str().and_then(DesktopEntry::parse)?;
+    entry.id = id.to_string();
+    visibility.is_shown().then_some(entry)
+}
+
+/// The file in `dir` or its subdirectories whose desktop file ID is `id`, where each
+/// dash may stand for a slash.
+fn file_with_id(dir: &Path, id: &str) -> Option<PathBuf> {
+    let path = dir.join(id);
+    if fs::symlink_metadata(&path).is_ok() && !path.is_dir() {
+        return Some(path);
+    }
+    id.match_indices('-').find_map(|(i, _)| {
+        let subdir = dir.join(&id[..i]);
+        subdir
+            .is_dir()
+            .then(|| file_with_id(&subdir, &id[i + 1..]))
+            .flatten()
+    })
+}
+
+/// Indices of the entries named in `recent`, in that order.
+pub fn recent<'a>(entries: &[DesktopEntry], recent: impl Iterator<Item = &'a str>) -> Vec<usize> {
+    recent
+        .filter_map(|name| entries.iter().position(|entry| entry.name == name))
+        .take(SECTION_LIMIT)
+        .collect()
+}
+
+/// Rows shown for an empty filter: each non-empty section under its own header, followed
+/// by all apps in the order of `all`.
+pub fn sections(all: Vec<usize>, sections: Vec<(&str, Vec<usize>)>) -> Vec<Row> {
+    let mut rows = Vec::new();
+    for (title, indices) in sections {
+        if indices.is_empty() {
+            continue;
+        }
+        rows.push(Row::Header(tr(title).to_string()));
+        rows.extend(indices.into_iter().take(SECTION_LIMIT).map(Row::entry));
+        rows.push(Row::Separator);
+    }
+    if !rows.is_empty() {
+        rows.push(Row::Header(tr("All apps").to_string()));
+    }
+    rows.extend(all.into_iter().map(Row::entry));
+    rows
+}
@@ -0,0 +1,89 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::Path,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{paths, vault};
+
+const DATA_PREFIX: &str = "data/";
+
+/// The config plus every file in the state and data directories (query history, launch
+/// history, ...), as a single JSON document for backups and moving to another machine.
+/// Files from the data directory are keyed with a `data/` prefix. Encrypted files are
+/// exported decrypted, as the key stays in this machine's keyring, and imported in
+/// plaintext until they are next saved.
+#[derive(Debug, Serialize, Deserialize)]
+struct Archive {
+    config: String,
+    state: BTreeMap<String, String>,
+}
+
+/// Writes the archive to `path`, or to stdout when no path is given. Fails on the first
+/// file that can't be read or decrypted rather than leaving it out.
+pub fn export(config_path: Option<&Path>, path: Option<&str>) -> io::Result<()> {
+    let mut state = BTreeMap::new();
+    let dirs = [(paths::state_dir(), ""), (paths::data_dir(), DATA_PREFIX)];
+    for (dir, prefix) in dirs {
+        let Some(dir) = dir else {
+            continue;
+        };
+        for file in fs::read_dir(dir).into_iter().flatten().flatten() {
+            // Leaves out the daemon's socket and any directories.
+            if !file.file_type().is_ok_and(|kind| kind.is_file()) {
+                continue;
+            }
+            let name = file.file_name().to_string_lossy().to_string();
+            let content = vault::read(&file.path()).map_err(|error| {
+                io::Error::new(
+                    error.kind(),
+                    format!("{}: {}", file.path().display(), error),
+                )
+            })?;
+            state.insert(format!("{}{}", prefix, name), content);
+        }
+    }
+    let archive = Archive {
+        config: paths::read_config(config_path)?,
+        state,
+    };
+    let json = serde_json::to_string_pretty(&archive)?;
+    match path {
+        Some(path) => fs::write(path, json),
+        None => writeln!(io::stdout(), "{}", json),
+    }
+}
+
+/// Restores an archive written by `export`, overwriting the config in
+/// `$XDG_CONFIG_HOME/launcher` and the state files.
+pub fn import(path: &str) -> io::Result<()> {
+    let archive: Archive = serde_json::from_str(&fs::read_to_string(path)?)?;
+    let config_dir = paths::config_home()
+        .map(|dir| dir.join("launcher"))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no config directory"))?;
+    let state_dir = paths::state_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no state directory"))?;
+    let data_dir = paths::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no data directory"))?;
+    for (name, content) in &archive.state {
+        let (dir, name) = match name.strip_prefix(DATA_PREFIX) {
+            Some(name) => (&data_dir, name),
+            None => (&state_dir, name.as_str()),
+        };
+        fs::create_dir_all(dir)?;
+        let file = Path::new(name)
+            .file_name()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "bad file name"))?;
+        fs::write(dir.join(file), content)?;
+    }
+    fs::create_dir_all(&config_dir)?;
+    fs::write(config_dir.join("launcher.toml"), archive.config)?;
+    println!(
+        "restored the config and {} state files",
+        archive.state.len()
+    );
+    Ok(())
+}
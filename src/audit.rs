@@ -0,0 +1,36 @@
+use std::{
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+pub struct AuditRecord<'a> {
+    pub timestamp: u64,
+    pub id: &'a str,
+    pub argv: &'a [String],
+    pub success: bool,
+}
+
+impl<'a> AuditRecord<'a> {
+    pub fn new(id: &'a str, argv: &'a [String], success: bool) -> Self {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        Self {
+            timestamp,
+            id,
+            argv,
+            success,
+        }
+    }
+
+    pub fn append_to(&self, path: &Path) -> io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", serde_json::to_string(self)?)
+    }
+}
@@ -0,0 +1,105 @@
+use ratatui::{style::Color, text::Span};
+use serde::Deserialize;
+
+use crate::{
+    config::Config, desktop_entry::DesktopEntry, first_seen::FirstSeen, running::Processes, theme,
+};
+
+/// A small tag rendered as a colored chip at the end of a row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Badge {
+    Flatpak,
+    Terminal,
+    Sandboxed,
+    New,
+    Running,
+}
+
+impl Badge {
+    /// Badges that apply to `entry` and are enabled in the config.
+    pub(crate) fn for_entry(
+        entry: &DesktopEntry,
+        config: &Config,
+        first_seen: &FirstSeen,
+        processes: &Processes,
+    ) -> Vec<Badge> {
+        let mut badges = Vec::new();
+        if entry.exec.starts_with("flatpak ") || entry.exec.contains("/flatpak ") {
+            badges.push(Badge::Flatpak);
+        }
+        if entry.terminal {
+            badges.push(Badge::Terminal);
+        }
+        if config.sandbox_rule(entry).is_some() {
+            badges.push(Badge::Sandboxed);
+        }
+        if first_seen.is_new(entry, config.new_badge_days) {
+            badges.push(Badge::New);
+        }
+        if config.reuses_window(entry) && processes.find(entry).is_some() {
+            badges.push(Badge::Running);
+        }
+        badges.retain(|badge| config.badges.enabled(*badge));
+        badges
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Badge::Flatpak => "flatpak",
+            Badge::Terminal => "terminal",
+            Badge::Sandboxed => "sandboxed",
+            Badge::New => "new",
+            Badge::Running => "running",
+        }
+    }
+
+    /// The background of the badge in the theme.
+    pub fn color(&self) -> Color {
+        theme::get()
+            .badges
+            .get(self.label())
+            .copied()
+            .unwrap_or(Color::Reset)
+    }
+
+    pub fn span(&self) -> Span<'static> {
+        Span::styled(
+            format!(" {} ", self.label()),
+            theme::get().chip(self.color()),
+        )
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct BadgeConfig {
+    pub flatpak: bool,
+    pub terminal: bool,
+    pub sandboxed: bool,
+    pub new: bool,
+    pub running: bool,
+}
+
+impl BadgeConfig {
+    pub fn enabled(&self, badge: Badge) -> bool {
+        match badge {
+            Badge::Flatpak => self.flatpak,
+            Badge::Terminal => self.terminal,
+            Badge::Sandboxed => self.sandboxed,
+            Badge::New => self.new,
+            Badge::Running => self.running,
+        }
+    }
+}
+
+impl Default for BadgeConfig {
+    fn default() -> Self {
+        Self {
+            flatpak: true,
+            terminal: true,
+            sandboxed: true,
+            new: true,
+            running: true,
+        }
+    }
+}
@@ -0,0 +1,214 @@
+use std::{
+    cmp::Reverse,
+    fs, io,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    widgets::{List, Widget},
+};
+
+use crate::{
+    config::Config, history::QueryHistory, matcher, names::Names, perf::percentile,
+    result_cache::ResultCache, row::Match, sources::Item,
+};
+
+const USAGE: &str = "usage: launcher bench [--entries N] [--trace FILE]";
+
+const DEFAULT_ENTRIES: usize = 10_000;
+
+/// Typed when there is neither a trace file nor a query history.
+const DEFAULT_TRACE: [&str; 8] = [
+    "firefox",
+    "term",
+    "lib wri",
+    "vsc",
+    "system monitor",
+    "gimp",
+    "ffx",
+    "zzzz",
+];
+
+/// Pieces of the synthetic entries' names.
+const SYLLABLES: [&str; 24] = [
+    "fire", "fox", "term", "in", "al", "lib", "re", "office", "writ", "er", "code", "gim", "p",
+    "sys", "tem", "mon", "it", "or", "zed", "vi", "m", "kde", "gnome", "set",
+];
+
+const WORDS: [&str; 8] = [
+    "Editor", "Viewer", "Settings", "Manager", "Player", "Browser", "Monitor", "Tools",
+];
+
+/// The size of the list rendered for each keystroke, like a full-screen launcher's.
+const LIST_AREA: Rect = Rect {
+    x: 0,
+    y: 0,
+    width: 80,
+    height: 24,
+};
+
+/// Times filtering as the list does for every keystroke of the queries in a trace, over
+/// generated entries, and prints the percentiles of each step. Runs with the `[matcher]`
+/// settings of the config, so they can be compared on the same machine.
+pub fn run(args: &[String], config: &Config) -> io::Result<()> {
+    let mut entries = DEFAULT_ENTRIES;
+    let mut trace_file = None;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), args.next()) {
+            ("--entries", Some(count)) => {
+                entries = count.parse().map_err(|_| usage())?;
+            }
+            ("--trace", Some(path)) => trace_file = Some(path),
+            _ => return Err(usage()),
+        }
+    }
+    let (queries, from) = match trace_file {
+        Some(path) => {
+            let trace = fs::read_to_string(path)?;
+            (trace.lines().map(String::from).collect(), path.as_str())
+        }
+        None => {
+            let history = QueryHistory::load();
+            let recent = history.recent().map(String::from).collect::<Vec<String>>();
+            if recent.is_empty() {
+                (
+                    DEFAULT_TRACE.map(String::from).to_vec(),
+                    "the built-in trace",
+                )
+            } else {
+                (recent, "the query history")
+            }
+        }
+    };
+    let items = synthetic_items(entries);
+    let mut timings = Timings::default();
+    for query in queries.iter().filter(|query| !query.is_empty()) {
+        replay(query, &items, config, &mut timings);
+    }
+    println!(
+        "{} entries, {} keystrokes of {} queries from {}",
+        items.len(),
+        timings.matching.len(),
+        queries.len(),
+        from
+    );
+    println!("{:<20}{:>12}{:>12}{:>12}", "", "p50", "p90", "p99");
+    for (name, samples) in [
+        ("match", &mut timings.matching),
+        ("match, unfiltered", &mut timings.unfiltered),
+        ("sort", &mut timings.sorting),
+        ("render", &mut timings.rendering),
+    ] {
+        samples.sort_by(f64::total_cmp);
+        let [p50, p90, p99] = [50, 90, 99].map(|p| match percentile(samples, p) {
+            Some(millis) => format!("{:.3}ms", millis),
+            None => "-".to_string(),
+        });
+        println!("{:<20}{:>12}{:>12}{:>12}", name, p50, p90, p99);
+    }
+    Ok(())
+}
+
+fn usage() -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, USAGE)
+}
+
+/// Milliseconds taken by each step, one sample per keystroke.
+#[derive(Debug, Default)]
+struct Timings {
+    matching: Vec<f64>,
+    /// Matching the same candidates without the byte prefilter, to compare.
+    unfiltered: Vec<f64>,
+    sorting: Vec<f64>,
+    rendering: Vec<f64>,
+}
+
+/// Types `query` one character at a time into a fresh list, narrowing each query's
+/// matches from the previous one's through the result cache as the app does.
+fn replay(query: &str, items: &[Item], config: &Config, timings: &mut Timings) {
+    let mut cache = ResultCache::default();
+    let names = Names::new(items);
+    let mut buffer = Buffer::empty(LIST_AREA);
+    let ends = query.char_indices().map(|(i, c)| i + c.len_utf8());
+    for prefix in ends.map(|end| &query[..end]) {
+        let start = Instant::now();
+        let narrowed = cache
+            .superset(prefix)
+            .filter(|_| config.matcher.algorithm.narrows());
+        let candidates = match narrowed {
+            Some(matches) => matches.iter().map(|m| m.index).collect(),
+            None => (0..items.len()).collect::<Vec<usize>>(),
+        };
+        let mut matches = candidates
+            .iter()
+            .filter_map(|&index| {
+                let score = matcher::score(names.get(index), prefix, &config.matcher)?;
+                Some(Match { index, score })
+            })
+            .collect::<Vec<Match>>();
+        timings.matching.push(millis(start.elapsed()));
+
+        let start = Instant::now();
+        let unfiltered = candidates
+            .iter()
+            .filter(|&&index| {
+                matcher::score_unfiltered(names.get(index), prefix, &config.matcher).is_some()
+            })
+            .count();
+        timings.unfiltered.push(millis(start.elapsed()));
+        debug_assert_eq!(unfiltered, matches.len());
+
+        let start = Instant::now();
+        matches
+            .sort_by_cached_key(|m| (Reverse(m.score.score), items[m.index].name.chars().count()));
+        cache.insert(prefix, matches.clone());
+        timings.sorting.push(millis(start.elapsed()));
+
+        let start = Instant::now();
+        let lines = matches
+            .iter()
+            .take(LIST_AREA.height as usize)
+            .map(|m| items[m.index].get_highlighted_name(&m.score.positions, true));
+        List::new(lines).render(LIST_AREA, &mut buffer);
+        timings.rendering.push(millis(start.elapsed()));
+    }
+}
+
+/// `count` entries with names like `Firefox Settings` or `Libreoffice`, the same on
+/// every run.
+fn synthetic_items(count: usize) -> Vec<Item> {
+    let mut random = Random(0x2545_f491_4f6c_dd1d);
+    (0..count)
+        .map(|i| {
+            let syllables = 2 + random.below(3);
+            let mut name = (0..syllables)
+                .map(|_| SYLLABLES[random.below(SYLLABLES.len())])
+                .collect::<String>();
+            name[..1].make_ascii_uppercase();
+            if random.below(2) == 0 {
+                name.push(' ');
+                name.push_str(WORDS[random.below(WORDS.len())]);
+            }
+            Item::new(format!("synthetic-{}.desktop", i), name, "󱌢")
+        })
+        .collect()
+}
+
+/// A xorshift generator, as the entries need to be varied but not random.
+struct Random(u64);
+
+impl Random {
+    fn below(&mut self, bound: usize) -> usize {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        (self.0 % bound as u64) as usize
+    }
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
@@ -0,0 +1,58 @@
+use std::{env, fmt::Debug, io, rc::Rc};
+
+pub mod hyprland;
+pub mod sway;
+pub mod wlr;
+pub mod x11;
+
+/// A toplevel window as reported by the compositor.
+#[derive(Debug, Clone)]
+pub struct Window {
+    /// The compositor's handle for the window, e.g. a Hyprland address.
+    pub id: String,
+    pub title: String,
+    pub app_id: String,
+    pub workspace: String,
+    pub pid: Option<u32>,
+    /// The window is the active one.
+    pub focused: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Workspace {
+    pub name: String,
+    pub windows: usize,
+    pub focused: bool,
+}
+
+/// Talks to the running compositor to list and focus windows and workspaces.
+pub trait Compositor: Debug {
+    fn windows(&self) -> io::Result<Vec<Window>>;
+    fn workspaces(&self) -> io::Result<Vec<Workspace>>;
+    fn focus_window(&self, window: &Window) -> io::Result<()>;
+    fn focus_pid(&self, pid: u32) -> io::Result<()>;
+    fn switch_workspace(&self, workspace: &Workspace) -> io::Result<()>;
+    /// Moves `window` to the workspace named `workspace` without following it.
+    fn move_to_workspace(&self, window: &Window, workspace: &str) -> io::Result<()>;
+}
+
+/// The integration for the compositor the launcher runs under, if one is supported.
+pub fn detect() -> Option<Rc<dyn Compositor>> {
+    if let Some(signature) = env::var_os("HYPRLAND_INSTANCE_SIGNATURE") {
+        return Some(Rc::new(hyprland::Hyprland::new(
+            &signature.to_string_lossy(),
+        )));
+    }
+    if let Some(socket) = env::var_os("SWAYSOCK").or(env::var_os("I3SOCK")) {
+        return Some(Rc::new(sway::Sway::new(socket.into())));
+    }
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        if let Some(wlr) = wlr::Wlr::connect() {
+            return Some(Rc::new(wlr));
+        }
+    }
+    if env::var_os("DISPLAY").is_some() {
+        return x11::X11::connect().map(|x11| Rc::new(x11) as Rc<dyn Compositor>);
+    }
+    None
+}
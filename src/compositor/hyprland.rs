@@ -0,0 +1,125 @@
+use std::{
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+};
+
+use serde_json::Value;
+
+use crate::{
+    compositor::{Compositor, Window, Workspace},
+    paths,
+};
+
+/// Hyprland, through the request socket that `hyprctl` uses.
+#[derive(Debug)]
+pub struct Hyprland {
+    socket: PathBuf,
+}
+
+impl Hyprland {
+    pub fn new(signature: &str) -> Self {
+        let runtime = paths::runtime_dir()
+            .map(|dir| dir.join("hypr").join(signature))
+            .filter(|dir| dir.exists());
+        let dir = runtime.unwrap_or_else(|| PathBuf::from("/tmp/hypr").join(signature));
+        Self {
+            socket: dir.join(".socket.sock"),
+        }
+    }
+
+    /// Sends one request and reads the reply; Hyprland closes the socket after each.
+    fn request(&self, request: &str) -> io::Result<String> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        stream.write_all(request.as_bytes())?;
+        let mut reply = String::new();
+        stream.read_to_string(&mut reply)?;
+        Ok(reply)
+    }
+
+    fn query(&self, request: &str) -> io::Result<Vec<Value>> {
+        let reply = self.request(&format!("j/{}", request))?;
+        let value = serde_json::from_str::<Value>(&reply)?;
+        Ok(value.as_array().cloned().unwrap_or_default())
+    }
+
+    fn dispatch(&self, dispatcher: &str) -> io::Result<()> {
+        let reply = self.request(&format!("dispatch {}", dispatcher))?;
+        if reply.trim() == "ok" {
+            Ok(())
+        } else {
+            Err(io::Error::other(reply.trim().to_string()))
+        }
+    }
+}
+
+impl Compositor for Hyprland {
+    fn windows(&self) -> io::Result<Vec<Window>> {
+        let clients = self.query("clients")?;
+        Ok(clients
+            .iter()
+            .filter(|client| client["mapped"].as_bool() != Some(false))
+            .map(|client| Window {
+                id: text(&client["address"]),
+                title: text(&client["title"]),
+                app_id: text(&client["class"]),
+                workspace: text(&client["workspace"]["name"]),
+                pid: client["pid"].as_u64().map(|pid| pid as u32),
+                focused: client["focusHistoryID"] == 0,
+            })
+            .collect())
+    }
+
+    fn workspaces(&self) -> io::Result<Vec<Workspace>> {
+        let active = self
+            .request("j/activeworkspace")
+            .ok()
+            .and_then(|reply| serde_json::from_str::<Value>(&reply).ok())
+            .map(|workspace| text(&workspace["name"]));
+        let mut workspaces = self
+            .query("workspaces")?
+            .iter()
+            .map(|workspace| {
+                let name = text(&workspace["name"]);
+                Workspace {
+                    focused: active.as_ref() == Some(&name),
+                    name,
+                    windows: workspace["windows"].as_u64().unwrap_or_default() as usize,
+                }
+            })
+            .collect::<Vec<Workspace>>();
+        workspaces.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(workspaces)
+    }
+
+    fn focus_window(&self, window: &Window) -> io::Result<()> {
+        self.dispatch(&format!("focuswindow address:{}", window.id))
+    }
+
+    fn focus_pid(&self, pid: u32) -> io::Result<()> {
+        self.dispatch(&format!("focuswindow pid:{}", pid))
+    }
+
+    fn switch_workspace(&self, workspace: &Workspace) -> io::Result<()> {
+        self.dispatch(&format!("workspace name:{}", workspace.name))
+    }
+
+    fn move_to_workspace(&self, window: &Window, workspace: &str) -> io::Result<()> {
+        let workspace = match workspace.parse::<i32>() {
+            Ok(id) => id.to_string(),
+            Err(_) => format!("name:{}", workspace),
+        };
+        self.dispatch(&format!(
+            "movetoworkspacesilent {},address:{}",
+            workspace, window.id
+        ))
+    }
+}
+
+fn text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
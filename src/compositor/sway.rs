@@ -0,0 +1,136 @@
+use std::{
+    io::{self, Read, Write},
+    os::unix::net::UnixStream,
+    path::PathBuf,
+};
+
+use serde_json::Value;
+
+use crate::compositor::{Compositor, Window, Workspace};
+
+const MAGIC: &[u8] = b"i3-ipc";
+const RUN_COMMAND: u32 = 0;
+const GET_WORKSPACES: u32 = 1;
+const GET_TREE: u32 = 4;
+
+/// Sway or i3, through the IPC socket in `$SWAYSOCK` or `$I3SOCK`.
+#[derive(Debug)]
+pub struct Sway {
+    socket: PathBuf,
+}
+
+impl Sway {
+    pub fn new(socket: PathBuf) -> Self {
+        Self { socket }
+    }
+
+    /// Sends one message and reads its reply, framed as magic, length, type, payload.
+    fn message(&self, kind: u32, payload: &str) -> io::Result<Value> {
+        let mut stream = UnixStream::connect(&self.socket)?;
+        let mut message = MAGIC.to_vec();
+        message.extend((payload.len() as u32).to_ne_bytes());
+        message.extend(kind.to_ne_bytes());
+        message.extend(payload.as_bytes());
+        stream.write_all(&message)?;
+        let mut header = [0; 14];
+        stream.read_exact(&mut header)?;
+        let length = u32::from_ne_bytes([header[6], header[7], header[8], header[9]]);
+        let mut reply = vec![0; length as usize];
+        stream.read_exact(&mut reply)?;
+        Ok(serde_json::from_slice(&reply)?)
+    }
+
+    fn command(&self, command: &str) -> io::Result<()> {
+        let reply = self.message(RUN_COMMAND, command)?;
+        let results = reply.as_array().cloned().unwrap_or_default();
+        match results.iter().find(|result| result["success"] != true) {
+            Some(failed) => Err(io::Error::other(
+                failed["error"]
+                    .as_str()
+                    .unwrap_or("command failed")
+                    .to_string(),
+            )),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Compositor for Sway {
+    fn windows(&self) -> io::Result<Vec<Window>> {
+        let tree = self.message(GET_TREE, "")?;
+        let mut windows = Vec::new();
+        collect_windows(&tree, "", &mut windows);
+        Ok(windows)
+    }
+
+    fn workspaces(&self) -> io::Result<Vec<Workspace>> {
+        let windows = self.windows()?;
+        let workspaces = self.message(GET_WORKSPACES, "")?;
+        Ok(workspaces
+            .as_array()
+            .cloned()
+            .unwrap_or_default()
+            .iter()
+            .map(|workspace| {
+                let name = workspace["name"].as_str().unwrap_or_default().to_string();
+                Workspace {
+                    windows: windows.iter().filter(|w| w.workspace == name).count(),
+                    focused: workspace["focused"] == true,
+                    name,
+                }
+            })
+            .collect())
+    }
+
+    fn focus_window(&self, window: &Window) -> io::Result<()> {
+        self.command(&format!("[con_id={}] focus", window.id))
+    }
+
+    fn focus_pid(&self, pid: u32) -> io::Result<()> {
+        self.command(&format!("[pid={}] focus", pid))
+    }
+
+    fn switch_workspace(&self, workspace: &Workspace) -> io::Result<()> {
+        self.command(&format!("workspace {}", quote(&workspace.name)))
+    }
+
+    fn move_to_workspace(&self, window: &Window, workspace: &str) -> io::Result<()> {
+        self.command(&format!(
+            "[con_id={}] move container to workspace {}",
+            window.id,
+            quote(workspace)
+        ))
+    }
+}
+
+/// Quotes a workspace name as an argument of a command.
+fn quote(name: &str) -> String {
+    format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Walks the layout tree, collecting the leaves that hold an app, under the name of
+/// the workspace they're on.
+fn collect_windows(node: &Value, workspace: &str, windows: &mut Vec<Window>) {
+    let workspace = match node["type"].as_str() {
+        Some("workspace") => node["name"].as_str().unwrap_or(workspace),
+        _ => workspace,
+    };
+    let app_id = node["app_id"]
+        .as_str()
+        .or(node["window_properties"]["class"].as_str());
+    if let Some(app_id) = app_id {
+        windows.push(Window {
+            id: node["id"].to_string(),
+            title: node["name"].as_str().unwrap_or_default().to_string(),
+            app_id: app_id.to_string(),
+            workspace: workspace.to_string(),
+            pid: node["pid"].as_u64().map(|pid| pid as u32),
+            focused: node["focused"] == true,
+        });
+    }
+    for key in ["nodes", "floating_nodes"] {
+        for child in node[key].as_array().into_iter().flatten() {
+            collect_windows(child, workspace, windows);
+        }
+    }
+}
@@ -0,0 +1,226 @@
+use std::io;
+
+use wayland_client::{
+    event_created_child,
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_registry::WlRegistry, wl_seat::WlSeat},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+};
+use wayland_protocols_wlr::foreign_toplevel::v1::client::{
+    zwlr_foreign_toplevel_handle_v1::{self, ZwlrForeignToplevelHandleV1},
+    zwlr_foreign_toplevel_manager_v1::{self, ZwlrForeignToplevelManagerV1},
+};
+
+use crate::compositor::{Compositor, Window, Workspace};
+
+/// Any Wayland compositor with the wlr-foreign-toplevel protocol, like river, labwc or
+/// Wayfire. The protocol knows neither workspaces nor processes, so windows can only be
+/// listed and focused.
+#[derive(Debug)]
+pub struct Wlr;
+
+/// The toplevels announced on a fresh connection, and the seat to activate them on.
+struct Toplevels {
+    connection: Connection,
+    queue: EventQueue<State>,
+    state: State,
+    seat: WlSeat,
+}
+
+#[derive(Default)]
+struct State {
+    toplevels: Vec<Toplevel>,
+}
+
+struct Toplevel {
+    handle: ZwlrForeignToplevelHandleV1,
+    title: String,
+    app_id: String,
+    activated: bool,
+    closed: bool,
+}
+
+impl Wlr {
+    /// Fails unless the compositor offers the protocol.
+    pub fn connect() -> Option<Self> {
+        Toplevels::fetch().ok().map(|_| Self)
+    }
+}
+
+impl Toplevels {
+    fn fetch() -> io::Result<Self> {
+        let connection = Connection::connect_to_env().map_err(io::Error::other)?;
+        let (globals, mut queue) =
+            registry_queue_init::<State>(&connection).map_err(io::Error::other)?;
+        let handle = queue.handle();
+        globals
+            .bind::<ZwlrForeignToplevelManagerV1, _, _>(&handle, 1..=3, ())
+            .map_err(io::Error::other)?;
+        let seat = globals
+            .bind::<WlSeat, _, _>(&handle, 1..=1, ())
+            .map_err(io::Error::other)?;
+        let mut state = State::default();
+        // The toplevels arrive with the first roundtrip, their titles and states with
+        // the second at the latest.
+        for _ in 0..2 {
+            queue.roundtrip(&mut state).map_err(io::Error::other)?;
+        }
+        state.toplevels.retain(|toplevel| !toplevel.closed);
+        Ok(Self {
+            connection,
+            queue,
+            state,
+            seat,
+        })
+    }
+
+    fn windows(&self) -> Vec<Window> {
+        self.state
+            .toplevels
+            .iter()
+            .enumerate()
+            .map(|(i, toplevel)| Window {
+                id: i.to_string(),
+                title: toplevel.title.clone(),
+                app_id: toplevel.app_id.clone(),
+                workspace: String::new(),
+                pid: None,
+                focused: toplevel.activated,
+            })
+            .collect()
+    }
+}
+
+fn unsupported() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        "not supported through wlr-foreign-toplevel",
+    )
+}
+
+impl Compositor for Wlr {
+    fn windows(&self) -> io::Result<Vec<Window>> {
+        Ok(Toplevels::fetch()?.windows())
+    }
+
+    fn workspaces(&self) -> io::Result<Vec<Workspace>> {
+        Err(unsupported())
+    }
+
+    /// Activates the toplevel listed at the same position with the same title and app,
+    /// or else the first one with them, as handles don't outlive a connection.
+    fn focus_window(&self, window: &Window) -> io::Result<()> {
+        let mut toplevels = Toplevels::fetch()?;
+        let same = |toplevel: &&Toplevel| {
+            toplevel.title == window.title && toplevel.app_id == window.app_id
+        };
+        let listed = &toplevels.state.toplevels;
+        let toplevel = window
+            .id
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| listed.get(i))
+            .filter(same)
+            .or_else(|| listed.iter().find(same))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "the window was closed"))?;
+        toplevel.handle.activate(&toplevels.seat);
+        toplevels
+            .queue
+            .roundtrip(&mut toplevels.state)
+            .map_err(io::Error::other)?;
+        toplevels.connection.flush().map_err(io::Error::other)
+    }
+
+    fn focus_pid(&self, _pid: u32) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    fn switch_workspace(&self, _workspace: &Workspace) -> io::Result<()> {
+        Err(unsupported())
+    }
+
+    fn move_to_workspace(&self, _window: &Window, _workspace: &str) -> io::Result<()> {
+        Err(unsupported())
+    }
+}
+
+impl Dispatch<WlRegistry, GlobalListContents> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlRegistry,
+        _: <WlRegistry as Proxy>::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WlSeat, ()> for State {
+    fn event(
+        _: &mut Self,
+        _: &WlSeat,
+        _: <WlSeat as Proxy>::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<ZwlrForeignToplevelManagerV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        _: &ZwlrForeignToplevelManagerV1,
+        event: zwlr_foreign_toplevel_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let zwlr_foreign_toplevel_manager_v1::Event::Toplevel { toplevel } = event {
+            state.toplevels.push(Toplevel {
+                handle: toplevel,
+                title: String::new(),
+                app_id: String::new(),
+                activated: false,
+                closed: false,
+            });
+        }
+    }
+
+    event_created_child!(State, ZwlrForeignToplevelManagerV1, [
+        zwlr_foreign_toplevel_manager_v1::EVT_TOPLEVEL_OPCODE => (ZwlrForeignToplevelHandleV1, ()),
+    ]);
+}
+
+impl Dispatch<ZwlrForeignToplevelHandleV1, ()> for State {
+    fn event(
+        state: &mut Self,
+        handle: &ZwlrForeignToplevelHandleV1,
+        event: zwlr_foreign_toplevel_handle_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        let Some(toplevel) = state
+            .toplevels
+            .iter_mut()
+            .find(|toplevel| toplevel.handle == *handle)
+        else {
+            return;
+        };
+        match event {
+            zwlr_foreign_toplevel_handle_v1::Event::Title { title } => toplevel.title = title,
+            zwlr_foreign_toplevel_handle_v1::Event::AppId { app_id } => toplevel.app_id = app_id,
+            // An array of native-endian `u32` states.
+            zwlr_foreign_toplevel_handle_v1::Event::State { state } => {
+                let activated = zwlr_foreign_toplevel_handle_v1::State::Activated as u32;
+                toplevel.activated = state
+                    .chunks_exact(4)
+                    .any(|bytes| bytes == activated.to_ne_bytes());
+            }
+            zwlr_foreign_toplevel_handle_v1::Event::Closed => toplevel.closed = true,
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::io;
+
+use x11rb::{
+    connection::Connection,
+    protocol::xproto::{
+        Atom, AtomEnum, ClientMessageEvent, ConnectionExt, EventMask, Window as XWindow,
+    },
+    rust_connection::RustConnection,
+};
+
+use crate::compositor::{Compositor, Window, Workspace};
+
+/// An EWMH-compliant window manager on Xorg, through the root window's properties.
+#[derive(Debug)]
+pub struct X11 {
+    connection: RustConnection,
+    root: XWindow,
+    atoms: Atoms,
+}
+
+#[derive(Debug)]
+struct Atoms {
+    client_list: Atom,
+    active_window: Atom,
+    current_desktop: Atom,
+    desktop_names: Atom,
+    wm_desktop: Atom,
+    wm_name: Atom,
+    wm_pid: Atom,
+    utf8_string: Atom,
+}
+
+impl X11 {
+    pub fn connect() -> Option<Self> {
+        let (connection, screen) = x11rb::connect(None).ok()?;
+        let root = connection.setup().roots.get(screen)?.root;
+        let atom = |name: &str| -> Option<Atom> {
+            Some(
+                connection
+                    .intern_atom(false, name.as_bytes())
+                    .ok()?
+                    .reply()
+                    .ok()?
+                    .atom,
+            )
+        };
+        let atoms = Atoms {
+            client_list: atom("_NET_CLIENT_LIST")?,
+            active_window: atom("_NET_ACTIVE_WINDOW")?,
+            current_desktop: atom("_NET_CURRENT_DESKTOP")?,
+            desktop_names: atom("_NET_DESKTOP_NAMES")?,
+            wm_desktop: atom("_NET_WM_DESKTOP")?,
+            wm_name: atom("_NET_WM_NAME")?,
+            wm_pid: atom("_NET_WM_PID")?,
+            utf8_string: atom("UTF8_STRING")?,
+        };
+        Some(Self {
+            connection,
+            root,
+            atoms,
+        })
+    }
+
+    fn cardinals(&self, window: XWindow, property: Atom, kind: AtomEnum) -> Vec<u32> {
+        self.connection
+            .get_property(false, window, property, kind, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .and_then(|reply| reply.value32().map(Iterator::collect))
+            .unwrap_or_default()
+    }
+
+    fn text(&self, window: XWindow, property: Atom, kind: impl Into<Atom>) -> Vec<u8> {
+        self.connection
+            .get_property(false, window, property, kind, 0, u32::MAX)
+            .ok()
+            .and_then(|cookie| cookie.reply().ok())
+            .map(|reply| reply.value)
+            .unwrap_or_default()
+    }
+
+    /// Workspace names from `_NET_DESKTOP_NAMES`, or their 1-based numbers. Sticky
+    /// windows, on desktop `0xFFFFFFFF`, are on "all".
+    fn desktop_name(&self, names: &[String], desktop: u32) -> String {
+        if desktop == u32::MAX {
+            return "all".to_string();
+        }
+        names
+            .get(desktop as usize)
+            .filter(|name| !name.is_empty())
+            .cloned()
+            .unwrap_or_else(|| (desktop + 1).to_string())
+    }
+
+    fn desktop_names(&self) -> Vec<String> {
+        let names = self.text(self.root, self.atoms.desktop_names, self.atoms.utf8_string);
+        names
+            .split(|&b| b == 0)
+            .map(|name| String::from_utf8_lossy(name).to_string())
+            .collect()
+    }
+
+    /// Asks the window manager to change a root window property, as a pager would.
+    fn request(&self, window: XWindow, kind: Atom, data: [u32; 5]) -> io::Result<()> {
+        let event = ClientMessageEvent::new(32, window, kind, data);
+        let mask = EventMask::SUBSTRUCTURE_REDIRECT | EventMask::SUBSTRUCTURE_NOTIFY;
+        self.connection
+            .send_event(false, self.root, mask, event)
+            .map_err(io::Error::other)?;
+        self.connection.flush().map_err(io::Error::other)
+    }
+}
+
+impl Compositor for X11 {
+    fn windows(&self) -> io::Result<Vec<Window>> {
+        let names = self.desktop_names();
+        let active = self
+            .cardinals(self.root, self.atoms.active_window, AtomEnum::WINDOW)
+            .first()
+            .copied();
+        let clients = self.cardinals(self.root, self.atoms.client_list, AtomEnum::WINDOW);
+        Ok(clients
+            .into_iter()
+            .map(|client| {
+                let class = self.text(client, AtomEnum::WM_CLASS.into(), AtomEnum::STRING);
+                let class = class.split(|&b| b == 0).nth(1).unwrap_or_default();
+                let desktop = self
+                    .cardinals(client, self.atoms.wm_desktop, AtomEnum::CARDINAL)
+                    .first()
+                    .copied()
+                    .unwrap_or_default();
+                Window {
+                    id: client.to_string(),
+                    title: String::from_utf8_lossy(&self.text(
+                        client,
+                        self.atoms.wm_name,
+                        self.atoms.utf8_string,
+                    ))
+                    .to_string(),
+                    app_id: String::from_utf8_lossy(class).to_string(),
+                    workspace: self.desktop_name(&names, desktop),
+                    pid: self
+                        .cardinals(client, self.atoms.wm_pid, AtomEnum::CARDINAL)
+                        .first()
+                        .copied(),
+                    focused: active == Some(client),
+                }
+            })
+            .collect())
+    }
+
+    fn workspaces(&self) -> io::Result<Vec<Workspace>> {
+        let names = self.desktop_names();
+        let current = self
+            .cardinals(self.root, self.atoms.current_desktop, AtomEnum::CARDINAL)
+            .first()
+            .copied();
+        let windows = self.windows()?;
+        Ok((0..names.len().max(1) as u32)
+            .map(|desktop| {
+                let name = self.desktop_name(&names, desktop);
+                Workspace {
+                    windows: windows.iter().filter(|w| w.workspace == name).count(),
+                    focused: current == Some(desktop),
+                    name,
+                }
+            })
+            .collect())
+    }
+
+    fn focus_window(&self, window: &Window) -> io::Result<()> {
+        let window = window
+            .id
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "bad window id"))?;
+        self.request(window, self.atoms.active_window, [2, 0, 0, 0, 0])
+    }
+
+    fn focus_pid(&self, pid: u32) -> io::Result<()> {
+        let window = self
+            .windows()?
+            .into_iter()
+            .find(|window| window.pid == Some(pid))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no window for process"))?;
+        self.focus_window(&window)
+    }
+
+    fn switch_workspace(&self, workspace: &Workspace) -> io::Result<()> {
+        let names = self.desktop_names();
+        let desktop = (0..names.len().max(1) as u32)
+            .find(|&desktop| self.desktop_name(&names, desktop) == workspace.name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such workspace"))?;
+        self.request(self.root, self.atoms.current_desktop, [desktop, 0, 0, 0, 0])
+    }
+
+    fn move_to_workspace(&self, window: &Window, workspace: &str) -> io::Result<()> {
+        let names = self.desktop_names();
+        let desktop = (0..names.len().max(1) as u32)
+            .find(|&desktop| self.desktop_name(&names, desktop) == workspace)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no such workspace"))?;
+        let window = window
+            .id
+            .parse()
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "bad window id"))?;
+        self.request(window, self.atoms.wm_desktop, [desktop, 2, 0, 0, 0])
+    }
+}
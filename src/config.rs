@@ -1,7 +1,340 @@
+use std::{collections::HashMap, path::PathBuf};
+
 use serde::Deserialize;
 
+use crate::{
+    badge::BadgeConfig,
+    confirm::ConfirmConfig,
+    desktop_entry::{DesktopAction, DesktopEntry},
+    glob::glob_match,
+    hooks::Hooks,
+    keybindings::{KeyBinding, Keys},
+    launch::{self, EnvironmentConfig},
+    matcher::MatchWeights,
+    network::NetworkConfig,
+    sources::{
+        dmenu,
+        quick::{default_quick_commands, QuickCommand},
+        SourceConfig,
+    },
+    theme::ThemeConfig,
+};
+
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
     pub filter_label: String,
     pub entries_label: String,
+    #[serde(default)]
+    pub audit_log: Option<PathBuf>,
+    /// Only `allowed_entries` can be launched, and never through a shell, hooks included.
+    #[serde(default)]
+    pub restricted: bool,
+    /// Picks a line from stdin and prints it instead of launching apps, set by `--dmenu`.
+    #[serde(skip)]
+    pub dmenu: Option<dmenu::Options>,
+    /// Desktop file IDs (e.g. `firefox.desktop`) launchable in restricted mode.
+    #[serde(default)]
+    pub allowed_entries: Vec<String>,
+    #[serde(default)]
+    pub sandbox: Vec<SandboxRule>,
+    /// Template used by the elevated launch action, e.g. `sudo -A -u alice {cmd}`.
+    #[serde(default = "default_elevate_command")]
+    pub elevate_command: String,
+    /// The session's and the terminal's variables apps get when launched.
+    #[serde(default)]
+    pub environment: EnvironmentConfig,
+    /// Opens with the performance overlay shown, which F12 toggles.
+    #[serde(default)]
+    pub debug: bool,
+    /// Records startup, scan and keystroke latencies for `launcher perf report`.
+    #[serde(default)]
+    pub perf_telemetry: bool,
+    #[serde(default)]
+    pub log_file: Option<PathBuf>,
+    #[serde(default)]
+    pub matcher: MatchWeights,
+    /// Renders the selected entry's comment on a second line beneath its name.
+    #[serde(default)]
+    pub show_description: bool,
+    /// Lists recently launched apps in their own section while the filter is empty.
+    #[serde(default)]
+    pub show_recent: bool,
+    #[serde(default)]
+    pub badges: BadgeConfig,
+    /// Entries first seen within this many days get the "new" badge.
+    #[serde(default = "default_new_badge_days")]
+    pub new_badge_days: u64,
+    /// Lists newly discovered apps in their own section while the filter is empty.
+    #[serde(default)]
+    pub show_recently_installed: bool,
+    #[serde(default = "default_quick_commands")]
+    pub quick_commands: Vec<QuickCommand>,
+    #[serde(default)]
+    pub confirm: ConfirmConfig,
+    /// Overrides for modes, keyed by their keyword, e.g. `[sources.trash]`.
+    #[serde(default)]
+    pub sources: HashMap<String, SourceConfig>,
+    #[serde(default)]
+    pub entry_actions: Vec<EntryActionRule>,
+    #[serde(default)]
+    pub workspace_rules: Vec<WorkspaceRule>,
+    #[serde(default)]
+    pub hooks: Hooks,
+    /// Ranks apps by how often and how recently they were launched, keeping the launches
+    /// in `$XDG_DATA_HOME/launcher/history`. Alphabetical when off.
+    #[serde(default = "default_frecency")]
+    pub frecency: bool,
+    /// Days after which a launch counts half as much when ranking, so apps that were
+    /// used a lot long ago sink below ones used lately.
+    #[serde(default = "default_frecency_half_life")]
+    pub frecency_half_life: f64,
+    /// How many of an app's recently opened files its action menu lists.
+    #[serde(default = "default_recent_files")]
+    pub recent_files: usize,
+    /// Keeps each mode's last query and selection across runs, not just while open.
+    #[serde(default)]
+    pub remember_modes: bool,
+    #[serde(default)]
+    pub privacy: PrivacyConfig,
+    /// Encrypts the query and launch history and the remembered modes with a key kept
+    /// in the keyring through the Secret Service, using `secret-tool`.
+    #[serde(default)]
+    pub encrypt_history: bool,
+    /// Tells a daemon's user when a new release is out, looking once a day. Requests
+    /// are made as `update`, which `[network]` must allow too.
+    #[serde(default)]
+    pub check_updates: bool,
+    /// Requests of online sources like the web search's suggestions, none by default.
+    #[serde(default)]
+    pub network: NetworkConfig,
+    /// Marks entries whose app is running with a dot.
+    #[serde(default)]
+    pub show_running: bool,
+    /// Focuses the window of an already running single-window app instead of
+    /// launching it again.
+    #[serde(default)]
+    pub reuse_windows: bool,
+    /// Entries treated as single-window apps besides those with `SingleMainWindow=true`.
+    #[serde(default)]
+    pub single_instance: Vec<String>,
+    /// Raises a window, e.g. `swaymsg [pid={pid}] focus`. Detected from the compositor
+    /// when unset.
+    #[serde(default)]
+    pub focus_command: Option<String>,
+    /// Shows each entry's icon before its name.
+    #[serde(default = "default_show_icons")]
+    pub show_icons: bool,
+    /// Moving past the last result selects the first one and vice versa.
+    #[serde(default)]
+    pub wrap_selection: bool,
+    #[serde(default)]
+    pub sort: SortOrder,
+    /// Single-line input and a borderless list. Defaults to on in small terminals.
+    #[serde(default)]
+    pub compact: Option<bool>,
+    /// Defaults to off in small terminals.
+    #[serde(default)]
+    pub show_scrollbar: Option<bool>,
+    /// Rows of results shown at most, `0` for no limit. Defaults to 20 in full-screen
+    /// terminals.
+    #[serde(default)]
+    pub max_results: Option<usize>,
+    /// Shell commands run on a key, e.g. `"Ctrl+Y" = "echo {exec} | wl-copy"`.
+    #[serde(default)]
+    pub keybindings: HashMap<String, KeyBinding>,
+    /// Keys of the launcher's own actions, e.g. `select-next = ["Down", "Ctrl+J"]`.
+    #[serde(default)]
+    pub keys: Keys,
+    /// Opens the directory of the selected file on Alt+F, e.g. `nautilus {path}`. The
+    /// default app for directories is used when unset.
+    #[serde(default)]
+    pub file_manager: Option<String>,
+    /// When the whole filter matches nothing, its first word picks the entry and the
+    /// rest is passed to terminal apps as arguments, e.g. `htop -d 10`.
+    #[serde(default)]
+    pub pass_query_args: bool,
+    /// Stays open with the filter as typed after launching a graphical app, as Alt+Enter
+    /// does once.
+    #[serde(default)]
+    pub keep_open: bool,
+    /// Shows the last launched apps in a row above the list, picked with Ctrl+Left/Right
+    /// and Enter or launched directly with Alt+1 to Alt+5.
+    #[serde(default)]
+    pub show_recent_strip: bool,
+    /// Scrolls the list smoothly and fades the selection in.
+    #[serde(default)]
+    pub animations: bool,
+    /// Turns off animations and the spinner, and draws only when something changed, e.g.
+    /// over slow SSH links.
+    #[serde(default)]
+    pub reduced_motion: bool,
+    /// Draws with bright colors on black and without dimmed text, unless `[theme]`
+    /// names another theme.
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// Draws with ASCII characters only: no icons, box drawing or other glyphs.
+    #[serde(default)]
+    pub ascii: bool,
+    #[serde(default)]
+    pub theme: ThemeConfig,
+    /// The language of the interface, e.g. `de`. Taken from `$LC_ALL`, `$LC_MESSAGES` or
+    /// `$LANG` when unset.
+    #[serde(default)]
+    pub locale: Option<String>,
+}
+
+/// How results with equal match scores are ordered.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    /// By match score, then frecency, then shorter names first.
+    #[default]
+    Relevance,
+    /// By match score, then alphabetically.
+    Name,
+}
+
+impl SortOrder {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Relevance => "relevance",
+            Self::Name => "name",
+        }
+    }
+}
+
+fn default_frecency() -> bool {
+    true
+}
+
+fn default_frecency_half_life() -> f64 {
+    30.0
+}
+
+fn default_show_icons() -> bool {
+    true
+}
+
+fn default_recent_files() -> usize {
+    5
+}
+
+fn default_new_badge_days() -> u64 {
+    7
+}
+
+fn default_elevate_command() -> String {
+    "pkexec {cmd}".to_string()
+}
+
+impl Config {
+    pub fn sandbox_rule(&self, entry: &DesktopEntry) -> Option<&SandboxRule> {
+        self.sandbox.iter().find(|rule| rule.matches(entry))
+    }
+
+    /// Whether the list scrolls smoothly and fades the selection in.
+    pub fn animates(&self) -> bool {
+        self.animations && !self.reduced_motion
+    }
+
+    pub fn reuses_window(&self, entry: &DesktopEntry) -> bool {
+        self.reuse_windows
+            && (entry.single_main_window || matches_entry(&self.single_instance, entry))
+    }
+
+    /// The workspace matching entries are moved to once launched.
+    pub fn workspace_for(&self, entry: &DesktopEntry) -> Option<&str> {
+        self.workspace_rules
+            .iter()
+            .find(|rule| matches_entry(&rule.entries, entry))
+            .map(|rule| rule.workspace.as_str())
+    }
+
+    /// The entry's own desktop actions followed by the ones configured for it.
+    pub fn entry_actions(&self, entry: &DesktopEntry) -> Vec<DesktopAction> {
+        let custom = self
+            .entry_actions
+            .iter()
+            .filter(|rule| matches_entry(&rule.entries, entry))
+            .map(|rule| DesktopAction {
+                name: rule.name.clone(),
+                exec: rule.exec.clone(),
+            });
+        entry.actions.iter().cloned().chain(custom).collect()
+    }
+}
+
+/// Wraps matching entries in `command`, where `{cmd}` expands to the entry's command line
+/// or else the command line is appended. The launch-unsandboxed action skips the rule,
+/// except in a restricted launcher.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SandboxRule {
+    pub entries: Vec<String>,
+    pub command: String,
+}
+
+impl SandboxRule {
+    pub fn matches(&self, entry: &DesktopEntry) -> bool {
+        matches_entry(&self.entries, entry)
+    }
+
+    /// `None` if the command leaves a quote open.
+    pub fn wrap(&self, argv: Vec<String>) -> Option<Vec<String>> {
+        launch::wrap(&self.command, argv)
+    }
+}
+
+/// Adds an action named `name` running `exec` to the action menu of matching entries.
+#[derive(Debug, Clone, Deserialize)]
+pub struct EntryActionRule {
+    pub entries: Vec<String>,
+    pub name: String,
+    pub exec: String,
+}
+
+/// Opens the window of matching entries on `workspace`, e.g. Spotify always on `9`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct WorkspaceRule {
+    pub entries: Vec<String>,
+    pub workspace: String,
+}
+
+/// Launches and queries kept out of the query history, frecency and remembered modes.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct PrivacyConfig {
+    /// Entries whose launches aren't recorded, by ID or name glob.
+    #[serde(default)]
+    pub entries: Vec<String>,
+    /// Desktop actions whose launches aren't recorded, by name glob, e.g. `*Private*`.
+    #[serde(default)]
+    pub actions: Vec<String>,
+    /// Modes whose queries and selection aren't remembered, by keyword, e.g. `run`.
+    #[serde(default)]
+    pub modes: Vec<String>,
+    /// Starts incognito, recording nothing, as Alt+I toggles.
+    #[serde(default)]
+    pub incognito: bool,
+}
+
+impl PrivacyConfig {
+    pub fn excludes_entry(&self, entry: &DesktopEntry) -> bool {
+        matches_entry(&self.entries, entry)
+    }
+
+    pub fn excludes_action(&self, action: &DesktopAction) -> bool {
+        self.actions
+            .iter()
+            .any(|pattern| glob_match(pattern, &action.name))
+    }
+
+    pub fn excludes_mode(&self, keyword: &str) -> bool {
+        self.modes.iter().any(|mode| mode == keyword)
+    }
+}
+
+/// Whether any of the glob `patterns` matches the entry's ID or name.
+fn matches_entry(patterns: &[String], entry: &DesktopEntry) -> bool {
+    patterns
+        .iter()
+        .any(|pattern| glob_match(pattern, &entry.id) || glob_match(pattern, &entry.name))
 }
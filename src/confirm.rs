@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    style::{Color, Style, Stylize},
+    text::Line,
+    widgets::{Clear, Paragraph, Widget},
+};
+use serde::Deserialize;
+
+use crate::{
+    locale::{tr, tr_with},
+    theme,
+};
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct ConfirmConfig {
+    /// Seconds Enter stays disabled for destructive actions and elevated launches, so
+    /// they can't be confirmed by a stray keypress such as a second Enter.
+    pub countdown: u64,
+    pub border_color: Color,
+    pub message_color: Color,
+}
+
+impl Default for ConfirmConfig {
+    fn default() -> Self {
+        Self {
+            countdown: 2,
+            border_color: Color::Red,
+            message_color: Color::White,
+        }
+    }
+}
+
+/// What a key press did to a confirmation dialog.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Answer {
+    Confirmed,
+    Cancelled,
+    Pending,
+}
+
+/// A modal asking to confirm with Enter or cancel with Esc.
+#[derive(Debug)]
+pub struct ConfirmDialog {
+    title: String,
+    message: String,
+    ready_at: Instant,
+    border_color: Color,
+    message_color: Color,
+}
+
+impl ConfirmDialog {
+    pub fn new(title: String, message: String, config: &ConfirmConfig) -> Self {
+        Self {
+            title,
+            message,
+            ready_at: Instant::now(),
+            border_color: config.border_color,
+            message_color: config.message_color,
+        }
+    }
+
+    /// Keeps Enter disabled until `countdown` has passed.
+    pub fn with_countdown(mut self, countdown: Duration) -> Self {
+        self.ready_at = Instant::now() + countdown;
+        self
+    }
+
+    /// Time left until Enter confirms, if the countdown is still running.
+    pub fn remaining(&self) -> Option<Duration> {
+        let remaining = self.ready_at.saturating_duration_since(Instant::now());
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    pub fn handle_key(&self, key: KeyCode) -> Answer {
+        match key {
+            KeyCode::Enter if self.remaining().is_none() => Answer::Confirmed,
+            KeyCode::Esc => Answer::Cancelled,
+            _ => Answer::Pending,
+        }
+    }
+}
+
+impl Widget for &ConfirmDialog {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(4),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let hint = match self.remaining() {
+            Some(remaining) => tr_with(
+                "Enter to confirm in {seconds}s, Esc to cancel",
+                &[("seconds", &(remaining.as_secs() + 1).to_string())],
+            ),
+            None => tr("Enter to confirm, Esc to cancel").to_string(),
+        };
+        let dialog = Paragraph::new(vec![
+            Line::from(self.message.clone())
+                .bold()
+                .fg(self.message_color),
+            Line::styled(hint, theme::get().dim),
+        ])
+        .centered()
+        .block(
+            theme::get()
+                .block()
+                .title(self.title.clone())
+                .border_style(Style::new().fg(self.border_color)),
+        );
+        Clear.render(area, buf);
+        dialog.render(area, buf);
+    }
+}
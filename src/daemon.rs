@@ -0,0 +1,107 @@
+use std::{
+    fs,
+    io::{self, BufRead, BufReader, Write},
+    os::unix::net::{UnixListener, UnixStream},
+    path::PathBuf,
+    time::Duration,
+};
+
+use crate::paths;
+
+/// How long a client may take to send its command before it's ignored.
+const READ_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// What a client like `launcher --toggle` asks the daemon to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Command {
+    Toggle,
+    Show,
+    Hide,
+    Quit,
+}
+
+impl Command {
+    const ALL: [Command; 4] = [Self::Toggle, Self::Show, Self::Hide, Self::Quit];
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Toggle => "toggle",
+            Self::Show => "show",
+            Self::Hide => "hide",
+            Self::Quit => "quit",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|command| command.name() == name)
+    }
+}
+
+/// The socket of a launcher started with `--daemon`, which stays resident when closed
+/// and is shown again by clients instead of being started anew.
+#[derive(Debug)]
+pub struct Daemon {
+    listener: UnixListener,
+    path: PathBuf,
+}
+
+impl Daemon {
+    /// Listens on `paths::daemon_socket`, taking over the socket of a daemon that died.
+    /// Fails if another daemon is listening.
+    pub fn listen() -> io::Result<Self> {
+        let path = socket()?;
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(
+                io::ErrorKind::AddrInUse,
+                format!("a daemon is already listening on {}", path.display()),
+            ));
+        }
+        let _ = fs::remove_file(&path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let listener = UnixListener::bind(&path)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self { listener, path })
+    }
+
+    /// The commands clients sent since the last call.
+    pub fn take_commands(&self) -> Vec<Command> {
+        let mut commands = Vec::new();
+        while let Ok((stream, _)) = self.listener.accept() {
+            let _ = stream.set_read_timeout(Some(READ_TIMEOUT));
+            let mut line = String::new();
+            if BufReader::new(stream).read_line(&mut line).is_ok() {
+                commands.extend(Command::from_name(line.trim()));
+            }
+        }
+        commands
+    }
+}
+
+impl Drop for Daemon {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Sends `command` to the running daemon.
+pub fn send(command: Command) -> io::Result<()> {
+    let path = socket()?;
+    let mut stream = UnixStream::connect(&path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("no daemon listening on {}: {}", path.display(), error),
+        )
+    })?;
+    writeln!(stream, "{}", command.name())
+}
+
+fn socket() -> io::Result<PathBuf> {
+    paths::daemon_socket().ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::NotFound,
+            "neither $XDG_RUNTIME_DIR nor $HOME is set",
+        )
+    })
+}
@@ -1,108 +1,196 @@
-use crate::icons::{APPLICATION_ICON_MAP, CATEGORY_ICON_MAP};
-use ini::Ini;
-use ratatui::{
-    style::{Modifier, Style, Stylize},
-    text::{Line, Span},
-    widgets::ListItem,
+use crate::{
+    icons::{APPLICATION_ICON_MAP, CATEGORY_ICON_MAP},
+    interner,
+    sources::run,
+};
+use ini::{Ini, Properties};
+use serde::{Deserialize, Serialize};
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    sync::Arc,
 };
-use std::fs;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct DesktopEntry {
+    pub id: String,
+    /// The directory of the desktop file, interned as most entries share one of a few.
+    #[serde(deserialize_with = "interner::deserialize")]
+    pub dir: Arc<str>,
+    /// The name of the desktop file within `dir`, see `path`.
+    pub file_name: String,
     pub name: String,
+    pub comment: Option<String>,
+    /// The Exec line as written, field codes and quoting included, see `argv`.
     pub exec: String,
     pub terminal: bool,
-    pub icon: String,
+    /// Interned, as most entries share one of a few icons.
+    #[serde(deserialize_with = "interner::deserialize")]
+    pub icon: Arc<str>,
+    /// The `Categories` key, interned as entries share them.
+    #[serde(deserialize_with = "interner::deserialize_all")]
+    pub categories: Vec<Arc<str>>,
+    /// The `Icon` key, which `%i` passes on.
+    pub icon_name: Option<String>,
+    /// Additional ways to launch the entry from its `[Desktop Action]` sections.
+    pub actions: Vec<DesktopAction>,
+    /// The app has a single window and opening a second instance makes no sense.
+    pub single_main_window: bool,
+}
+
+/// A named alternative command for an entry, e.g. opening a private window.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DesktopAction {
+    pub name: String,
+    pub exec: String,
+}
+
+/// The keys deciding whether an entry is listed. They're kept apart from the entry
+/// as the outcome depends on the environment, see `is_shown`.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Visibility {
+    /// `NoDisplay` or `Hidden` is set.
+    hidden: bool,
+    not_show_in: Option<String>,
+    only_show_in: Option<String>,
+    try_exec: Option<String>,
 }
 
 impl DesktopEntry {
+    /// The entry in the desktop file at `path`, unless it's hidden or invalid.
     pub fn from_file(path: &str) -> Option<DesktopEntry> {
+        Self::parse(path)
+            .filter(|(_, visibility)| visibility.is_shown())
+            .map(|(entry, _)| entry)
+    }
+
+    /// The entry in the desktop file at `path` whether it's shown or not, along with
+    /// the keys deciding that.
+    pub fn parse(path: &str) -> Option<(DesktopEntry, Visibility)> {
         let content = match fs::read_to_string(path) {
             Ok(content) => content,
             Err(_) => return None,
         };
-        let ini = match Ini::load_from_str(&content) {
+        // Files saved by editors on Windows may start with a byte order mark.
+        let content = content.strip_prefix('\u{feff}').unwrap_or(&content);
+        let ini = match Ini::load_from_str(content) {
             Ok(ini) => ini,
             Err(_) => return None,
         };
         let section = ini.section(Some("Desktop Entry"));
         if let Some(section) = section {
-            let Some(name) = section.get("Name") else {
-                return None;
-            };
-            let Some(exec) = section.get("Exec") else {
-                return None;
-            };
+            let name = section.get("Name")?;
+            let exec = section.get("Exec")?;
             let terminal = match section.get("Terminal") {
                 Some("True") | Some("true") => true,
                 Some("False") | Some("false") => false,
+                // The key is optional, as flatpak exports often leave it out.
+                None => false,
                 Some(_) => {
                     return None;
                 }
-                None => {
-                    return None;
-                }
             };
-            let categories = match section.get("Categories") {
-                Some(categories) => categories
-                    .split(';')
-                    .map(|s| s.to_string())
-                    .collect::<Vec<String>>(),
-                None => Vec::new(),
-            };
-            let icon = Self::get_icon(name, categories);
-            return Some(Self {
+            let categories = section
+                .get("Categories")
+                .unwrap_or_default()
+                .split(';')
+                .filter(|category| !category.is_empty())
+                .map(interner::intern)
+                .collect::<Vec<Arc<str>>>();
+            let icon = Self::get_icon(name, &categories);
+            let actions = section
+                .get("Actions")
+                .unwrap_or_default()
+                .split(';')
+                .filter(|action| !action.is_empty())
+                .filter_map(|action| {
+                    let section = ini.section(Some(format!("Desktop Action {}", action)))?;
+                    Some(DesktopAction {
+                        name: section.get("Name")?.to_string(),
+                        exec: section.get("Exec")?.to_string(),
+                    })
+                })
+                .collect();
+            let (dir, file_name) = path.rsplit_once('/').unwrap_or(("", path));
+            let entry = Self {
+                id: file_name.to_string(),
+                dir: interner::intern(dir),
+                file_name: file_name.to_string(),
                 name: name.to_string(),
-                exec: exec
-                    .split_whitespace()
-                    .filter(|s| !s.starts_with('%'))
-                    .collect::<Vec<&str>>()
-                    .join(" "),
+                comment: section
+                    .get("Comment")
+                    .or(section.get("GenericName"))
+                    .map(String::from),
+                exec: exec.to_string(),
                 terminal,
                 icon,
-            });
+                categories,
+                icon_name: section.get("Icon").map(String::from),
+                actions,
+                single_main_window: section.get("SingleMainWindow") == Some("true"),
+            };
+            return Some((entry, Visibility::of(section)));
         }
         None
     }
 
-    pub fn get_highlighted_name(&self, filter: String) -> Line {
-        let mut spans = Vec::new();
-        let name = &self.name;
-        let indices = name
-            .to_lowercase()
-            .match_indices(&filter.to_lowercase())
-            .map(|(index, _)| index)
-            .collect::<Vec<usize>>();
-        spans.push(Span::from(format!(" {} ", self.icon)));
-        if filter.len() == 0 || indices.len() == 0 {
-            spans.push(Span::raw(name));
-            return Line::from(spans);
-        }
-        if indices[0] > 0 {
-            spans.push(Span::raw(&name[..indices[0]]));
+    /// The arguments the Exec line runs with `files` per the Desktop Entry spec: `%f` and `%u`
+    /// take the first file, `%F` and `%U` all of them, `%i` the icon, `%c` the name, `%k`
+    /// the desktop file and `%%` a percent sign, while deprecated codes are dropped.
+    /// `None` if a quote is left open.
+    pub fn argv(&self, files: &[String]) -> Option<Vec<String>> {
+        let mut argv = Vec::new();
+        for (arg, quoted) in split_exec(&self.exec)? {
+            if quoted {
+                argv.push(arg);
+                continue;
+            }
+            match arg.as_str() {
+                "%F" | "%U" => argv.extend(files.iter().cloned()),
+                "%f" | "%u" => argv.extend(files.first().cloned()),
+                "%i" => {
+                    if let Some(icon) = &self.icon_name {
+                        argv.push("--icon".to_string());
+                        argv.push(icon.clone());
+                    }
+                }
+                _ => argv.push(self.expand_codes(&arg, files)),
+            }
         }
-        let mut iteration = 0;
-        for index in indices.iter() {
-            let start = *index;
-            let end = start + filter.len();
-            spans.push(Span::raw(&name[start..end]).bold().reversed());
-            let next_index: usize;
-            if iteration < indices.len() - 1 {
-                iteration += 1;
-                next_index = indices[iteration];
-            } else {
-                next_index = name.len();
+        Some(argv)
+    }
+
+    /// `arg` with the field codes within it expanded.
+    fn expand_codes(&self, arg: &str, files: &[String]) -> String {
+        let mut expanded = String::new();
+        let mut chars = arg.chars();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                expanded.push(c);
+                continue;
+            }
+            match chars.next() {
+                Some('%') => expanded.push('%'),
+                Some('f' | 'u' | 'F' | 'U') => expanded.push_str(files.first().map_or("", |f| f)),
+                Some('c') => expanded.push_str(&self.name),
+                Some('k') => expanded.push_str(&self.path().to_string_lossy()),
+                Some('i') => expanded.push_str(self.icon_name.as_deref().unwrap_or_default()),
+                _ => {}
             }
-            spans.push(Span::raw(&name[end..next_index]));
         }
-        Line::from(spans)
+        expanded
+    }
+
+    /// The desktop file the entry was read from.
+    pub fn path(&self) -> PathBuf {
+        Path::new(&*self.dir).join(&self.file_name)
     }
 
-    fn get_icon(name: &str, categories: Vec<String>) -> String {
+    fn get_icon(name: &str, categories: &[Arc<str>]) -> Arc<str> {
         let mut i = 0;
         while i < APPLICATION_ICON_MAP.len() {
             if APPLICATION_ICON_MAP[i].0.to_lowercase() == name.to_lowercase() {
-                return APPLICATION_ICON_MAP[i].1.to_string();
+                return interner::intern(APPLICATION_ICON_MAP[i].1);
             }
             i += 1;
         }
@@ -110,12 +198,207 @@ impl DesktopEntry {
         while i < CATEGORY_ICON_MAP.len() {
             for category in categories.iter() {
                 if CATEGORY_ICON_MAP[i].0.to_lowercase() == category.to_lowercase() {
-                    return CATEGORY_ICON_MAP[i].1.to_string();
+                    return interner::intern(CATEGORY_ICON_MAP[i].1);
                 }
             }
             i += 1;
         }
-        " ".to_string()
+        interner::intern(" ")
         // "󰍹".to_string()
     }
 }
+
+impl Visibility {
+    fn of(section: &Properties) -> Self {
+        Self {
+            hidden: section.get("NoDisplay") == Some("true")
+                || section.get("Hidden") == Some("true"),
+            not_show_in: section.get("NotShowIn").map(String::from),
+            only_show_in: section.get("OnlyShowIn").map(String::from),
+            try_exec: section.get("TryExec").map(String::from),
+        }
+    }
+
+    /// Whether the entry is meant to be listed: not hidden, meant for the current desktop
+    /// and with its `TryExec` program installed.
+    pub fn is_shown(&self) -> bool {
+        if self.hidden {
+            return false;
+        }
+        let current = env::var("XDG_CURRENT_DESKTOP").unwrap_or_default();
+        let current = current
+            .split(':')
+            .filter(|desktop| !desktop.is_empty())
+            .collect::<Vec<&str>>();
+        let listed = |desktops: &Option<String>| {
+            desktops.as_ref().map(|desktops| {
+                desktops
+                    .split(';')
+                    .any(|desktop| current.contains(&desktop))
+            })
+        };
+        if listed(&self.not_show_in) == Some(true) || listed(&self.only_show_in) == Some(false) {
+            return false;
+        }
+        self.try_exec.as_deref().is_none_or(is_installed)
+    }
+}
+
+/// Whether `program`, a path or a name looked up in `$PATH`, can be run.
+fn is_installed(program: &str) -> bool {
+    if program.contains('/') {
+        return run::is_executable(Path::new(program));
+    }
+    env::var_os("PATH").is_some_and(|path| {
+        env::split_paths(&path).any(|dir| run::is_executable(&dir.join(program)))
+    })
+}
+
+/// Splits an Exec line into arguments, unquoting double quoted ones. Quoted arguments
+/// are marked, as field codes are only expanded outside quotes. Outside quotes a
+/// backslash escapes the next character like in a shell, which wine writes its paths
+/// with. `None` if a quote is left open.
+fn split_exec(exec: &str) -> Option<Vec<(String, bool)>> {
+    let mut args = Vec::new();
+    let mut arg = None::<(String, bool)>;
+    let mut chars = exec.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' => args.extend(arg.take()),
+            '"' => {
+                let (arg, quoted) = arg.get_or_insert_with(|| (String::new(), true));
+                *quoted = true;
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '`' | '$' | '\\') => arg.push(c),
+                            c => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                        },
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '\\' => {
+                let c = chars.next()?;
+                arg.get_or_insert_with(|| (String::new(), false)).0.push(c);
+            }
+            c => arg.get_or_insert_with(|| (String::new(), false)).0.push(c),
+        }
+    }
+    args.extend(arg);
+    Some(args)
+}
+
+/// Quotes `text` as an argument of an Exec line.
+fn quote_exec(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for c in text.chars() {
+        if matches!(c, '"' | '`' | '$' | '\\') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// `exec` opening `file`: in place of its first file or URL field code, or else after
+/// its arguments.
+pub fn exec_with_file(exec: &str, file: &str) -> String {
+    let code = exec
+        .split([' ', '\t'])
+        .find(|arg| matches!(*arg, "%f" | "%F" | "%u" | "%U"));
+    match code {
+        Some(code) => exec.replacen(code, &quote_exec(file), 1),
+        None => format!("{} {}", exec, quote_exec(file)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fmt::Write, path::PathBuf};
+
+    use super::*;
+
+    const FIXTURES: &str = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/desktop_entries"
+    );
+
+    /// What parsing the desktop file at `path` yields, a field per line, with the Exec
+    /// line expanded for a file with a space in its name.
+    fn describe(path: &Path) -> String {
+        let Some((entry, visibility)) = DesktopEntry::parse(path.to_str().unwrap()) else {
+            return "not an entry\n".to_string();
+        };
+        let mut text = String::new();
+        let fields: [(&str, &dyn std::fmt::Debug); 11] = [
+            ("id", &entry.id),
+            ("name", &entry.name),
+            ("comment", &entry.comment),
+            ("exec", &entry.exec),
+            ("argv", &entry.argv(&["/tmp/a b.png".to_string()])),
+            ("terminal", &entry.terminal),
+            ("icon", &entry.icon),
+            ("categories", &entry.categories),
+            ("icon_name", &entry.icon_name),
+            ("single_main_window", &entry.single_main_window),
+            ("visibility", &visibility),
+        ];
+        for (name, value) in fields {
+            writeln!(text, "{}: {:?}", name, value).unwrap();
+        }
+        for action in &entry.actions {
+            writeln!(text, "action: {:?} {:?}", action.name, action.exec).unwrap();
+        }
+        text
+    }
+
+    /// Compares the parse of each desktop file among the fixtures with the `.golden`
+    /// file beside it. `UPDATE_GOLDEN=1 cargo test` rewrites them after a deliberate
+    /// change.
+    #[test]
+    fn fixtures_match_golden_files() {
+        let mut fixtures = fs::read_dir(FIXTURES)
+            .unwrap()
+            .flatten()
+            .map(|file| file.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext == "desktop"))
+            .collect::<Vec<PathBuf>>();
+        fixtures.sort();
+        assert!(!fixtures.is_empty());
+        let update = env::var_os("UPDATE_GOLDEN").is_some();
+        let mut differing = Vec::new();
+        for fixture in fixtures {
+            let parsed = describe(&fixture);
+            let golden = fixture.with_extension("golden");
+            if update {
+                fs::write(&golden, &parsed).unwrap();
+            } else if fs::read_to_string(&golden).ok().as_ref() != Some(&parsed) {
+                differing.push(format!("{}:\n{}", golden.display(), parsed));
+            }
+        }
+        assert!(
+            differing.is_empty(),
+            "parsed differently than the golden files, run with UPDATE_GOLDEN=1 if intended:\n{}",
+            differing.join("\n")
+        );
+    }
+
+    #[test]
+    fn entries_share_interned_strings() {
+        let parse = |file| {
+            let path = Path::new(FIXTURES).join(file);
+            DesktopEntry::parse(path.to_str().unwrap()).unwrap().0
+        };
+        let (a, b) = (parse("firefox.desktop"), parse("firefox.desktop"));
+        assert!(Arc::ptr_eq(&a.icon, &b.icon));
+        assert!(Arc::ptr_eq(&a.categories[0], &b.categories[0]));
+        assert!(Arc::ptr_eq(&a.dir, &parse("htop.desktop").dir));
+        assert_eq!(a.path(), Path::new(FIXTURES).join("firefox.desktop"));
+    }
+}
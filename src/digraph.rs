@@ -0,0 +1,87 @@
+/// Letters with a diacritic, as vim's RFC 1345 digraphs: the mark typed after the base
+/// letter, the base letters taking it, and the letters they make in the same order.
+const ACCENTED: [(char, &str, &str); 13] = [
+    ('!', "AaEeIiNnOoUu", "ÀàÈèÌìǸǹÒòÙù"),
+    (
+        '\'',
+        "AaCcEeGgIiLlNnOoRrSsUuYyZz",
+        "ÁáĆćÉéǴǵÍíĹĺŃńÓóŔŕŚśÚúÝýŹź",
+    ),
+    ('>', "AaCcEeGgHhIiJjOoSsUuWwYy", "ÂâĈĉÊêĜĝĤĥÎîĴĵÔôŜŝÛûŴŵŶŷ"),
+    ('?', "AaIiNnOoUu", "ÃãĨĩÑñÕõŨũ"),
+    ('-', "AaEeIiOoUuYy", "ĀāĒēĪīŌōŪūȲȳ"),
+    ('(', "AaEeGgIiOoUu", "ĂăĔĕĞğĬĭŎŏŬŭ"),
+    ('.', "AaCcEeGgIOoZz", "ȦȧĊċĖėĠġİȮȯŻż"),
+    (':', "AaEeIiOoUuYy", "ÄäËëÏïÖöÜüŸÿ"),
+    ('0', "AaUu", "ÅåŮů"),
+    ('"', "OoUu", "ŐőŰű"),
+    (
+        '<',
+        "AaCcDdEeGgHhIijKkLlNnOoRrSsTtUuZz",
+        "ǍǎČčĎďĚěǦǧȞȟǏǐǰǨǩĽľŇňǑǒŘřŠšŤťǓǔŽž",
+    ),
+    (',', "CcEeGgKkLlNnRrSsTt", "ÇçȨȩĢģĶķĻļŅņŖŗŞşŢţ"),
+    (';', "AaEeIiOoUu", "ĄąĘęĮįǪǫŲų"),
+];
+
+/// Digraphs for the other letters and symbols of European languages.
+const OTHERS: [(&str, char); 36] = [
+    ("ss", 'ß'),
+    ("ae", 'æ'),
+    ("AE", 'Æ'),
+    ("oe", 'œ'),
+    ("OE", 'Œ'),
+    ("aa", 'å'),
+    ("AA", 'Å'),
+    ("o/", 'ø'),
+    ("O/", 'Ø'),
+    ("l/", 'ł'),
+    ("L/", 'Ł'),
+    ("d/", 'đ'),
+    ("D/", 'Đ'),
+    ("i.", 'ı'),
+    ("th", 'þ'),
+    ("TH", 'Þ'),
+    ("dh", 'ð'),
+    ("DH", 'Ð'),
+    ("Eu", '€'),
+    ("Pd", '£'),
+    ("Ye", '¥'),
+    ("Ct", '¢'),
+    ("Co", '©'),
+    ("Rg", '®'),
+    ("DG", '°'),
+    ("SE", '§'),
+    ("My", 'µ'),
+    ("<<", '«'),
+    (">>", '»'),
+    ("!I", '¡'),
+    ("?I", '¿'),
+    ("+-", '±'),
+    ("*X", '×'),
+    ("-:", '÷'),
+    ("12", '½'),
+    ("14", '¼'),
+];
+
+/// The character the digraph `first` `second` stands for, like `ä` for `a:`. As in vim,
+/// the two may be typed in either order.
+pub fn compose(first: char, second: char) -> Option<char> {
+    lookup(first, second).or_else(|| lookup(second, first))
+}
+
+fn lookup(first: char, second: char) -> Option<char> {
+    let accented = ACCENTED
+        .iter()
+        .find(|(mark, _, _)| *mark == second)
+        .and_then(|(_, bases, letters)| {
+            let position = bases.chars().position(|base| base == first)?;
+            letters.chars().nth(position)
+        });
+    accented.or_else(|| {
+        OTHERS
+            .iter()
+            .find(|(digraph, _)| digraph.chars().eq([first, second]))
+            .map(|(_, composed)| *composed)
+    })
+}
@@ -0,0 +1,106 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    desktop_entry::{DesktopEntry, Visibility},
+    paths,
+};
+
+/// Bumped whenever the cached types change, dropping caches written before.
+const VERSION: u32 = 2;
+
+/// Desktop files parsed on earlier runs by path, reused while their modification time
+/// is unchanged. Stored with bincode in `$XDG_CACHE_HOME/launcher/entries.bin`.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EntryCache {
+    version: u32,
+    files: HashMap<PathBuf, CachedFile>,
+    /// The files looked up this run, the others being dropped on save.
+    #[serde(skip)]
+    used: HashSet<PathBuf>,
+    /// How many files were parsed as they weren't cached or changed.
+    #[serde(skip)]
+    pub parsed: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedFile {
+    modified: SystemTime,
+    /// `None` for files that aren't valid entries.
+    entry: Option<(DesktopEntry, Visibility)>,
+}
+
+impl EntryCache {
+    pub fn load() -> Self {
+        file()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|content| bincode::deserialize::<Self>(&content).ok())
+            .filter(|cache| cache.version == VERSION)
+            .unwrap_or(Self {
+                version: VERSION,
+                ..Self::default()
+            })
+    }
+
+    /// The entry in the desktop file at `path` unless it's hidden or invalid, parsing
+    /// the file only when it changed since it was cached. Whether it's shown is decided
+    /// anew, as that depends on the environment.
+    pub fn entry(&mut self, path: &Path) -> Option<DesktopEntry> {
+        self.used.insert(path.to_path_buf());
+        let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+        let cached = match (self.files.get(path), &modified) {
+            (Some(cached), Ok(modified)) if cached.modified == *modified => cached,
+            _ => {
+                self.parsed += 1;
+                let entry = path.to_str().and_then(DesktopEntry::parse);
+                let Ok(modified) = modified else {
+                    return entry
+                        .filter(|(_, visibility)| visibility.is_shown())
+                        .map(|(entry, _)| entry);
+                };
+                self.files
+                    .entry(path.to_path_buf())
+                    .insert_entry(CachedFile { modified, entry })
+                    .into_mut()
+            }
+        };
+        let (entry, visibility) = cached.entry.as_ref()?;
+        if !visibility.is_shown() {
+            return None;
+        }
+        Some(entry.clone())
+    }
+
+    /// Writes the files looked up this run, if any were parsed or dropped. Written to a
+    /// temporary file first, as other instances may be reading it.
+    pub fn save(mut self) {
+        let count = self.files.len();
+        self.files.retain(|path, _| self.used.contains(path));
+        if self.parsed == 0 && self.files.len() == count {
+            return;
+        }
+        let Some(path) = file() else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(content) = bincode::serialize(&self) else {
+            return;
+        };
+        let temporary = path.with_extension("tmp");
+        if fs::write(&temporary, content).is_ok() {
+            let _ = fs::rename(&temporary, path);
+        }
+    }
+}
+
+fn file() -> Option<PathBuf> {
+    paths::cache_home().map(|dir| dir.join("launcher/entries.bin"))
+}
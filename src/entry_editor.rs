@@ -0,0 +1,118 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    text::{Line, Span},
+    widgets::{Clear, Paragraph, Widget},
+};
+
+use crate::{
+    desktop_entry::DesktopEntry,
+    locale::{tr, tr_with},
+    overrides::Override,
+    theme,
+};
+
+const LABELS: [&str; 2] = ["Name", "Icon"];
+
+/// What a key press did to the entry editor.
+#[derive(Debug, Clone)]
+pub enum EditorAnswer {
+    Saved(Override),
+    Cancelled,
+    Pending,
+}
+
+/// A modal editing the name and icon an entry is shown with. A field left empty shows
+/// the desktop file's own value.
+#[derive(Debug)]
+pub struct EntryEditor {
+    pub id: String,
+    /// The name and icon typed, prefilled with the current override.
+    values: [String; 2],
+    /// Shown dim in empty fields.
+    placeholders: [String; 2],
+    focused: usize,
+}
+
+impl EntryEditor {
+    pub fn new(entry: &DesktopEntry, current: Override) -> Self {
+        Self {
+            id: entry.id.clone(),
+            values: [
+                current.name.unwrap_or_default(),
+                current.icon.unwrap_or_default(),
+            ],
+            placeholders: [entry.name.clone(), entry.icon.to_string()],
+            focused: 0,
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> EditorAnswer {
+        match key {
+            KeyCode::Tab | KeyCode::Down | KeyCode::Up | KeyCode::BackTab => {
+                self.focused = 1 - self.focused;
+            }
+            KeyCode::Char(c) => self.values[self.focused].push(c),
+            KeyCode::Backspace => {
+                self.values[self.focused].pop();
+            }
+            KeyCode::Enter => {
+                let [name, icon] = self.values.clone().map(|value| {
+                    let value = value.trim().to_string();
+                    (!value.is_empty()).then_some(value)
+                });
+                return EditorAnswer::Saved(Override { name, icon });
+            }
+            KeyCode::Esc => return EditorAnswer::Cancelled,
+            _ => {}
+        }
+        EditorAnswer::Pending
+    }
+}
+
+impl Widget for &EntryEditor {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(LABELS.len() as u16 + 2),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let theme = theme::get();
+        let lines = LABELS
+            .iter()
+            .enumerate()
+            .map(|(i, label)| {
+                let mut spans = vec![Span::styled(format!(" {:<6}", tr(label)), theme.dim)];
+                if self.values[i].is_empty() {
+                    spans.push(Span::styled(
+                        self.placeholders[i].clone(),
+                        theme.placeholder,
+                    ));
+                } else {
+                    spans.push(Span::raw(self.values[i].clone()));
+                }
+                if i == self.focused {
+                    spans.push(Span::raw(theme.cursor.clone()));
+                }
+                Line::from(spans)
+            })
+            .collect::<Vec<Line>>();
+        let hint = ["Tab switch", "Enter save", "Esc cancel"]
+            .map(tr)
+            .join(&theme.hint_separator);
+        let block = theme
+            .block()
+            .title(tr_with("Edit {id}", &[("id", &self.id)]))
+            .title_bottom(Line::styled(hint, theme.dim));
+        Clear.render(area, buf);
+        Paragraph::new(lines).block(block).render(area, buf);
+    }
+}
@@ -0,0 +1,80 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::{desktop_entry::DesktopEntry, paths};
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// When each desktop file ID was first discovered, persisted across runs.
+#[derive(Debug, Default)]
+pub struct FirstSeen {
+    path: Option<PathBuf>,
+    timestamps: HashMap<String, u64>,
+    /// Set when there was no store to load.
+    first_run: bool,
+}
+
+impl FirstSeen {
+    pub fn load() -> Self {
+        let path = paths::state_dir().map(|dir| dir.join("first_seen.json"));
+        let existing = path
+            .as_deref()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<HashMap<String, u64>>(&content).ok());
+        Self {
+            path,
+            first_run: existing.is_none(),
+            timestamps: existing.unwrap_or_default(),
+        }
+    }
+
+    /// Records the entries found by the startup scan that weren't seen before. On the
+    /// very first run they are recorded as seen long ago, so nothing is flagged as new.
+    pub fn observe_scanned(&mut self, entries: &[DesktopEntry]) {
+        let timestamp = if self.first_run { 0 } else { now() };
+        self.observe(entries, timestamp);
+    }
+
+    pub fn observe(&mut self, entries: &[DesktopEntry], timestamp: u64) {
+        let mut changed = false;
+        for entry in entries {
+            if !self.timestamps.contains_key(&entry.id) {
+                self.timestamps.insert(entry.id.clone(), timestamp);
+                changed = true;
+            }
+        }
+        if changed {
+            self.save();
+        }
+    }
+
+    /// Whether `entry` was first seen within the last `days` days.
+    pub fn is_new(&self, entry: &DesktopEntry, days: u64) -> bool {
+        self.timestamps
+            .get(&entry.id)
+            .is_some_and(|&timestamp| now().saturating_sub(timestamp) < days * SECONDS_PER_DAY)
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&self.timestamps) {
+            let _ = fs::write(path, content);
+        }
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+}
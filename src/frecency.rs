@@ -0,0 +1,189 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{first_seen::now, paths, vault};
+
+const SECONDS_PER_DAY: f64 = 24.0 * 60.0 * 60.0;
+
+/// A launch count that halves every half-life, stored with the time it was last
+/// brought up to date so it can be decayed lazily.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Usage {
+    pub score: f64,
+    pub updated: u64,
+}
+
+impl Usage {
+    /// The score decayed up to `now`.
+    pub fn at(&self, now: u64, half_life_days: f64) -> f64 {
+        decay(self.score, now.saturating_sub(self.updated), half_life_days)
+    }
+
+    /// Decays the score up to `now` and counts one more launch.
+    pub fn bump(&mut self, now: u64, half_life_days: f64) {
+        self.add(1.0, now, half_life_days);
+    }
+
+    /// Counts `launches` that happened at `time`, which may be older than the last
+    /// update when importing.
+    pub fn add(&mut self, launches: f64, time: u64, half_life_days: f64) {
+        if time >= self.updated {
+            self.score = self.at(time, half_life_days) + launches;
+            self.updated = time;
+        } else {
+            self.score += decay(launches, self.updated - time, half_life_days);
+        }
+    }
+}
+
+/// Halves `score` for every `half_life_days` in `elapsed_seconds`. A half-life of zero
+/// or less disables decay.
+pub fn decay(score: f64, elapsed_seconds: u64, half_life_days: f64) -> f64 {
+    if half_life_days <= 0.0 {
+        return score;
+    }
+    let half_lives = elapsed_seconds as f64 / (half_life_days * SECONDS_PER_DAY);
+    score * 0.5f64.powf(half_lives)
+}
+
+/// How often and how recently each desktop file ID was launched, persisted across runs.
+#[derive(Debug, Default)]
+pub struct Frecency {
+    path: Option<PathBuf>,
+    usage: HashMap<String, Usage>,
+    half_life_days: f64,
+}
+
+impl Frecency {
+    /// Reads `$XDG_DATA_HOME/launcher/history`, or the `frecency.json` in the state
+    /// directory it used to be kept in until the first launch moves it.
+    pub fn load(half_life_days: f64) -> Self {
+        let path = paths::data_dir().map(|dir| dir.join("history"));
+        let legacy = paths::state_dir().map(|dir| dir.join("frecency.json"));
+        let usage = [&path, &legacy]
+            .into_iter()
+            .flatten()
+            .find_map(|path| vault::read(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self {
+            path,
+            usage,
+            half_life_days,
+        }
+    }
+
+    pub fn record(&mut self, id: &str) {
+        self.usage
+            .entry(id.to_string())
+            .or_insert(Usage {
+                score: 0.0,
+                updated: now(),
+            })
+            .bump(now(), self.half_life_days);
+        self.save();
+    }
+
+    /// Merges `(id, launches, time)` records from elsewhere into the store and returns
+    /// how many there were.
+    pub fn import(&mut self, records: impl IntoIterator<Item = (String, f64, u64)>) -> usize {
+        let mut count = 0;
+        for (id, launches, time) in records {
+            self.usage
+                .entry(id)
+                .or_insert(Usage {
+                    score: 0.0,
+                    updated: time,
+                })
+                .add(launches, time, self.half_life_days);
+            count += 1;
+        }
+        self.save();
+        count
+    }
+
+    pub fn score(&self, id: &str) -> f64 {
+        self.usage
+            .get(id)
+            .map(|usage| usage.at(now(), self.half_life_days))
+            .unwrap_or_default()
+    }
+
+    fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let Ok(content) = serde_json::to_string(&self.usage) else {
+            return;
+        };
+        let _ = vault::write(path, &content);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 24 * 60 * 60;
+
+    #[test]
+    fn score_halves_every_half_life() {
+        assert_eq!(decay(8.0, 0, 30.0), 8.0);
+        assert!((decay(8.0, 30 * DAY, 30.0) - 4.0).abs() < 1e-9);
+        assert!((decay(8.0, 90 * DAY, 30.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn zero_half_life_disables_decay() {
+        assert_eq!(decay(5.0, 365 * DAY, 0.0), 5.0);
+    }
+
+    #[test]
+    fn bump_decays_before_counting() {
+        let mut usage = Usage {
+            score: 2.0,
+            updated: 0,
+        };
+        usage.bump(7 * DAY, 7.0);
+        assert!((usage.score - 2.0).abs() < 1e-9);
+        assert_eq!(usage.updated, 7 * DAY);
+    }
+
+    #[test]
+    fn older_launches_are_decayed_to_the_last_update() {
+        let mut usage = Usage {
+            score: 1.0,
+            updated: 14 * DAY,
+        };
+        usage.add(4.0, 0, 7.0);
+        assert!((usage.score - 2.0).abs() < 1e-9);
+        assert_eq!(usage.updated, 14 * DAY);
+    }
+
+    #[test]
+    fn heavy_old_use_sinks_below_light_recent_use() {
+        let last_year = Usage {
+            score: 200.0,
+            updated: 0,
+        };
+        let this_week = Usage {
+            score: 3.0,
+            updated: 360 * DAY,
+        };
+        let now = 365 * DAY;
+        assert!(last_year.at(now, 30.0) < this_week.at(now, 30.0));
+    }
+
+    #[test]
+    fn clock_going_backwards_does_not_grow_scores() {
+        let usage = Usage {
+            score: 4.0,
+            updated: 10 * DAY,
+        };
+        assert_eq!(usage.at(DAY, 30.0), 4.0);
+    }
+}
@@ -0,0 +1,75 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{paths, vault};
+
+const MAX_ITEMS: usize = 100;
+
+/// Previously launched queries or app names, newest last.
+#[derive(Debug, Default)]
+pub struct QueryHistory {
+    path: Option<PathBuf>,
+    items: Vec<String>,
+}
+
+impl QueryHistory {
+    /// The queries something was launched from, used for autosuggestions.
+    pub fn load() -> Self {
+        Self::open("queries")
+    }
+
+    /// The names of launched apps, for the recently launched sections.
+    pub fn launches() -> Self {
+        Self::open("launches")
+    }
+
+    fn open(name: &str) -> Self {
+        let path = paths::state_dir().map(|dir| dir.join(name));
+        let items = path
+            .as_deref()
+            .and_then(|path| vault::read(path).ok())
+            .map(|content| content.lines().map(String::from).collect())
+            .unwrap_or_default();
+        Self { path, items }
+    }
+
+    pub fn record(&mut self, item: &str) {
+        if item.is_empty() {
+            return;
+        }
+        self.items.retain(|existing| existing != item);
+        self.items.push(item.to_string());
+        let overflow = self.items.len().saturating_sub(MAX_ITEMS);
+        self.items.drain(..overflow);
+        if let Some(path) = &self.path {
+            let _ = Self::save(path, &self.items);
+        }
+    }
+
+    /// The most recent item that extends `prefix`, ignoring case.
+    pub fn suggest(&self, prefix: &str) -> Option<&str> {
+        if prefix.is_empty() {
+            return None;
+        }
+        let prefix = prefix.to_lowercase();
+        self.items
+            .iter()
+            .rev()
+            .find(|item| item.len() > prefix.len() && item.to_lowercase().starts_with(&prefix))
+            .map(String::as_str)
+    }
+
+    /// Items from newest to oldest.
+    pub fn recent(&self) -> impl Iterator<Item = &str> {
+        self.items.iter().rev().map(String::as_str)
+    }
+
+    fn save(path: &Path, items: &[String]) -> std::io::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        vault::write(path, &items.join("\n"))
+    }
+}
@@ -0,0 +1,34 @@
+use std::process::{Command, Stdio};
+
+use serde::Deserialize;
+
+use crate::{launch, log};
+
+/// Shell commands run around every launch. `{id}`, `{name}` and `{cmd}` expand to the
+/// launched entry's desktop file ID, name and command line, each quoted as one word. The
+/// command line has its arguments quoted in turn, so `sh -c {cmd}` runs it as launched.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre: Option<String>,
+    pub post: Option<String>,
+}
+
+/// Runs `template` through `sh` and waits for it. Failures are logged, never fatal,
+/// so a broken hook can't keep an app from launching.
+pub fn run(template: Option<&str>, values: &[(&str, String)]) {
+    let Some(template) = template else {
+        return;
+    };
+    let command = launch::fill(template, values);
+    let status = Command::new("sh")
+        .args(["-c", &command])
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => log::info(&format!("hook `{}` failed with {}", command, status)),
+        Err(error) => log::info(&format!("hook `{}` failed to start: {}", command, error)),
+    }
+}
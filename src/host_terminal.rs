@@ -0,0 +1,180 @@
+use std::fs;
+
+/// Terminal emulators, by `comm` name (at most 15 characters, hence `gnome-terminal-`).
+const TERMINALS: &[&str] = &[
+    "alacritty",
+    "contour",
+    "foot",
+    "ghostty",
+    "gnome-terminal-",
+    "kgx",
+    "kitty",
+    "konsole",
+    "lxterminal",
+    "ptyxis-agent",
+    "rio",
+    "sakura",
+    "st",
+    "terminator",
+    "termite",
+    "tilix",
+    "urxvt",
+    "wezterm-gui",
+    "xfce4-terminal",
+    "xterm",
+];
+
+/// Multiplexers, whose panes must not be closed under the user.
+const MULTIPLEXERS: &[&str] = &["tmux: server", "tmux", "screen", "SCREEN", "zellij"];
+
+const SHELLS: &[&str] = &[
+    "ash", "bash", "csh", "dash", "elvish", "fish", "ksh", "mksh", "nu", "sh", "tcsh", "xonsh",
+    "zsh",
+];
+
+/// What a process in the launcher's ancestry is, judged by its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Shell,
+    Terminal,
+    Multiplexer,
+    Other,
+}
+
+fn kind(name: &str) -> Kind {
+    // Login shells are named with a leading dash, e.g. `-zsh`.
+    let name = name.strip_prefix('-').unwrap_or(name);
+    if SHELLS.contains(&name) {
+        Kind::Shell
+    } else if TERMINALS.contains(&name) {
+        Kind::Terminal
+    } else if MULTIPLEXERS.contains(&name) {
+        Kind::Multiplexer
+    } else {
+        Kind::Other
+    }
+}
+
+/// The parent and name of processes, read from `/proc` or made up in tests.
+pub trait ProcessTree {
+    fn parent(&self, pid: u32) -> Option<u32>;
+    fn name(&self, pid: u32) -> Option<String>;
+}
+
+#[derive(Debug, Default)]
+pub struct ProcFs;
+
+impl ProcessTree for ProcFs {
+    fn parent(&self, pid: u32) -> Option<u32> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        // The name in parentheses may contain spaces, so fields count from its end.
+        let fields = &stat[stat.rfind(')')? + 1..];
+        fields.split_whitespace().nth(1)?.parse().ok()
+    }
+
+    fn name(&self, pid: u32) -> Option<String> {
+        let comm = fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?;
+        Some(comm.trim_end().to_string())
+    }
+}
+
+/// The process to kill so the terminal window `pid` runs in closes once an app was
+/// launched from it. Walks past nested shells to the outermost one, or takes the
+/// terminal itself when the launcher is its direct child. `None` inside a multiplexer,
+/// whose session would go with it, or when no terminal is found above the shells.
+pub fn process_to_close(tree: &impl ProcessTree, pid: u32) -> Option<u32> {
+    let mut outermost_shell = None;
+    let mut current = tree.parent(pid)?;
+    while current > 1 {
+        match kind(&tree.name(current)?) {
+            Kind::Shell => outermost_shell = Some(current),
+            Kind::Terminal => return Some(outermost_shell.unwrap_or(current)),
+            Kind::Multiplexer | Kind::Other => return None,
+        }
+        current = tree.parent(current)?;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    /// A process tree given as `(pid, parent, name)`, with the launcher as pid 100.
+    struct Tree(HashMap<u32, (u32, &'static str)>);
+
+    impl Tree {
+        fn new(processes: &[(u32, u32, &'static str)]) -> Self {
+            let mut tree = processes
+                .iter()
+                .map(|&(pid, parent, name)| (pid, (parent, name)))
+                .collect::<HashMap<u32, (u32, &str)>>();
+            tree.insert(1, (0, "systemd"));
+            Self(tree)
+        }
+    }
+
+    impl ProcessTree for Tree {
+        fn parent(&self, pid: u32) -> Option<u32> {
+            self.0.get(&pid).map(|(parent, _)| *parent)
+        }
+
+        fn name(&self, pid: u32) -> Option<String> {
+            self.0.get(&pid).map(|(_, name)| name.to_string())
+        }
+    }
+
+    #[test]
+    fn closes_the_shell_of_a_terminal() {
+        let tree = Tree::new(&[(10, 1, "foot"), (20, 10, "bash"), (100, 20, "launcher")]);
+        assert_eq!(process_to_close(&tree, 100), Some(20));
+    }
+
+    #[test]
+    fn closes_the_terminal_running_the_launcher_directly() {
+        let tree = Tree::new(&[(10, 1, "alacritty"), (100, 10, "launcher")]);
+        assert_eq!(process_to_close(&tree, 100), Some(10));
+    }
+
+    #[test]
+    fn closes_the_outermost_of_nested_shells() {
+        let tree = Tree::new(&[
+            (10, 1, "kitty"),
+            (20, 10, "-zsh"),
+            (30, 20, "bash"),
+            (40, 30, "sh"),
+            (100, 40, "launcher"),
+        ]);
+        assert_eq!(process_to_close(&tree, 100), Some(20));
+    }
+
+    #[test]
+    fn leaves_multiplexers_alone() {
+        for multiplexer in ["tmux: server", "screen", "zellij"] {
+            let tree = Tree::new(&[
+                (10, 1, "foot"),
+                (15, 10, "zsh"),
+                (17, 15, multiplexer),
+                (20, 17, "zsh"),
+                (100, 20, "launcher"),
+            ]);
+            assert_eq!(process_to_close(&tree, 100), None, "{}", multiplexer);
+        }
+    }
+
+    #[test]
+    fn leaves_shells_without_a_terminal_alone() {
+        let tree = Tree::new(&[(10, 1, "sshd"), (20, 10, "bash"), (100, 20, "launcher")]);
+        assert_eq!(process_to_close(&tree, 100), None);
+        let tree = Tree::new(&[(20, 1, "bash"), (100, 20, "launcher")]);
+        assert_eq!(process_to_close(&tree, 100), None);
+    }
+
+    #[test]
+    fn stops_at_unknown_processes() {
+        let tree = Tree::new(&[(20, 10, "bash"), (100, 20, "launcher")]);
+        assert_eq!(process_to_close(&tree, 100), None);
+    }
+}
@@ -0,0 +1,84 @@
+use std::{collections::BTreeMap, mem, sync::Arc, time::Duration};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::Line,
+    widgets::{Clear, Paragraph, Widget},
+};
+
+use crate::{
+    desktop_entry::DesktopEntry,
+    sources::{format_size, Item},
+    theme,
+};
+
+/// Timings and sizes shown in the performance overlay, toggled with F12.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    pub frame_time: Duration,
+    /// Time taken by the last filter update, including matching and sorting.
+    pub match_time: Duration,
+    /// Results of the last query in each mode visited, by keyword or `apps`.
+    pub results: BTreeMap<String, usize>,
+    /// Approximate bytes held by the desktop entries and the current mode's items.
+    pub index_size: usize,
+}
+
+impl Metrics {
+    /// Estimates the memory of the application index and the loaded items. Interned icons,
+    /// categories and directories are left out.
+    pub fn measure_index(&mut self, entries: &[DesktopEntry], items: &[Item]) {
+        let entries_size = entries
+            .iter()
+            .map(|entry| {
+                mem::size_of::<DesktopEntry>()
+                    + entry.id.capacity()
+                    + entry.file_name.capacity()
+                    + entry.categories.capacity() * mem::size_of::<Arc<str>>()
+                    + entry.name.capacity()
+                    + entry.comment.as_ref().map_or(0, String::capacity)
+                    + entry.exec.capacity()
+                    + entry
+                        .actions
+                        .iter()
+                        .map(|action| action.name.capacity() + action.exec.capacity())
+                        .sum::<usize>()
+            })
+            .sum::<usize>();
+        let items_size = items
+            .iter()
+            .map(|item| {
+                mem::size_of::<Item>()
+                    + item.id.capacity()
+                    + item.name.capacity()
+                    + item.comment.as_ref().map_or(0, String::capacity)
+                    + item.detail.as_ref().map_or(0, String::capacity)
+                    + item.path.as_ref().map_or(0, |path| path.as_os_str().len())
+            })
+            .sum::<usize>();
+        self.index_size = entries_size + items_size;
+    }
+}
+
+impl Widget for &Metrics {
+    /// Draws the overlay in the top right corner of `area`.
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let theme = theme::get();
+        let mut lines = vec![
+            Line::from(format!("frame  {:.2?}", self.frame_time)),
+            Line::from(format!("match  {:.2?}", self.match_time)),
+            Line::from(format!("index  {}", format_size(self.index_size as u64))),
+        ];
+        for (mode, count) in &self.results {
+            lines.push(Line::styled(format!("{:<6} {}", mode, count), theme.dim));
+        }
+        let width = 26.min(area.width);
+        let height = (lines.len() as u16 + 2).min(area.height);
+        let area = Rect::new(area.right() - width, area.y, width, height);
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(theme.block().title("perf").style(theme.accent))
+            .render(area, buf);
+    }
+}
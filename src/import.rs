@@ -0,0 +1,123 @@
+use std::{
+    collections::HashMap,
+    env, fs, io,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use crate::{config::Config, first_seen::now, frecency::Frecency, paths};
+
+/// Launch records as `(id, launches, time)`, ready for `Frecency::import`.
+type Records = Vec<(String, f64, u64)>;
+
+/// Runs `launcher import --from <rofi|ulauncher|zsh-history>`, seeding the frecency
+/// store from another launcher's usage counts.
+pub fn run(args: &[String], config: &Config) -> io::Result<()> {
+    let from = match args {
+        [flag, from] if flag == "--from" => from.as_str(),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "usage: launcher import --from rofi|ulauncher|zsh-history",
+            ))
+        }
+    };
+    let records = match from {
+        "rofi" => rofi(),
+        "ulauncher" => ulauncher(),
+        "zsh-history" => zsh_history(),
+        _ => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unknown source {}", from),
+            ))
+        }
+    };
+    let count = Frecency::load(config.frecency_half_life).import(records);
+    println!("imported {} entries from {}", count, from);
+    Ok(())
+}
+
+/// rofi keeps `<count> <desktop id>` lines for drun and `<count> <command>` for run.
+fn rofi() -> Records {
+    let mut records = Vec::new();
+    for (file, prefix) in [("rofi3.druncache", ""), ("rofi3.runcache", "run:")] {
+        let Some(path) = paths::cache_home().map(|dir| dir.join(file)) else {
+            continue;
+        };
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let time = modified(&path);
+        for line in content.lines() {
+            let Some((count, id)) = line.trim().split_once(' ') else {
+                continue;
+            };
+            if let Ok(count) = count.parse::<f64>() {
+                records.push((format!("{}{}", prefix, id), count, time));
+            }
+        }
+    }
+    records
+}
+
+/// Ulauncher counts starts per desktop file path in a JSON object.
+fn ulauncher() -> Records {
+    let candidates = [
+        paths::cache_home().map(|dir| dir.join("ulauncher_cache/app_starts.json")),
+        paths::data_home().map(|dir| dir.join("ulauncher/app_starts.json")),
+    ];
+    let Some(path) = candidates.iter().flatten().find(|path| path.exists()) else {
+        return Vec::new();
+    };
+    let time = modified(path);
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str::<HashMap<String, f64>>(&content).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(path, count)| {
+            let id = Path::new(&path).file_name()?.to_string_lossy().to_string();
+            Some((id, count, time))
+        })
+        .collect()
+}
+
+/// Every command in the zsh history counts as one launch of its program, at the time
+/// recorded with extended history or else the file's modification time.
+fn zsh_history() -> Records {
+    let Some(path) = env::var_os("HISTFILE")
+        .map(PathBuf::from)
+        .or_else(|| paths::home().map(|home| home.join(".zsh_history")))
+    else {
+        return Vec::new();
+    };
+    let Ok(content) = fs::read(&path) else {
+        return Vec::new();
+    };
+    let fallback = modified(&path);
+    String::from_utf8_lossy(&content)
+        .lines()
+        .filter_map(|line| {
+            let (time, command) = match line.strip_prefix(": ") {
+                Some(extended) => {
+                    let (meta, command) = extended.split_once(';')?;
+                    let time = meta.split(':').next()?.parse().unwrap_or(fallback);
+                    (time, command)
+                }
+                None => (fallback, line),
+            };
+            let program = command.split_whitespace().next()?;
+            Some((format!("run:{}", program), 1.0, time))
+        })
+        .collect()
+}
+
+fn modified(path: &Path) -> u64 {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|duration| duration.as_secs())
+        .unwrap_or_else(now)
+}
@@ -0,0 +1,181 @@
+use ratatui::text::Span;
+
+/// The text typed into the filter and the cursor in it.
+#[derive(Debug, Clone, Default)]
+pub struct InputState {
+    pub text: String,
+    /// How many characters are before the cursor.
+    pub cursor: usize,
+}
+
+impl InputState {
+    /// Replaces the text, with the cursor at its end.
+    pub fn set(&mut self, text: String) {
+        self.text = text;
+        self.move_to_end();
+    }
+
+    pub fn at_end(&self) -> bool {
+        self.cursor == self.len()
+    }
+
+    pub fn move_left(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+
+    pub fn move_right(&mut self) {
+        self.cursor = (self.cursor + 1).min(self.len());
+    }
+
+    pub fn move_to_end(&mut self) {
+        self.cursor = self.len();
+    }
+
+    pub fn insert(&mut self, c: char) {
+        self.text.insert(self.byte_index(), c);
+        self.cursor += 1;
+    }
+
+    /// Deletes the character before the cursor, returning whether there was one.
+    pub fn delete_left(&mut self) -> bool {
+        if self.cursor == 0 {
+            return false;
+        }
+        self.move_left();
+        self.text.remove(self.byte_index());
+        true
+    }
+
+    /// Deletes the character after the cursor, returning whether there was one.
+    pub fn delete_right(&mut self) -> bool {
+        if self.at_end() {
+            return false;
+        }
+        self.text.remove(self.byte_index());
+        true
+    }
+
+    /// The byte offset of the cursor in the text.
+    pub fn byte_index(&self) -> usize {
+        self.text
+            .char_indices()
+            .nth(self.cursor)
+            .map_or(self.text.len(), |(i, _)| i)
+    }
+
+    /// How many columns the text before the cursor takes, which wide characters like
+    /// emoji take two of.
+    pub fn column(&self) -> usize {
+        Span::raw(&self.text[..self.byte_index()]).width()
+    }
+
+    fn len(&self) -> usize {
+        self.text.chars().count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    enum Edit {
+        Insert(char),
+        DeleteLeft,
+        DeleteRight,
+        Left,
+        Right,
+        End,
+    }
+
+    /// A character that can be typed, which is never a control character like the
+    /// `\r` that a following `\n` would make a single column with.
+    fn typable() -> impl Strategy<Value = char> {
+        any::<char>().prop_filter("typed", |c| !c.is_control())
+    }
+
+    fn edit() -> impl Strategy<Value = Edit> {
+        prop_oneof![
+            3 => typable().prop_map(Edit::Insert),
+            1 => Just(Edit::DeleteLeft),
+            1 => Just(Edit::DeleteRight),
+            1 => Just(Edit::Left),
+            1 => Just(Edit::Right),
+            1 => Just(Edit::End),
+        ]
+    }
+
+    fn apply(input: &mut InputState, edit: &Edit) {
+        match edit {
+            Edit::Insert(c) => input.insert(*c),
+            Edit::DeleteLeft => {
+                input.delete_left();
+            }
+            Edit::DeleteRight => {
+                input.delete_right();
+            }
+            Edit::Left => input.move_left(),
+            Edit::Right => input.move_right(),
+            Edit::End => input.move_to_end(),
+        }
+    }
+
+    fn typed(text: String, cursor: usize) -> InputState {
+        let cursor = cursor % (text.chars().count() + 1);
+        InputState { text, cursor }
+    }
+
+    proptest! {
+        #[test]
+        fn cursor_stays_on_a_character_boundary(
+            edits in prop::collection::vec(edit(), 0..64),
+        ) {
+            let mut input = InputState::default();
+            for edit in &edits {
+                apply(&mut input, edit);
+                prop_assert!(input.cursor <= input.text.chars().count());
+                prop_assert!(input.text.is_char_boundary(input.byte_index()));
+                prop_assert!(input.column() <= Span::raw(&input.text).width());
+                prop_assert_eq!(input.at_end(), input.byte_index() == input.text.len());
+            }
+        }
+
+        #[test]
+        fn insert_then_delete_left_restores(text in ".*", cursor in any::<usize>(), c in typable()) {
+            let mut input = typed(text, cursor);
+            let before = input.clone();
+            input.insert(c);
+            prop_assert_eq!(input.cursor, before.cursor + 1);
+            prop_assert!(input.delete_left());
+            prop_assert_eq!(&input.text, &before.text);
+            prop_assert_eq!(input.cursor, before.cursor);
+        }
+
+        #[test]
+        fn insert_then_left_and_delete_right_restores(
+            text in ".*",
+            cursor in any::<usize>(),
+            c in typable(),
+        ) {
+            let mut input = typed(text, cursor);
+            let before = input.clone();
+            input.insert(c);
+            input.move_left();
+            prop_assert!(input.delete_right());
+            prop_assert_eq!(&input.text, &before.text);
+            prop_assert_eq!(input.cursor, before.cursor);
+        }
+
+        #[test]
+        fn deleting_past_the_ends_changes_nothing(text in ".*") {
+            let mut input = InputState::default();
+            input.set(text.clone());
+            prop_assert!(!input.delete_right());
+            input.cursor = 0;
+            prop_assert!(!input.delete_left());
+            prop_assert_eq!(input.text, text);
+        }
+    }
+}
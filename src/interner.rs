@@ -0,0 +1,37 @@
+use std::{
+    collections::HashSet,
+    sync::{Arc, Mutex, OnceLock},
+};
+
+use serde::{Deserialize, Deserializer};
+
+/// Strings repeated across many entries, like icons, categories and directories, stored
+/// once.
+static STRINGS: OnceLock<Mutex<HashSet<Arc<str>>>> = OnceLock::new();
+
+/// The shared copy of `text`, made on first use.
+pub fn intern(text: &str) -> Arc<str> {
+    let Ok(mut strings) = STRINGS.get_or_init(Mutex::default).lock() else {
+        return Arc::from(text);
+    };
+    if let Some(interned) = strings.get(text) {
+        return interned.clone();
+    }
+    let interned = Arc::<str>::from(text);
+    strings.insert(interned.clone());
+    interned
+}
+
+/// Deserializes a string as its shared copy, for `#[serde(deserialize_with)]`.
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Arc<str>, D::Error> {
+    let text = String::deserialize(deserializer)?;
+    Ok(intern(&text))
+}
+
+/// Deserializes strings as their shared copies, for `#[serde(deserialize_with)]`.
+pub fn deserialize_all<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Vec<Arc<str>>, D::Error> {
+    let texts = Vec::<String>::deserialize(deserializer)?;
+    Ok(texts.iter().map(|text| intern(text)).collect())
+}
@@ -0,0 +1,213 @@
+use std::collections::HashMap;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::log;
+
+/// A shell command bound to a key, either just the command or with options.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum KeyBinding {
+    Command(String),
+    Options {
+        command: String,
+        /// Exits the launcher after the command succeeded.
+        #[serde(default)]
+        close: bool,
+    },
+}
+
+impl KeyBinding {
+    pub fn command(&self) -> &str {
+        match self {
+            Self::Command(command) | Self::Options { command, .. } => command,
+        }
+    }
+
+    pub fn close(&self) -> bool {
+        matches!(self, Self::Options { close: true, .. })
+    }
+}
+
+/// Something the launcher does on a key, rebindable under `[keys]`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyAction {
+    SelectNext,
+    SelectPrev,
+    SelectFirst,
+    SelectLast,
+    PageDown,
+    PageUp,
+    Launch,
+    LaunchKeepOpen,
+    LaunchUnsandboxed,
+    Elevate,
+    Quit,
+    ClearFilter,
+    CycleFocus,
+    NextMode,
+    PrevMode,
+    ActionMenu,
+    EditEntry,
+    WorkspaceMenu,
+    OpenDirectory,
+    Settings,
+    Hud,
+    Digraph,
+    Incognito,
+    SshMode,
+}
+
+const DEFAULT_KEYS: [(KeyAction, &[&str]); 24] = [
+    (KeyAction::SelectNext, &["Down"]),
+    (KeyAction::SelectPrev, &["Up"]),
+    (KeyAction::SelectFirst, &[]),
+    (KeyAction::SelectLast, &[]),
+    (KeyAction::PageDown, &["PageDown"]),
+    (KeyAction::PageUp, &["PageUp"]),
+    (KeyAction::Launch, &["Enter"]),
+    (KeyAction::LaunchKeepOpen, &["Alt+Enter"]),
+    (KeyAction::LaunchUnsandboxed, &["Alt+U"]),
+    (KeyAction::Elevate, &["Alt+E"]),
+    (KeyAction::Quit, &["Esc"]),
+    (KeyAction::ClearFilter, &["Ctrl+U"]),
+    (KeyAction::CycleFocus, &["Tab", "Shift+Tab"]),
+    (KeyAction::NextMode, &["Alt+Right"]),
+    (KeyAction::PrevMode, &["Alt+Left"]),
+    (KeyAction::ActionMenu, &["Alt+A"]),
+    (KeyAction::EditEntry, &["Alt+R"]),
+    (KeyAction::WorkspaceMenu, &["Alt+W"]),
+    (KeyAction::OpenDirectory, &["Alt+F"]),
+    (KeyAction::Settings, &["F2"]),
+    (KeyAction::Hud, &["F12"]),
+    (KeyAction::Digraph, &["Ctrl+K"]),
+    (KeyAction::Incognito, &["Alt+I"]),
+    (KeyAction::SshMode, &["Alt+S"]),
+];
+
+/// One key or a list of keys, e.g. `"Ctrl+J"` or `["Down", "Ctrl+J"]`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum Chords {
+    One(String),
+    Many(Vec<String>),
+}
+
+/// The keys of the launcher's own actions: the defaults, with the actions configured
+/// under `[keys]` bound to their keys instead.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(from = "HashMap<KeyAction, Chords>")]
+pub struct Keys {
+    bindings: Vec<((KeyCode, KeyModifiers), KeyAction)>,
+}
+
+impl Default for Keys {
+    fn default() -> Self {
+        Self::from(HashMap::new())
+    }
+}
+
+impl From<HashMap<KeyAction, Chords>> for Keys {
+    fn from(configured: HashMap<KeyAction, Chords>) -> Self {
+        let mut bindings = Vec::new();
+        for (action, defaults) in DEFAULT_KEYS {
+            let names = match configured.get(&action) {
+                Some(Chords::One(name)) => vec![name.as_str()],
+                Some(Chords::Many(names)) => names.iter().map(String::as_str).collect(),
+                None => defaults.to_vec(),
+            };
+            for name in names {
+                match parse(name) {
+                    Some(key) => bindings.push((key, action)),
+                    None => log::info(&format!("unknown key {:?} for {:?}", name, action)),
+                }
+            }
+        }
+        Self { bindings }
+    }
+}
+
+impl Keys {
+    /// The action bound to `key`, if any.
+    pub fn action(&self, key: &KeyEvent) -> Option<KeyAction> {
+        let pressed = normalize(key.code, key.modifiers);
+        self.bindings
+            .iter()
+            .find(|(key, _)| *key == pressed)
+            .map(|(_, action)| *action)
+    }
+}
+
+/// The binding for `key` among bindings keyed like `Ctrl+Y`, `Alt+Shift+Enter` or `F5`.
+/// Letters match regardless of case.
+pub fn find<'a>(
+    bindings: &'a HashMap<String, KeyBinding>,
+    key: &KeyEvent,
+) -> Option<&'a KeyBinding> {
+    let pressed = normalize(key.code, key.modifiers);
+    bindings
+        .iter()
+        .find(|(name, _)| parse(name) == Some(pressed))
+        .map(|(_, binding)| binding)
+}
+
+/// Whether `key` is the key named like `Ctrl+Y`.
+pub fn matches(name: &str, key: &KeyEvent) -> bool {
+    parse(name) == Some(normalize(key.code, key.modifiers))
+}
+
+fn normalize(code: KeyCode, modifiers: KeyModifiers) -> (KeyCode, KeyModifiers) {
+    match code {
+        KeyCode::BackTab => (KeyCode::Tab, modifiers | KeyModifiers::SHIFT),
+        KeyCode::Char(c) if c.is_alphabetic() => (
+            KeyCode::Char(c.to_ascii_lowercase()),
+            modifiers - KeyModifiers::SHIFT,
+        ),
+        code => (code, modifiers),
+    }
+}
+
+fn parse(name: &str) -> Option<(KeyCode, KeyModifiers)> {
+    let mut modifiers = KeyModifiers::NONE;
+    let mut parts = name.split('+').peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            return Some(normalize(parse_code(part)?, modifiers));
+        }
+        modifiers |= match part.to_lowercase().as_str() {
+            "ctrl" | "control" => KeyModifiers::CONTROL,
+            "alt" => KeyModifiers::ALT,
+            "shift" => KeyModifiers::SHIFT,
+            _ => return None,
+        };
+    }
+    None
+}
+
+fn parse_code(name: &str) -> Option<KeyCode> {
+    let mut chars = name.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(KeyCode::Char(c));
+    }
+    let code = match name.to_lowercase().as_str() {
+        "enter" => KeyCode::Enter,
+        "esc" | "escape" => KeyCode::Esc,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" => KeyCode::Delete,
+        "insert" => KeyCode::Insert,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pageup" => KeyCode::PageUp,
+        "pagedown" => KeyCode::PageDown,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        name => KeyCode::F(name.strip_prefix('f')?.parse().ok()?),
+    };
+    Some(code)
+}
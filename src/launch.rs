@@ -0,0 +1,562 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io,
+    os::unix::process::CommandExt,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Mutex, OnceLock},
+    thread,
+    time::Duration,
+};
+
+use fork::{fork, setsid, Fork};
+use serde::Deserialize;
+
+use crate::{config::Config, glob::glob_match, paths};
+
+/// How long a detached app is watched for failing right away.
+const STARTUP_WINDOW: Duration = Duration::from_millis(500);
+
+/// The log detached apps write their stderr to is emptied once it grows past this.
+const MAX_LOG_SIZE: u64 = 1 << 20;
+
+/// Variables describing the graphical session, which a launcher running in a terminal
+/// started before it, e.g. a tmux session, has stale or none of.
+const SESSION_VARIABLES: [&str; 4] = ["WAYLAND_DISPLAY", "DISPLAY", "XAUTHORITY", "XDG_SESSION_*"];
+
+/// The environment launched apps get, which is otherwise the launcher's own.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct EnvironmentConfig {
+    /// Takes the session's variables from systemd's user manager where it has them.
+    pub from_session: bool,
+    /// Variables of the launcher's terminal that apps detached from it don't get, as
+    /// globs, e.g. `KITTY_*`.
+    pub strip: Vec<String>,
+    /// Variables passed on although `strip` matches them.
+    pub keep: Vec<String>,
+}
+
+impl Default for EnvironmentConfig {
+    fn default() -> Self {
+        Self {
+            from_session: true,
+            strip: [
+                "WINDOWID",
+                "KITTY_*",
+                "ALACRITTY_*",
+                "WEZTERM_*",
+                "TERM_PROGRAM*",
+                "VTE_VERSION",
+                "TMUX",
+                "TMUX_PANE",
+                "STY",
+            ]
+            .map(String::from)
+            .to_vec(),
+            keep: Vec::new(),
+        }
+    }
+}
+
+impl EnvironmentConfig {
+    pub fn strips(&self, name: &str) -> bool {
+        let matches = |patterns: &[String]| patterns.iter().any(|p| glob_match(p, name));
+        matches(&self.strip) && !matches(&self.keep)
+    }
+}
+
+static ENVIRONMENT: OnceLock<EnvironmentConfig> = OnceLock::new();
+
+/// The session's variables as last read from systemd, see `refresh_session`.
+static SESSION: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+pub fn init(config: &Config) {
+    let _ = ENVIRONMENT.set(config.environment.clone());
+    refresh_session();
+}
+
+/// Reads the session's environment again in the background, at startup and whenever a
+/// daemon is shown, as it may outlive the session it started in. Launches before the
+/// first read finished get the launcher's own variables.
+pub fn refresh_session() {
+    if !ENVIRONMENT.get().is_some_and(|config| config.from_session) {
+        return;
+    }
+    thread::spawn(|| {
+        let environment = session_environment();
+        if let Ok(mut session) = SESSION.lock() {
+            *session = environment;
+        }
+    });
+}
+
+/// Spawns `argv` attached to the current terminal, for `Terminal=true` entries.
+pub fn spawn_in_terminal(argv: &[String]) -> io::Result<Child> {
+    let mut command = command(argv)?;
+    set_environment(&mut command, false);
+    command.spawn()
+}
+
+/// Spawns `argv` in a new session so it outlives the terminal the launcher runs in,
+/// without letting its output into the terminal.
+///
+/// The child forks once more before running `argv` and exits once the app ran for
+/// `STARTUP_WINDOW`, so the app is reparented to init and never left a zombie of the
+/// launcher, nor able to take the terminal back as a session leader. Fails if `argv`
+/// can't be run or exits unsuccessfully within the window, with the last line it wrote
+/// to stderr, which goes to `launch.log` in the state dir.
+pub fn spawn_detached(argv: &[String]) -> io::Result<()> {
+    detach(argv, log_path().as_deref(), STARTUP_WINDOW)
+}
+
+/// Spawns `argv` detached like `spawn_detached`, without waiting to see whether it fails.
+pub fn spawn_background(argv: &[String]) -> io::Result<()> {
+    detach(argv, None, Duration::ZERO)
+}
+
+fn detach(argv: &[String], log: Option<&Path>, window: Duration) -> io::Result<()> {
+    let mut command = command(argv)?;
+    set_environment(&mut command, true);
+    let log_file = log.and_then(|path| open_log(path).ok());
+    let offset = log_file
+        .as_ref()
+        .and_then(|file| file.metadata().ok())
+        .map_or(0, |metadata| metadata.len() as usize);
+    let stderr = match &log_file {
+        Some(file) => Stdio::from(file.try_clone()?),
+        None => Stdio::null(),
+    };
+    command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(stderr);
+    unsafe {
+        command.pre_exec(move || {
+            setsid().map_err(|_| io::Error::last_os_error())?;
+            match fork() {
+                Ok(Fork::Child) => Ok(()),
+                Ok(Fork::Parent(pid)) => libc::_exit(watch(pid, window)),
+                Err(_) => Err(io::Error::last_os_error()),
+            }
+        });
+    }
+    // Returns once `argv` ran for the window or failed to, when the intermediate child
+    // exited with the app's own status.
+    let status = command.spawn()?.wait()?;
+    if status.success() {
+        return Ok(());
+    }
+    let stderr = log
+        .and_then(|path| fs::read(path).ok())
+        .and_then(|log| {
+            log.get(offset..)
+                .map(|new| String::from_utf8_lossy(new).to_string())
+        })
+        .unwrap_or_default();
+    let message = match stderr
+        .lines()
+        .rev()
+        .map(str::trim)
+        .find(|line| !line.is_empty())
+    {
+        Some(line) => line.to_string(),
+        None => format!("exited with status {}", status.code().unwrap_or(1)),
+    };
+    Err(io::Error::other(message))
+}
+
+/// Waits up to `window` for the app `pid` to exit, returning its exit status if it did
+/// unsuccessfully and 0 otherwise. Runs between fork and exec, so only makes system calls.
+fn watch(pid: libc::pid_t, window: Duration) -> libc::c_int {
+    const STEP: Duration = Duration::from_millis(10);
+    let mut waited = Duration::ZERO;
+    while waited < window {
+        let mut status = 0;
+        if unsafe { libc::waitpid(pid, &mut status, libc::WNOHANG) } == pid {
+            return if libc::WIFEXITED(status) {
+                libc::WEXITSTATUS(status)
+            } else {
+                128 + libc::WTERMSIG(status)
+            };
+        }
+        thread::sleep(STEP);
+        waited += STEP;
+    }
+    0
+}
+
+/// Corrects the environment `command` inherits as `[environment]` says, stripping the
+/// terminal's variables if it's `detached` from it.
+fn set_environment(command: &mut Command, detached: bool) {
+    let Some(config) = ENVIRONMENT.get() else {
+        return;
+    };
+    if let Ok(session) = SESSION.lock() {
+        for (name, value) in session.iter() {
+            if SESSION_VARIABLES.iter().any(|p| glob_match(p, name)) {
+                command.env(name, value);
+            }
+        }
+    }
+    if detached {
+        for (name, _) in std::env::vars_os() {
+            if name.to_str().is_some_and(|name| config.strips(name)) {
+                command.env_remove(name);
+            }
+        }
+    }
+}
+
+/// The environment of systemd's user manager, which compositors import their session
+/// into. Empty without systemd.
+fn session_environment() -> Vec<(String, String)> {
+    let Ok(output) = Command::new("systemctl")
+        .args(["--user", "show-environment"])
+        .stderr(Stdio::null())
+        .output()
+    else {
+        return Vec::new();
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        // Values with special characters are quoted like `$'...'`, which none of the
+        // session's are.
+        .filter(|line| !line.contains("=$'"))
+        .filter_map(|line| line.split_once('='))
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect()
+}
+
+fn log_path() -> Option<PathBuf> {
+    paths::state_dir().map(|dir| dir.join("launch.log"))
+}
+
+/// Opens the log for appending, emptied first if it grew too large.
+fn open_log(path: &Path) -> io::Result<File> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    if file.metadata()?.len() > MAX_LOG_SIZE {
+        file.set_len(0)?;
+    }
+    Ok(file)
+}
+
+/// Expands a template like `firejail {cmd}`, substituting `{cmd}` with `argv`, or
+/// appending `argv` when the template has no `{cmd}`, as in `firejail`. The template
+/// is split like a shell would; `None` if a quote is left open.
+pub fn wrap(template: &str, argv: Vec<String>) -> Option<Vec<String>> {
+    let words = split(template)?;
+    if !words.iter().any(|word| word == "{cmd}") {
+        return Some(words.into_iter().chain(argv).collect());
+    }
+    Some(
+        words
+            .into_iter()
+            .flat_map(|word| match word.as_str() {
+                "{cmd}" => argv.clone(),
+                _ => vec![word],
+            })
+            .collect(),
+    )
+}
+
+/// Splits `template` into arguments like a shell would and replaces every `{name}` in
+/// them with its value, without going through a shell. `None` if a quote is left open.
+pub fn expand(template: &str, values: &[(&str, String)]) -> Option<Vec<String>> {
+    let words = split(template)?;
+    Some(
+        words
+            .into_iter()
+            .map(|word| {
+                values.iter().fold(word, |word, (name, value)| {
+                    word.replace(&format!("{{{}}}", name), value)
+                })
+            })
+            .collect(),
+    )
+}
+
+/// Replaces every `{name}` in the shell command `template` with its value quoted as a
+/// single word, so no value can inject commands.
+pub fn fill(template: &str, values: &[(&str, String)]) -> String {
+    fill_with(template, |name| {
+        values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| quote(value))
+    })
+}
+
+/// Replaces every `{name}` in `template` that `word` knows with the shell text it gives.
+/// In one pass, so a value containing `{name}` stays as it is.
+pub fn fill_with(template: &str, word: impl Fn(&str) -> Option<String>) -> String {
+    let mut command = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        command.push_str(&rest[..start]);
+        rest = &rest[start..];
+        match rest
+            .find('}')
+            .and_then(|end| Some((end, word(&rest[1..end])?)))
+        {
+            Some((end, word)) => {
+                command.push_str(&word);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                command.push('{');
+                rest = &rest[1..];
+            }
+        }
+    }
+    command.push_str(rest);
+    command
+}
+
+/// Quotes `text` as a single shell word.
+pub fn quote(text: &str) -> String {
+    format!("'{}'", text.replace('\'', r"'\''"))
+}
+
+/// `argv` as a shell command line.
+pub fn join(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| quote(arg))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Splits `text` into arguments like a POSIX shell would, honoring single and double
+/// quotes and backslash escapes. `None` if a quote is left open.
+pub fn split(text: &str) -> Option<Vec<String>> {
+    let mut args = Vec::new();
+    let mut arg = None::<String>;
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            c if c.is_whitespace() => args.extend(arg.take()),
+            '\\' => arg.get_or_insert_with(String::new).extend(chars.next()),
+            '\'' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        c => arg.push(c),
+                    }
+                }
+            }
+            '"' => {
+                let arg = arg.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            c @ ('"' | '\\' | '$' | '`') => arg.push(c),
+                            c => {
+                                arg.push('\\');
+                                arg.push(c);
+                            }
+                        },
+                        c => arg.push(c),
+                    }
+                }
+            }
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    Some(args)
+}
+
+fn command(argv: &[String]) -> io::Result<Command> {
+    let Some((program, args)) = argv.split_first() else {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "empty command"));
+    };
+    let mut command = Command::new(program);
+    command.args(args);
+    Ok(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fs, process,
+        sync::{Mutex, MutexGuard},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    /// Held by the tests that detach, so one never sees another's children mid-exit.
+    static DETACHING: Mutex<()> = Mutex::new(());
+
+    fn detaching() -> MutexGuard<'static, ()> {
+        DETACHING
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// The state, parent and session of process `pid`, read from `/proc`.
+    fn stat(pid: u32) -> Option<(char, u32, u32)> {
+        let stat = fs::read_to_string(format!("/proc/{}/stat", pid)).ok()?;
+        let mut fields = stat.rsplit_once(')')?.1.split_whitespace();
+        let state = fields.next()?.chars().next()?;
+        let parent = fields.next()?.parse().ok()?;
+        let session = fields.nth(1)?.parse().ok()?;
+        Some((state, parent, session))
+    }
+
+    /// Zombie children of this process that `detach` left, told apart from those of
+    /// tests elsewhere spawning commands by the new session they started.
+    fn zombies() -> Vec<u32> {
+        let session = stat(process::id()).unwrap().2;
+        fs::read_dir("/proc")
+            .unwrap()
+            .flatten()
+            .filter_map(|proc| proc.file_name().to_str()?.parse().ok())
+            .filter(|&pid| {
+                matches!(stat(pid), Some(('Z', parent, other))
+                    if parent == process::id() && other != session)
+            })
+            .collect()
+    }
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("launcher-test-{}-{}", name, process::id()))
+    }
+
+    #[test]
+    fn wrap_appends_the_command_without_a_placeholder() {
+        let app = argv(&["firefox", "--private-window"]);
+        assert_eq!(
+            wrap("firejail --net=none {cmd}", app.clone()),
+            Some(argv(&[
+                "firejail",
+                "--net=none",
+                "firefox",
+                "--private-window"
+            ]))
+        );
+        assert_eq!(
+            wrap("firejail", app.clone()),
+            Some(argv(&["firejail", "firefox", "--private-window"]))
+        );
+        assert_eq!(
+            wrap("firejail '--profile=/path/my profile'", app.clone()),
+            Some(argv(&[
+                "firejail",
+                "--profile=/path/my profile",
+                "firefox",
+                "--private-window"
+            ]))
+        );
+        assert_eq!(wrap("firejail \"--profile=/x", app), None);
+    }
+
+    #[test]
+    fn expanded_values_stay_inside_their_argument() {
+        let values = [("path", "/home/me/My Files".to_string())];
+        assert_eq!(
+            expand("thunar '--open={path}' --now", &values),
+            Some(argv(&["thunar", "--open=/home/me/My Files", "--now"]))
+        );
+        assert_eq!(expand("thunar \"{path}", &values), None);
+    }
+
+    #[test]
+    fn filled_values_are_single_shell_words() {
+        let values = [
+            ("name", "Tom's $(reboot)".to_string()),
+            ("id", "{name}".to_string()),
+        ];
+        assert_eq!(
+            fill("notify-send {name} {id} {other}", &values),
+            r"notify-send 'Tom'\''s $(reboot)' '{name}' {other}"
+        );
+    }
+
+    #[test]
+    fn keep_overrides_strip() {
+        let config = EnvironmentConfig {
+            keep: vec!["KITTY_PUBLIC_KEY".to_string()],
+            ..EnvironmentConfig::default()
+        };
+        assert!(config.strips("KITTY_WINDOW_ID"));
+        assert!(!config.strips("KITTY_PUBLIC_KEY"));
+        assert!(!config.strips("WAYLAND_DISPLAY"));
+    }
+
+    #[test]
+    fn detached_app_leaves_no_zombie() {
+        let _detaching = detaching();
+        detach(&argv(&["true"]), None, STARTUP_WINDOW).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(zombies(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn detached_app_runs_in_its_own_session() {
+        let _detaching = detaching();
+        let pid_file = temp_path("pid");
+        let script = format!("echo $$ > {}; exec sleep 5", pid_file.display());
+        detach(&argv(&["sh", "-c", &script]), None, STARTUP_WINDOW).unwrap();
+        let mut pid = None;
+        for _ in 0..50 {
+            pid = fs::read_to_string(&pid_file)
+                .ok()
+                .and_then(|pid| pid.trim().parse::<u32>().ok());
+            if pid.is_some() {
+                break;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        let _ = fs::remove_file(&pid_file);
+        let pid = pid.expect("the app never ran");
+        let (_, parent, session) = stat(pid).unwrap();
+        let _ = Command::new("kill").arg(pid.to_string()).status();
+        assert_ne!(parent, process::id());
+        assert_ne!(session, pid);
+        assert_ne!(session, stat(process::id()).unwrap().2);
+    }
+
+    #[test]
+    fn missing_program_fails_without_zombie() {
+        let _detaching = detaching();
+        assert!(detach(&argv(&["/nonexistent/program"]), None, STARTUP_WINDOW).is_err());
+        thread::sleep(Duration::from_millis(200));
+        assert_eq!(zombies(), Vec::<u32>::new());
+    }
+
+    #[test]
+    fn failing_app_reports_its_last_line_of_stderr() {
+        let _detaching = detaching();
+        let log = temp_path("log");
+        let script = "echo starting >&2; echo 'no display' >&2; exit 3";
+        let error = detach(&argv(&["sh", "-c", script]), Some(&log), STARTUP_WINDOW);
+        let _ = fs::remove_file(&log);
+        assert_eq!(error.unwrap_err().to_string(), "no display");
+    }
+
+    #[test]
+    fn failing_app_without_output_reports_its_status() {
+        let _detaching = detaching();
+        let error = detach(&argv(&["sh", "-c", "exit 3"]), None, STARTUP_WINDOW);
+        assert_eq!(error.unwrap_err().to_string(), "exited with status 3");
+    }
+
+    #[test]
+    fn app_failing_after_the_window_is_not_reported() {
+        let _detaching = detaching();
+        let script = "sleep 0.3; exit 1";
+        let window = Duration::from_millis(100);
+        assert!(detach(&argv(&["sh", "-c", script]), None, window).is_ok());
+    }
+}
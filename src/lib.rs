@@ -0,0 +1,177 @@
+//! The launcher as a library, for building it with modes of your own.
+//!
+//! A mode is a [`Source`] listing [`Item`]s and running [`Action`]s on them. Pass
+//! yours to [`run`] from your own `main` to get the `launcher` binary with them
+//! added, as `examples/fortune.rs` does with a fortune cookie mode.
+//!
+//! What's exported here is the extension API, versioned with semver: while the
+//! version is `0.x`, only minor releases may break it. `Item`, `Action` and `Outcome`
+//! may gain fields and variants in any release, so items and actions are made with
+//! their constructors. Everything else is private to the binary.
+
+use std::{
+    env,
+    fs::OpenOptions,
+    io::{self, Write},
+    path::Path,
+    process,
+};
+
+use app::App;
+use config::Config;
+use crossterm::{
+    cursor, execute,
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+};
+use daemon::{Command, Daemon};
+use ratatui::{backend::CrosstermBackend, Terminal};
+use sources::dmenu;
+
+mod action_menu;
+mod animation;
+mod ansi;
+mod app;
+mod applications;
+mod archive;
+mod audit;
+mod badge;
+mod bench;
+mod clipboard;
+mod compositor;
+mod config;
+mod confirm;
+mod daemon;
+mod desktop_entry;
+mod digraph;
+mod entry_cache;
+mod entry_editor;
+mod first_seen;
+mod frecency;
+mod glob;
+mod history;
+mod hooks;
+mod host_terminal;
+mod hud;
+mod icons;
+mod import;
+mod input;
+mod interner;
+mod keybindings;
+mod launch;
+mod locale;
+mod log;
+mod matcher;
+mod modal;
+mod mode_state;
+mod names;
+mod network;
+mod open;
+mod overrides;
+mod paths;
+mod pattern;
+mod perf;
+mod placement;
+mod preview;
+mod recent_files;
+mod release_check;
+mod result_cache;
+mod row;
+mod running;
+mod screen;
+mod settings;
+mod signals;
+mod sources;
+mod theme;
+mod vault;
+mod watcher;
+
+pub use badge::Badge;
+pub use matcher::{Matcher, Score};
+pub use sources::{Action, Item, Outcome, Source};
+
+/// Runs the launcher with `sources` as modes besides the built-in ones, reading the
+/// config and command line like the `launcher` binary does. Their keywords should
+/// differ from the built-in modes', which come first.
+pub fn run(sources: Vec<Box<dyn Source>>) -> io::Result<()> {
+    let config_path = paths::config_file();
+    let config_path = config_path.as_deref();
+    let toml = paths::read_config(config_path)?;
+    let mut config = toml::from_str::<Config>(&toml).map_err(io::Error::other)?;
+    if env::args().any(|arg| arg == "--restricted") {
+        config.restricted = true;
+    }
+    if env::args().any(|arg| arg == "--offline") {
+        config.network.offline = true;
+    }
+    config.dmenu = dmenu::Options::from_args(&env::args().collect::<Vec<String>>())?;
+    log::init(config.log_file.clone());
+    theme::init(&config);
+    locale::init(&config);
+    vault::init(&config);
+    network::init(&config);
+    launch::init(&config);
+    let args = env::args().skip(1).collect::<Vec<String>>();
+    match args
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<&str>>()
+        .as_slice()
+    {
+        ["import", "--from", ..] => return import::run(&args[1..], &config),
+        ["import", path] => return archive::import(path),
+        ["export"] => return archive::export(config_path, None),
+        ["export", path] => return archive::export(config_path, Some(path)),
+        ["perf", "report"] => return perf::report(),
+        ["bench", ..] => return bench::run(&args[1..], &config),
+        ["place-window", ..] => return placement::run(&args[1..]),
+        ["--toggle"] => return daemon::send(Command::Toggle),
+        ["--show"] => return daemon::send(Command::Show),
+        ["--hide"] => return daemon::send(Command::Hide),
+        ["--quit"] => return daemon::send(Command::Quit),
+        _ => {}
+    }
+    if let Some(options) = config.dmenu.clone() {
+        return dmenu(config, options, config_path);
+    }
+    // Listening before taking over the terminal, so a second daemon fails with the reason.
+    let daemon = args
+        .iter()
+        .any(|arg| arg == "--daemon")
+        .then(Daemon::listen)
+        .transpose()?;
+    let mut terminal = ratatui::init();
+    let app_result = App::new(config, config_path, sources)
+        .with_daemon(daemon)
+        .run(&mut terminal);
+    ratatui::restore();
+    app_result
+}
+
+/// Lists the lines piped to stdin and prints the one picked, drawing on the controlling
+/// terminal since stdin and stdout are taken by the pipe. Exits with 1 when cancelled.
+fn dmenu(config: Config, options: dmenu::Options, config_path: Option<&Path>) -> io::Result<()> {
+    let mut tty = OpenOptions::new().read(true).write(true).open("/dev/tty")?;
+    terminal::enable_raw_mode()?;
+    execute!(tty, EnterAlternateScreen)?;
+    let app_result = Terminal::new(CrosstermBackend::new(tty.try_clone()?))
+        .and_then(|mut terminal| App::new(config, config_path, Vec::new()).run(&mut terminal));
+    execute!(tty, LeaveAlternateScreen, cursor::Show)?;
+    terminal::disable_raw_mode()?;
+    app_result?;
+    let Some(line) = dmenu::selection() else {
+        process::exit(1);
+    };
+    let accepted_with = dmenu::accepted_with();
+    if !options.expect.is_empty() {
+        let key = accepted_with.map_or("", |index| &options.expect[index]);
+        print!("{}{}", key, options.terminator());
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(line)?;
+    write!(stdout, "{}", options.terminator())?;
+    stdout.flush()?;
+    match accepted_with {
+        Some(index) => process::exit(10 + index as i32),
+        None => Ok(()),
+    }
+}
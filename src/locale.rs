@@ -0,0 +1,77 @@
+use std::{collections::HashMap, env, fs, sync::OnceLock};
+
+use crate::{config::Config, log, paths};
+
+/// Translations of the English UI strings, keyed by the English text.
+static MESSAGES: OnceLock<HashMap<String, String>> = OnceLock::new();
+
+/// Translations shipped with the launcher, by language.
+const BUNDLED: [(&str, &str); 1] = [("de", include_str!("locales/de.toml"))];
+
+/// Loads the translations for the `locale` in the config or else the environment's.
+/// Strings stay in English until this is called.
+pub fn init(config: &Config) {
+    let _ = MESSAGES.set(load(config.locale.as_deref()));
+}
+
+/// The translation of the English `text`, or `text` itself when there is none.
+pub fn tr(text: &str) -> &str {
+    MESSAGES
+        .get()
+        .and_then(|messages| messages.get(text))
+        .map_or(text, String::as_str)
+}
+
+/// Translates `text` and fills in its placeholders like `{name}` with `values`.
+pub fn tr_with(text: &str, values: &[(&str, &str)]) -> String {
+    values
+        .iter()
+        .fold(tr(text).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+}
+
+/// The bundled and the user's translations for `locale`, those in
+/// `$XDG_CONFIG_HOME/launcher/locales` overriding the bundled ones, and `de_AT.toml`
+/// overriding `de.toml`.
+fn load(locale: Option<&str>) -> HashMap<String, String> {
+    let mut messages = HashMap::new();
+    for name in languages(locale).iter().rev() {
+        if let Some((_, content)) = BUNDLED.iter().find(|(language, _)| language == name) {
+            extend(&mut messages, name, content);
+        }
+        let path =
+            paths::config_home().map(|dir| dir.join(format!("launcher/locales/{}.toml", name)));
+        if let Some(content) = path.and_then(|path| fs::read_to_string(path).ok()) {
+            extend(&mut messages, name, &content);
+        }
+    }
+    messages
+}
+
+fn extend(messages: &mut HashMap<String, String>, name: &str, content: &str) {
+    match toml::from_str::<HashMap<String, String>>(content) {
+        Ok(translations) => messages.extend(translations),
+        Err(error) => log::info(&format!("locale {}: {}", name, error)),
+    }
+}
+
+/// The locale files to look for, most specific first, e.g. `de_AT` and `de` for
+/// `de_AT.UTF-8`. Taken from `$LC_ALL`, `$LC_MESSAGES` or `$LANG` unless given.
+fn languages(locale: Option<&str>) -> Vec<String> {
+    let locale = locale.map(String::from).or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|var| env::var(var).ok().filter(|value| !value.is_empty()))
+    });
+    let Some(locale) = locale else {
+        return Vec::new();
+    };
+    let name = locale.split(['.', '@']).next().unwrap_or_default();
+    let mut names = vec![name.to_string()];
+    if let Some((language, _)) = name.split_once('_') {
+        names.push(language.to_string());
+    }
+    names.retain(|name| !name.is_empty() && name != "C" && name != "POSIX");
+    names
+}
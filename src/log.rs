@@ -0,0 +1,27 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::PathBuf,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+static LOG_FILE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// Sets the file `info` appends to; logging is a no-op until this is called with a path.
+pub fn init(path: Option<PathBuf>) {
+    let _ = LOG_FILE.set(path);
+}
+
+pub fn info(message: &str) {
+    let Some(Some(path)) = LOG_FILE.get() else {
+        return;
+    };
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default();
+    if let Ok(mut file) = OpenOptions::new().create(true).append(true).open(path) {
+        let _ = writeln!(file, "{} {}", timestamp, message);
+    }
+}
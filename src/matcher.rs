@@ -0,0 +1,492 @@
+use std::fmt::Debug;
+
+use memchr::memchr2;
+use serde::Deserialize;
+
+use crate::pattern;
+
+/// Points for each character of a scattered match, e.g. "ffx" in "Firefox".
+const FUZZY_MATCH: i64 = 16;
+/// Added when a character of a scattered match is at a word start.
+const FUZZY_BOUNDARY: i64 = 8;
+/// Added when a character directly follows the one matched before it.
+const FUZZY_CONSECUTIVE: i64 = 4;
+/// Taken for skipping characters between two matched ones, plus one per extra character.
+const FUZZY_GAP: i64 = 3;
+
+/// skim's points for each matched character.
+const SKIM_MATCH: i64 = 16;
+/// skim's penalty for starting a gap, and for each further character of it.
+const SKIM_GAP_START: i64 = -3;
+const SKIM_GAP_EXTENSION: i64 = -1;
+/// skim's bonus for a character starting a word, after a non-word character.
+const SKIM_BOUNDARY: i64 = SKIM_MATCH / 2;
+/// skim's bonus for matching a non-word character.
+const SKIM_NON_WORD: i64 = SKIM_MATCH / 2;
+/// skim's bonus for a capital after a lowercase letter or a digit after a non-digit.
+const SKIM_CAMEL: i64 = SKIM_BOUNDARY + SKIM_GAP_EXTENSION;
+/// skim's least bonus for a character directly following the one matched before it.
+const SKIM_CONSECUTIVE: i64 = -(SKIM_GAP_START + SKIM_GAP_EXTENSION);
+/// How much more the bonus of the first query character counts.
+const SKIM_FIRST_CHAR_MULTIPLIER: i64 = 2;
+
+/// Scores names against a query, higher for better matches. The `[matcher]` config is
+/// one, and a source can bring its own.
+pub trait Matcher: Debug {
+    /// `None` unless `name` matches `query`, which an empty query always does. A name
+    /// matching a query must match the query's prefixes too, as the matches of each
+    /// keystroke are narrowed down from the previous one's, unless `narrows` says not.
+    fn score(&self, name: &str, query: &str) -> Option<Score>;
+
+    /// Whether a query's matches can be narrowed down from those of its prefixes.
+    fn narrows(&self) -> bool {
+        true
+    }
+}
+
+/// How names are matched, trading what counts as a match for speed.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Algorithm {
+    /// A substring, else word initials like "gie" in "GIMP Image Editor", else any
+    /// characters in order.
+    #[default]
+    Auto,
+    /// Only a substring, the fastest.
+    Substring,
+    /// Any characters in order, scored by their best alignment. The launcher's own
+    /// scoring, in the spirit of fzf's but not the same.
+    Fuzzy,
+    /// Any characters in order, scored by their best alignment the way skim does.
+    FuzzySkim,
+    /// The query as a regular expression, ignoring case, e.g. `^lib.*(calc|writer)`.
+    /// Matched as a substring while it doesn't parse, e.g. halfway through a group.
+    Regex,
+}
+
+impl Algorithm {
+    /// Whether a name matching a query matches each of its prefixes too. A regular
+    /// expression like `fire|vlc` doesn't, as its prefix `fire|` matches anything.
+    pub fn narrows(self) -> bool {
+        !matches!(self, Self::Regex)
+    }
+}
+
+/// Tuning knobs for how matches are ranked against each other.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct MatchWeights {
+    pub algorithm: Algorithm,
+    /// Added when a substring match begins at a word start or a capital letter.
+    pub word_start_bonus: i64,
+    /// Added per query character matched against word initials, e.g. "gie" in
+    /// "GIMP Image Editor".
+    pub initials_bonus: i64,
+}
+
+impl Default for MatchWeights {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Auto,
+            word_start_bonus: 50,
+            initials_bonus: 20,
+        }
+    }
+}
+
+/// The score of a match and the byte offsets of the matched characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Score {
+    pub score: i64,
+    pub positions: Vec<usize>,
+}
+
+pub fn score(name: &str, query: &str, weights: &MatchWeights) -> Option<Score> {
+    if weights.algorithm.narrows() && !may_match(name, query) {
+        return None;
+    }
+    score_unfiltered(name, query, weights)
+}
+
+/// Whether `query` could match `name` at all, checked on bytes before the costlier
+/// scoring. Every kind of match but a regular expression needs the query's characters in order in the name,
+/// which for ASCII is a `memchr` for each of them. Other text may fold differently,
+/// like `K` (Kelvin) to `k`, so it's left to the scoring.
+pub fn may_match(name: &str, query: &str) -> bool {
+    if !name.is_ascii() || !query.is_ascii() {
+        return true;
+    }
+    let mut rest = name.as_bytes();
+    for byte in query.bytes() {
+        match memchr2(byte.to_ascii_lowercase(), byte.to_ascii_uppercase(), rest) {
+            Some(i) => rest = &rest[i + 1..],
+            None => return false,
+        }
+    }
+    true
+}
+
+/// `score` without the prefilter, for benchmarking it.
+pub fn score_unfiltered(name: &str, query: &str, weights: &MatchWeights) -> Option<Score> {
+    if query.is_empty() {
+        return Some(Score {
+            score: 0,
+            positions: Vec::new(),
+        });
+    }
+    let chars = name.char_indices().collect::<Vec<(usize, char)>>();
+    // Folding would turn escapes like `\W` into others.
+    let regex_query = query;
+    let query = query.chars().map(fold).collect::<Vec<char>>();
+    let boundaries = (0..chars.len())
+        .filter(|&i| is_boundary(&chars, i))
+        .collect::<Vec<usize>>();
+
+    match weights.algorithm {
+        Algorithm::Auto => substring(&chars, &query, &boundaries, weights)
+            .or_else(|| initials(&chars, &query, &boundaries, weights))
+            .or_else(|| fuzzy(&chars, &query, &boundaries)),
+        Algorithm::Substring => substring(&chars, &query, &boundaries, weights),
+        Algorithm::Fuzzy => fuzzy(&chars, &query, &boundaries),
+        Algorithm::FuzzySkim => fuzzy_skim(&chars, &query),
+        Algorithm::Regex => regex(name, &chars, regex_query, &query, &boundaries, weights),
+    }
+}
+
+impl Matcher for MatchWeights {
+    fn score(&self, name: &str, query: &str) -> Option<Score> {
+        score(name, query, self)
+    }
+
+    fn narrows(&self) -> bool {
+        self.algorithm.narrows()
+    }
+}
+
+/// `query` within `chars`, preferring the occurrence at a word start, else the first.
+fn substring(
+    chars: &[(usize, char)],
+    query: &[char],
+    boundaries: &[usize],
+    weights: &MatchWeights,
+) -> Option<Score> {
+    let start = (0..chars.len())
+        .filter(|&start| {
+            chars.len() - start >= query.len()
+                && query
+                    .iter()
+                    .zip(&chars[start..])
+                    .all(|(q, (_, c))| *q == fold(*c))
+        })
+        .max_by_key(|&start| (boundaries.contains(&start), std::cmp::Reverse(start)))?;
+    let mut score = 100;
+    if boundaries.contains(&start) {
+        score += weights.word_start_bonus;
+    }
+    Some(Score {
+        score,
+        positions: (start..start + query.len()).map(|i| chars[i].0).collect(),
+    })
+}
+
+/// The leftmost match of the regular expression `pattern` in `name`, scored like a
+/// substring, or `query` as a substring while `pattern` doesn't parse.
+fn regex(
+    name: &str,
+    chars: &[(usize, char)],
+    pattern: &str,
+    query: &[char],
+    boundaries: &[usize],
+    weights: &MatchWeights,
+) -> Option<Score> {
+    let Ok(found) = pattern::find(pattern, name) else {
+        return substring(chars, query, boundaries, weights);
+    };
+    let range = found?;
+    let start = chars.iter().position(|(i, _)| *i == range.start);
+    let mut score = 100;
+    if start.is_some_and(|start| boundaries.contains(&start)) {
+        score += weights.word_start_bonus;
+    }
+    Some(Score {
+        score,
+        positions: chars
+            .iter()
+            .map(|(i, _)| *i)
+            .filter(|i| range.contains(i))
+            .collect(),
+    })
+}
+
+/// `query` as the initials of words in `chars`, in order.
+fn initials(
+    chars: &[(usize, char)],
+    query: &[char],
+    boundaries: &[usize],
+    weights: &MatchWeights,
+) -> Option<Score> {
+    let mut positions = Vec::new();
+    let mut remaining = query.iter().peekable();
+    for &i in boundaries {
+        if remaining.peek().is_some_and(|q| **q == fold(chars[i].1)) {
+            remaining.next();
+            positions.push(chars[i].0);
+        }
+    }
+    if remaining.peek().is_some() {
+        return None;
+    }
+    Some(Score {
+        score: weights.initials_bonus * query.len() as i64,
+        positions,
+    })
+}
+
+/// The best alignment of `query` as a subsequence of `chars`, rewarding word starts and
+/// runs and penalizing gaps, like fzf. Scores stay below any substring match.
+fn fuzzy(chars: &[(usize, char)], query: &[char], boundaries: &[usize]) -> Option<Score> {
+    let n = chars.len();
+    // best[i][j]: the score of `query[..=i]` with `query[i]` matched at `chars[j]`.
+    let mut best = vec![vec![None::<i64>; n]; query.len()];
+    let mut from = vec![vec![0; n]; query.len()];
+    for (i, q) in query.iter().enumerate() {
+        // The best `best[i - 1][k] + k` for `k < j - 1`, so gaps cost by their length.
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in 0..n {
+            if j >= 2 && i > 0 {
+                if let Some(score) = best[i - 1][j - 2] {
+                    let candidate = score + (j - 2) as i64;
+                    if gapped.is_none_or(|(best, _)| candidate > best) {
+                        gapped = Some((candidate, j - 2));
+                    }
+                }
+            }
+            if *q != fold(chars[j].1) {
+                continue;
+            }
+            let mut score = FUZZY_MATCH;
+            if boundaries.contains(&j) {
+                score += FUZZY_BOUNDARY;
+            }
+            if i == 0 {
+                best[i][j] = Some(score);
+                continue;
+            }
+            let consecutive = j
+                .checked_sub(1)
+                .and_then(|k| best[i - 1][k].map(|previous| (previous + FUZZY_CONSECUTIVE, k)));
+            let gap = gapped.map(|(best, k)| (best - j as i64 + 2 - FUZZY_GAP, k));
+            let Some((previous, k)) = consecutive.into_iter().chain(gap).max_by_key(|(s, _)| *s)
+            else {
+                continue;
+            };
+            best[i][j] = Some(previous + score);
+            from[i][j] = k;
+        }
+    }
+    let last = query.len() - 1;
+    let (mut j, score) = (0..n)
+        .filter_map(|j| Some((j, best[last][j]?)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; query.len()];
+    for i in (0..query.len()).rev() {
+        positions[i] = chars[j].0;
+        j = from[i][j];
+    }
+    let max = query.len() as i64 * (FUZZY_MATCH + FUZZY_BOUNDARY + FUZZY_CONSECUTIVE);
+    Some(Score {
+        score: score.max(0) * 100 / (max + 1),
+        positions,
+    })
+}
+
+/// The best alignment of `query` as a subsequence of `chars` by skim's scoring: bonuses
+/// for word starts, camel case and runs, the first character's counting double, and
+/// penalties for gaps by their length.
+fn fuzzy_skim(chars: &[(usize, char)], query: &[char]) -> Option<Score> {
+    let n = chars.len();
+    let bonuses = (0..n)
+        .map(|j| skim_bonus(j.checked_sub(1).map(|k| chars[k].1), chars[j].1))
+        .collect::<Vec<i64>>();
+    // best[i][j]: the score of `query[..=i]` with `query[i]` matched at `chars[j]`, and
+    // the bonus its run of consecutive matches started with.
+    let mut best = vec![vec![None::<(i64, i64)>; n]; query.len()];
+    let mut from = vec![vec![0; n]; query.len()];
+    for (i, q) in query.iter().enumerate() {
+        // The best `best[i - 1][k] + k` for `k < j - 1`, so gaps cost by their length.
+        let mut gapped: Option<(i64, usize)> = None;
+        for j in 0..n {
+            if j >= 2 && i > 0 {
+                if let Some((score, _)) = best[i - 1][j - 2] {
+                    let candidate = score - (j - 2) as i64 * SKIM_GAP_EXTENSION;
+                    if gapped.is_none_or(|(best, _)| candidate > best) {
+                        gapped = Some((candidate, j - 2));
+                    }
+                }
+            }
+            if *q != fold(chars[j].1) {
+                continue;
+            }
+            if i == 0 {
+                let bonus = bonuses[j] * SKIM_FIRST_CHAR_MULTIPLIER;
+                best[i][j] = Some((SKIM_MATCH + bonus, bonuses[j]));
+                continue;
+            }
+            let consecutive = j.checked_sub(1).and_then(|k| {
+                let (previous, run) = best[i - 1][k]?;
+                let bonus = bonuses[j].max(run).max(SKIM_CONSECUTIVE);
+                Some((previous + SKIM_MATCH + bonus, run, k))
+            });
+            let gap = gapped.map(|(best, k)| {
+                let skipped = (j - k - 1) as i64;
+                let penalty = SKIM_GAP_START + (skipped - 1) * SKIM_GAP_EXTENSION;
+                let previous = best + k as i64 * SKIM_GAP_EXTENSION;
+                (previous + penalty + SKIM_MATCH + bonuses[j], bonuses[j], k)
+            });
+            let Some((score, run, k)) = consecutive
+                .into_iter()
+                .chain(gap)
+                .max_by_key(|(score, _, _)| *score)
+            else {
+                continue;
+            };
+            best[i][j] = Some((score, run));
+            from[i][j] = k;
+        }
+    }
+    let last = query.len() - 1;
+    let (mut j, score) = (0..n)
+        .filter_map(|j| Some((j, best[last][j]?.0)))
+        .max_by_key(|&(j, score)| (score, std::cmp::Reverse(j)))?;
+    let mut positions = vec![0; query.len()];
+    for i in (0..query.len()).rev() {
+        positions[i] = chars[j].0;
+        j = from[i][j];
+    }
+    Some(Score { score, positions })
+}
+
+/// skim's bonus for matching `c` after `previous`.
+fn skim_bonus(previous: Option<char>, c: char) -> i64 {
+    let word = |c: char| c.is_alphanumeric();
+    match previous {
+        _ if !word(c) => SKIM_NON_WORD,
+        None => SKIM_BOUNDARY,
+        Some(previous) if !word(previous) => SKIM_BOUNDARY,
+        Some(previous)
+            if (previous.is_lowercase() && c.is_uppercase())
+                || (!previous.is_numeric() && c.is_numeric()) =>
+        {
+            SKIM_CAMEL
+        }
+        Some(_) => 0,
+    }
+}
+
+fn is_boundary(chars: &[(usize, char)], i: usize) -> bool {
+    let c = chars[i].1;
+    if !c.is_alphanumeric() {
+        return false;
+    }
+    match i.checked_sub(1).map(|previous| chars[previous].1) {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric() || (c.is_uppercase() && previous.is_lowercase())
+        }
+    }
+}
+
+fn fold(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NAMES: [&str; 5] = [
+        "Firefox",
+        "GIMP Image Editor",
+        "LibreOffice Writer",
+        "org.gnome.Nautilus",
+        "VLC media player",
+    ];
+
+    const QUERIES: [&str; 10] = [
+        "fire", "FFX", "gie", "lib wri", "naut", "ONN", "vmp", "xof", "zzz", "",
+    ];
+
+    #[test]
+    fn prefilter_keeps_every_match() {
+        let weights = MatchWeights::default();
+        for name in NAMES {
+            for query in QUERIES {
+                let matches = score_unfiltered(name, query, &weights).is_some();
+                assert_eq!(score(name, query, &weights).is_some(), matches);
+                if matches {
+                    assert!(may_match(name, query), "{:?} in {:?}", query, name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn algorithms_differ_in_what_matches() {
+        let with = |algorithm| MatchWeights {
+            algorithm,
+            ..MatchWeights::default()
+        };
+        for (name, query, auto, substring, fuzzy, regex) in [
+            ("Firefox", "fox", true, true, true, true),
+            ("GIMP Image Editor", "gie", true, false, true, false),
+            ("Firefox", "ffx", true, false, true, false),
+            ("Firefox", "xof", false, false, false, false),
+            ("Firefox", "^fire|vlc", false, false, false, true),
+            ("Fire-fox", r"E\Wf", false, false, false, true),
+            ("Firefox", "fire(", false, false, false, false),
+            ("Firefox (beta)", "fox (", true, true, true, true),
+        ] {
+            assert_eq!(score(name, query, &with(Algorithm::Auto)).is_some(), auto);
+            assert_eq!(
+                score(name, query, &with(Algorithm::Substring)).is_some(),
+                substring
+            );
+            assert_eq!(score(name, query, &with(Algorithm::Fuzzy)).is_some(), fuzzy);
+            assert_eq!(
+                score(name, query, &with(Algorithm::FuzzySkim)).is_some(),
+                fuzzy
+            );
+            assert_eq!(score(name, query, &with(Algorithm::Regex)).is_some(), regex);
+        }
+    }
+
+    #[test]
+    fn skim_prefers_word_starts_and_runs() {
+        let weights = MatchWeights {
+            algorithm: Algorithm::FuzzySkim,
+            ..MatchWeights::default()
+        };
+        let score = |name| score(name, "gie", &weights).unwrap();
+        assert_eq!(score("GIMP Image Editor").positions, vec![0, 1, 11]);
+        assert!(score("GIMP Image Editor").score > score("Fidgies").score);
+        assert_eq!(score("Fidgies").positions, vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn regex_highlights_the_whole_match() {
+        let weights = MatchWeights {
+            algorithm: Algorithm::Regex,
+            ..MatchWeights::default()
+        };
+        let found = score("LibreOffice Writer", "o.*w", &weights).unwrap();
+        assert_eq!(found.positions, (5..13).collect::<Vec<usize>>());
+        assert!(!weights.narrows());
+    }
+
+    #[test]
+    fn prefilter_rejects_characters_out_of_order() {
+        assert!(may_match("Firefox", "ffx"));
+        assert!(!may_match("Firefox", "xof"));
+        assert!(!may_match("Firefox", "fireffox"));
+    }
+}
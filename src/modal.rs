@@ -0,0 +1,92 @@
+use ratatui::{buffer::Buffer, layout::Rect, widgets::Widget};
+
+use crate::{
+    action_menu::ActionMenu,
+    confirm::ConfirmDialog,
+    desktop_entry::DesktopEntry,
+    entry_editor::EntryEditor,
+    settings::SettingsMenu,
+    sources::{Action, Item},
+};
+
+/// What a confirmed dialog goes on to do.
+#[derive(Debug)]
+pub enum PendingAction {
+    /// Launching the entry with the argv the dialog showed.
+    Elevate(DesktopEntry, Vec<String>),
+    /// An action of the source at the index.
+    Source(usize, Action, Option<Item>),
+}
+
+#[derive(Debug)]
+pub struct Confirmation {
+    pub dialog: ConfirmDialog,
+    pub action: PendingAction,
+}
+
+/// An overlay drawn above the list.
+#[derive(Debug)]
+pub enum Modal {
+    Confirm(Confirmation),
+    Actions(ActionMenu),
+    /// The workspaces to launch an entry on, as an action menu of their names.
+    Workspaces(ActionMenu),
+    Settings(SettingsMenu),
+    Editor(EntryEditor),
+}
+
+impl Modal {
+    /// Whether the modal changes on its own and needs redrawing without input.
+    fn is_animating(&self) -> bool {
+        match self {
+            Modal::Confirm(confirmation) => confirmation.dialog.remaining().is_some(),
+            Modal::Actions(_) | Modal::Workspaces(_) | Modal::Settings(_) | Modal::Editor(_) => {
+                false
+            }
+        }
+    }
+
+    fn render(&self, area: Rect, buf: &mut Buffer) {
+        match self {
+            Modal::Confirm(confirmation) => confirmation.dialog.render(area, buf),
+            Modal::Actions(menu) | Modal::Workspaces(menu) => menu.render(area, buf),
+            Modal::Settings(menu) => menu.render(area, buf),
+            Modal::Editor(editor) => editor.render(area, buf),
+        }
+    }
+}
+
+/// Overlays stacked above the list, drawn bottom to top. Only the topmost one
+/// receives keys.
+#[derive(Debug, Default)]
+pub struct ModalStack {
+    modals: Vec<Modal>,
+}
+
+impl ModalStack {
+    pub fn push(&mut self, modal: Modal) {
+        self.modals.push(modal);
+    }
+
+    pub fn pop(&mut self) -> Option<Modal> {
+        self.modals.pop()
+    }
+
+    pub fn top(&self) -> Option<&Modal> {
+        self.modals.last()
+    }
+
+    pub fn top_mut(&mut self) -> Option<&mut Modal> {
+        self.modals.last_mut()
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.modals.iter().any(Modal::is_animating)
+    }
+
+    pub fn render(&self, area: Rect, buf: &mut Buffer) {
+        for modal in &self.modals {
+            modal.render(area, buf);
+        }
+    }
+}
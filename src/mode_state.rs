@@ -0,0 +1,56 @@
+use std::{collections::HashMap, fs, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{paths, vault};
+
+/// The query and selection a mode was left with.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ModeState {
+    pub query: String,
+    /// The ID of the selected item.
+    pub selected: Option<String>,
+}
+
+/// Mode states keyed by the mode's keyword, with applications under `""`. Only
+/// persisted when `path` is set.
+#[derive(Debug, Default)]
+pub struct ModeStates {
+    path: Option<PathBuf>,
+    states: HashMap<String, ModeState>,
+}
+
+impl ModeStates {
+    pub fn load(persist: bool) -> Self {
+        if !persist {
+            return Self::default();
+        }
+        let path = paths::state_dir().map(|dir| dir.join("modes.json"));
+        let states = path
+            .as_deref()
+            .and_then(|path| vault::read(path).ok())
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+        Self { path, states }
+    }
+
+    pub fn get(&self, keyword: &str) -> ModeState {
+        self.states.get(keyword).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, keyword: &str, state: ModeState) {
+        self.states.insert(keyword.to_string(), state);
+    }
+
+    pub fn save(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(content) = serde_json::to_string(&self.states) {
+            let _ = vault::write(path, &content);
+        }
+    }
+}
@@ -0,0 +1,41 @@
+use crate::sources::Item;
+
+/// The names of the current items in one buffer, which the matcher loop reads in order
+/// instead of following a pointer to each item's own allocation.
+#[derive(Debug, Default)]
+pub struct Names {
+    text: String,
+    /// Where each name ends in `text`, by item index.
+    ends: Vec<u32>,
+}
+
+impl Names {
+    pub fn new(items: &[Item]) -> Self {
+        let mut names = Self {
+            text: String::with_capacity(items.iter().map(|item| item.name.len()).sum()),
+            ends: Vec::with_capacity(items.len()),
+        };
+        for item in items {
+            names.push(&item.name);
+        }
+        names
+    }
+
+    pub fn push(&mut self, name: &str) {
+        self.text.push_str(name);
+        self.ends.push(self.text.len() as u32);
+    }
+
+    /// The name of the item at `index`.
+    pub fn get(&self, index: usize) -> &str {
+        let start = index
+            .checked_sub(1)
+            .map_or(0, |previous| self.ends[previous]);
+        &self.text[start as usize..self.ends[index] as usize]
+    }
+
+    pub fn clear(&mut self) {
+        self.text.clear();
+        self.ends.clear();
+    }
+}
@@ -0,0 +1,103 @@
+use std::{
+    collections::HashMap,
+    io,
+    process::Command,
+    sync::{Mutex, OnceLock},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde::Deserialize;
+
+use crate::config::Config;
+
+/// Network access of online sources, which make no requests unless enabled here.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub enabled: bool,
+    /// Refuses all requests while keeping the other settings, as `--offline` does.
+    pub offline: bool,
+    /// The sources that may make requests, by keyword, e.g. `web`.
+    pub allow: Vec<String>,
+    /// Seconds a request may take before it's given up.
+    pub timeout: u64,
+    /// Milliseconds between two requests of the same source, later ones waiting.
+    pub interval: u64,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            offline: false,
+            allow: Vec::new(),
+            timeout: 3,
+            interval: 250,
+        }
+    }
+}
+
+impl NetworkConfig {
+    pub fn allows(&self, source: &str) -> bool {
+        self.enabled && !self.offline && self.allow.iter().any(|allowed| allowed == source)
+    }
+}
+
+static CONFIG: OnceLock<NetworkConfig> = OnceLock::new();
+
+/// When each source may make its next request.
+static NEXT_REQUEST: Mutex<Option<HashMap<String, Instant>>> = Mutex::new(None);
+
+pub fn init(config: &Config) {
+    let _ = CONFIG.set(config.network.clone());
+}
+
+/// Whether `source` may make requests, for sources to skip preparing them otherwise.
+pub fn allows(source: &str) -> bool {
+    CONFIG.get().is_some_and(|config| config.allows(source))
+}
+
+/// Fetches `url` with `curl` for `source`, the only way sources reach the network.
+/// Refused unless the config allows `source`, and waits for the source's turn when it
+/// made a request less than `interval` ago, so it's best called off the UI thread.
+pub fn get(source: &str, url: &str) -> io::Result<String> {
+    let Some(config) = CONFIG.get().filter(|config| config.allows(source)) else {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("network access of {} is disabled", source),
+        ));
+    };
+    wait_for_turn(source, Duration::from_millis(config.interval));
+    let output = Command::new("curl")
+        .args([
+            "--silent",
+            "--show-error",
+            "--fail",
+            "--proto",
+            "=https,http",
+        ])
+        .args(["--max-time", &config.timeout.to_string()])
+        .arg(url)
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ));
+    }
+    String::from_utf8(output.stdout).map_err(io::Error::other)
+}
+
+fn wait_for_turn(source: &str, interval: Duration) {
+    let turn = {
+        let mut next = NEXT_REQUEST
+            .lock()
+            .unwrap_or_else(|error| error.into_inner());
+        let next = next.get_or_insert_with(HashMap::new);
+        let now = Instant::now();
+        let turn = next.get(source).map_or(now, |&turn| turn.max(now));
+        next.insert(source.to_string(), turn + interval);
+        turn
+    };
+    thread::sleep(turn.saturating_duration_since(Instant::now()));
+}
@@ -0,0 +1,56 @@
+use std::{io, process::Command};
+
+/// `busctl` arguments addressing the portal's OpenURI interface.
+const PORTAL: [&str; 5] = [
+    "--user",
+    "call",
+    "org.freedesktop.portal.Desktop",
+    "/org/freedesktop/portal/desktop",
+    "org.freedesktop.portal.OpenURI",
+];
+
+/// Opens a URL or local file with the user's default app, through the XDG desktop
+/// portal when it's available and with `xdg-open` otherwise.
+pub fn open(target: &str) -> io::Result<()> {
+    let portal = match target.strip_prefix("file://") {
+        Some(path) => open_file(path),
+        None if target.contains("://") => open_uri(target),
+        None => open_file(target),
+    };
+    if portal.is_ok() {
+        return Ok(());
+    }
+    let status = Command::new("xdg-open").arg(target).output()?.status;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("xdg-open failed with {}", status)))
+    }
+}
+
+fn open_uri(uri: &str) -> io::Result<()> {
+    portal(
+        Command::new("busctl")
+            .args(PORTAL)
+            .args(["OpenURI", "ssa{sv}", "", uri, "0"]),
+    )
+}
+
+/// Files are handed to the portal as a file descriptor, opened by the shell as fd 3.
+fn open_file(path: &str) -> io::Result<()> {
+    let script = format!(
+        "exec busctl {} OpenFile 'sha{{sv}}' '' 3 0 3<\"$1\"",
+        PORTAL.join(" ")
+    );
+    portal(Command::new("sh").args(["-c", &script, "sh", path]))
+}
+
+fn portal(command: &mut Command) -> io::Result<()> {
+    let output = command.output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(stderr.trim().to_string()))
+    }
+}
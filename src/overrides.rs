@@ -0,0 +1,67 @@
+use std::{collections::BTreeMap, fs, io, path::PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{desktop_entry::DesktopEntry, paths};
+
+/// The user's own name or icon for an entry, shown instead of its desktop file's.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Override {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+}
+
+impl Override {
+    fn is_empty(&self) -> bool {
+        self.name.is_none() && self.icon.is_none()
+    }
+}
+
+/// `$XDG_CONFIG_HOME/launcher/overrides.toml`, keyed by desktop file ID.
+fn path() -> Option<PathBuf> {
+    paths::config_home().map(|dir| dir.join("launcher/overrides.toml"))
+}
+
+pub fn load() -> BTreeMap<String, Override> {
+    path()
+        .and_then(|path| fs::read_to_string(path).ok())
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Saves the override of the entry `id`, or drops it if it overrides nothing.
+pub fn save(id: &str, value: Override) -> io::Result<()> {
+    let path = path().ok_or_else(|| io::Error::other("no config dir"))?;
+    let mut overrides = load();
+    if value.is_empty() {
+        overrides.remove(id);
+    } else {
+        overrides.insert(id.to_string(), value);
+    }
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(path, toml::to_string(&overrides).map_err(io::Error::other)?)
+}
+
+/// Renames `entries` and changes their icons as overridden.
+pub fn apply(entries: &mut [DesktopEntry]) {
+    let overrides = load();
+    if overrides.is_empty() {
+        return;
+    }
+    for entry in entries {
+        let Some(value) = overrides.get(&entry.id) else {
+            continue;
+        };
+        if let Some(name) = &value.name {
+            entry.name = name.clone();
+        }
+        if let Some(icon) = &value.icon {
+            entry.icon = icon.as_str().into();
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// The config used when `$XDG_CONFIG_HOME/launcher/launcher.toml` doesn't exist.
+const BUNDLED_CONFIG: &str = include_str!("launcher.toml");
+
+/// `$HOME`, or `None` when unset or empty.
+pub fn home() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .filter(|home| !home.is_empty())
+        .map(PathBuf::from)
+}
+
+/// The directory in `variable`, or `fallback` under the home directory. Relative paths
+/// are ignored as the XDG Base Directory spec requires.
+fn base_dir(variable: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| home().map(|home| home.join(fallback)))
+}
+
+/// `$XDG_CONFIG_HOME`, by default `~/.config`.
+pub fn config_home() -> Option<PathBuf> {
+    base_dir("XDG_CONFIG_HOME", ".config")
+}
+
+/// `$XDG_CACHE_HOME`, by default `~/.cache`.
+pub fn cache_home() -> Option<PathBuf> {
+    base_dir("XDG_CACHE_HOME", ".cache")
+}
+
+/// `$XDG_DATA_HOME`, by default `~/.local/share`.
+pub fn data_home() -> Option<PathBuf> {
+    base_dir("XDG_DATA_HOME", ".local/share")
+}
+
+/// `$XDG_STATE_HOME`, by default `~/.local/state`.
+pub fn state_home() -> Option<PathBuf> {
+    base_dir("XDG_STATE_HOME", ".local/state")
+}
+
+/// `$XDG_RUNTIME_DIR`, which has no fallback as it must be private to the user.
+pub fn runtime_dir() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+}
+
+/// `$XDG_DATA_DIRS` in order of preference, by default `/usr/local/share` and
+/// `/usr/share`.
+pub fn data_dirs() -> Vec<PathBuf> {
+    let dirs = env::var("XDG_DATA_DIRS").unwrap_or_default();
+    let dirs = dirs
+        .split(':')
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .collect::<Vec<PathBuf>>();
+    if dirs.is_empty() {
+        vec![
+            PathBuf::from("/usr/local/share"),
+            PathBuf::from("/usr/share"),
+        ]
+    } else {
+        dirs
+    }
+}
+
+/// `$XDG_CONFIG_HOME/launcher/launcher.toml`, whether or not it exists.
+pub fn config_file() -> Option<PathBuf> {
+    config_home().map(|dir| dir.join("launcher/launcher.toml"))
+}
+
+/// The config file at `path`, or the example config built in when there is none.
+pub fn read_config(path: Option<&Path>) -> io::Result<String> {
+    match path.filter(|path| path.exists()) {
+        Some(path) => fs::read_to_string(path),
+        None => Ok(BUNDLED_CONFIG.to_string()),
+    }
+}
+
+/// Where the query history and the other remembered state live, `$XDG_STATE_HOME/launcher`.
+pub fn state_dir() -> Option<PathBuf> {
+    state_home().map(|dir| dir.join("launcher"))
+}
+
+/// Where the launch history behind frecency lives, `$XDG_DATA_HOME/launcher`.
+pub fn data_dir() -> Option<PathBuf> {
+    data_home().map(|dir| dir.join("launcher"))
+}
+
+/// The socket a launcher started with `--daemon` listens on, `$XDG_RUNTIME_DIR/launcher.sock`
+/// or else in the state dir.
+pub fn daemon_socket() -> Option<PathBuf> {
+    runtime_dir()
+        .map(|dir| dir.join("launcher.sock"))
+        .or_else(|| state_dir().map(|dir| dir.join("daemon.sock")))
+}
@@ -0,0 +1,169 @@
+use std::{
+    cell::{Cell, RefCell},
+    ops::Range,
+};
+
+use regex_syntax::{
+    hir::{Class, Hir, HirKind, Look},
+    ParserBuilder,
+};
+
+/// Steps a search may take per name before giving up on it, so a pattern like `(a*)*b`
+/// can't stall the list.
+const STEP_LIMIT: usize = 100_000;
+
+thread_local! {
+    /// The last pattern parsed, as every name is matched against the same one in a row.
+    static PARSED: RefCell<Option<(String, Option<Hir>)>> = const { RefCell::new(None) };
+}
+
+/// The byte range of the leftmost match of the regular expression `pattern` in `text`,
+/// ignoring case. `Err` if `pattern` doesn't parse.
+pub fn find(pattern: &str, text: &str) -> Result<Option<Range<usize>>, ()> {
+    PARSED.with_borrow_mut(|parsed| {
+        if parsed.as_ref().is_none_or(|(cached, _)| cached != pattern) {
+            let hir = ParserBuilder::new()
+                .case_insensitive(true)
+                .build()
+                .parse(pattern)
+                .ok();
+            *parsed = Some((pattern.to_string(), hir));
+        }
+        let Some((_, Some(hir))) = parsed.as_ref() else {
+            return Err(());
+        };
+        let search = Search {
+            text,
+            steps: Cell::new(0),
+        };
+        let starts = text.char_indices().map(|(i, _)| i).chain([text.len()]);
+        for start in starts {
+            let mut end = None;
+            if search.at(hir, start, &mut |at| {
+                end = Some(at);
+                true
+            }) {
+                return Ok(end.map(|end| start..end));
+            }
+            if search.steps.get() > STEP_LIMIT {
+                break;
+            }
+        }
+        Ok(None)
+    })
+}
+
+/// A backtracking search in `text`, trying alternatives in order like Perl does.
+struct Search<'a> {
+    text: &'a str,
+    steps: Cell<usize>,
+}
+
+impl Search<'_> {
+    /// Whether `hir` matches at `at` followed by whatever `next` accepts after it.
+    fn at(&self, hir: &Hir, at: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+        self.steps.set(self.steps.get() + 1);
+        if self.steps.get() > STEP_LIMIT {
+            return false;
+        }
+        match hir.kind() {
+            HirKind::Empty => next(at),
+            HirKind::Literal(literal) => {
+                self.text.as_bytes()[at..].starts_with(&literal.0) && next(at + literal.0.len())
+            }
+            HirKind::Class(Class::Unicode(class)) => {
+                let Some(c) = self.text[at..].chars().next() else {
+                    return false;
+                };
+                class
+                    .ranges()
+                    .iter()
+                    .any(|range| (range.start()..=range.end()).contains(&c))
+                    && next(at + c.len_utf8())
+            }
+            HirKind::Class(Class::Bytes(class)) => {
+                let Some(&byte) = self.text.as_bytes().get(at) else {
+                    return false;
+                };
+                // A byte class may match inside a character, which can't end a match.
+                let end = at + 1;
+                class
+                    .ranges()
+                    .iter()
+                    .any(|range| (range.start()..=range.end()).contains(&byte))
+                    && self.text.is_char_boundary(end)
+                    && next(end)
+            }
+            HirKind::Look(look) => self.looks(*look, at) && next(at),
+            HirKind::Repetition(repetition) => self.repeat(
+                &repetition.sub,
+                (repetition.min, repetition.max, repetition.greedy),
+                0,
+                at,
+                next,
+            ),
+            HirKind::Capture(capture) => self.at(&capture.sub, at, next),
+            HirKind::Concat(hirs) => self.concat(hirs, at, next),
+            HirKind::Alternation(hirs) => hirs.iter().any(|hir| self.at(hir, at, next)),
+        }
+    }
+
+    fn concat(&self, hirs: &[Hir], at: usize, next: &mut dyn FnMut(usize) -> bool) -> bool {
+        match hirs.split_first() {
+            None => next(at),
+            Some((first, rest)) => self.at(first, at, &mut |end| self.concat(rest, end, next)),
+        }
+    }
+
+    /// `sub` repeated between `min` and `max` times after `count` repetitions so far.
+    fn repeat(
+        &self,
+        sub: &Hir,
+        (min, max, greedy): (u32, Option<u32>, bool),
+        count: u32,
+        at: usize,
+        next: &mut dyn FnMut(usize) -> bool,
+    ) -> bool {
+        let done = count >= min;
+        if done && !greedy && next(at) {
+            return true;
+        }
+        if max.is_none_or(|max| count < max) {
+            let more = self.at(sub, at, &mut |end| {
+                // Repeating what matched nothing can't get any further.
+                (end != at || !done) && self.repeat(sub, (min, max, greedy), count + 1, end, next)
+            });
+            if more {
+                return true;
+            }
+        }
+        done && greedy && next(at)
+    }
+
+    fn looks(&self, look: Look, at: usize) -> bool {
+        let before = self.text[..at].chars().next_back();
+        let after = self.text[at..].chars().next();
+        let ascii = |c: Option<char>| c.is_some_and(|c| c.is_ascii_alphanumeric() || c == '_');
+        let unicode = |c: Option<char>| c.is_some_and(regex_syntax::is_word_character);
+        match look {
+            Look::Start => at == 0,
+            Look::End => at == self.text.len(),
+            Look::StartLF => before.is_none_or(|c| c == '\n'),
+            Look::EndLF => after.is_none_or(|c| c == '\n'),
+            Look::StartCRLF => before.is_none_or(|c| c == '\n' || c == '\r'),
+            Look::EndCRLF => after.is_none_or(|c| c == '\n' || c == '\r'),
+            Look::WordAscii => ascii(before) != ascii(after),
+            Look::WordAsciiNegate => ascii(before) == ascii(after),
+            Look::WordUnicode => unicode(before) != unicode(after),
+            Look::WordUnicodeNegate => unicode(before) == unicode(after),
+            Look::WordStartAscii => !ascii(before) && ascii(after),
+            Look::WordEndAscii => ascii(before) && !ascii(after),
+            Look::WordStartUnicode => !unicode(before) && unicode(after),
+            Look::WordEndUnicode => unicode(before) && !unicode(after),
+            Look::WordStartHalfAscii => !ascii(before),
+            Look::WordEndHalfAscii => !ascii(after),
+            Look::WordStartHalfUnicode => !unicode(before),
+            Look::WordEndHalfUnicode => !unicode(after),
+        }
+    }
+}
@@ -0,0 +1,131 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::paths;
+
+/// Sessions kept in the log, the oldest dropped first.
+const MAX_SESSIONS: usize = 200;
+
+/// The latencies of one run of the launcher, in milliseconds.
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Session {
+    first_frame: f64,
+    scan: f64,
+    keystrokes: Vec<f64>,
+}
+
+/// Measures a run for `perf_telemetry`, saved when it ends.
+#[derive(Debug)]
+pub struct Recorder {
+    started: Instant,
+    drawn: bool,
+    /// When the oldest key not drawn yet was pressed.
+    key_pressed: Option<Instant>,
+    session: Session,
+}
+
+impl Recorder {
+    pub fn new(started: Instant) -> Self {
+        Self {
+            started,
+            drawn: false,
+            key_pressed: None,
+            session: Session::default(),
+        }
+    }
+
+    pub fn scanned(&mut self, duration: Duration) {
+        self.session.scan = millis(duration);
+    }
+
+    pub fn key_pressed(&mut self) {
+        self.key_pressed.get_or_insert_with(Instant::now);
+    }
+
+    pub fn frame_drawn(&mut self) {
+        if !self.drawn {
+            self.drawn = true;
+            self.session.first_frame = millis(self.started.elapsed());
+        }
+        if let Some(pressed) = self.key_pressed.take() {
+            self.session.keystrokes.push(millis(pressed.elapsed()));
+        }
+    }
+
+    /// Appends the run to the log in the state dir.
+    pub fn save(&self) -> io::Result<()> {
+        let path = log_path().ok_or_else(|| io::Error::other("no state dir"))?;
+        let mut lines = fs::read_to_string(&path)
+            .map(|log| log.lines().map(String::from).collect::<Vec<String>>())
+            .unwrap_or_default();
+        lines.push(serde_json::to_string(&self.session)?);
+        let keep = lines.len().saturating_sub(MAX_SESSIONS);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let mut file = fs::File::create(&path)?;
+        for line in &lines[keep..] {
+            writeln!(file, "{}", line)?;
+        }
+        Ok(())
+    }
+}
+
+/// Prints the percentiles of the latencies logged, for `launcher perf report`.
+pub fn report() -> io::Result<()> {
+    let path = log_path().ok_or_else(|| io::Error::other("no state dir"))?;
+    let sessions = fs::read_to_string(&path)
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Session>(line).ok())
+        .collect::<Vec<Session>>();
+    if sessions.is_empty() {
+        println!(
+            "nothing recorded in {}, set perf_telemetry = true",
+            path.display()
+        );
+        return Ok(());
+    }
+    println!("{} runs recorded in {}", sessions.len(), path.display());
+    println!("{:<20}{:>10}{:>10}{:>10}", "", "p50", "p90", "p99");
+    let rows: [(&str, Vec<f64>); 3] = [
+        (
+            "startup to frame",
+            sessions.iter().map(|s| s.first_frame).collect(),
+        ),
+        ("scan", sessions.iter().map(|s| s.scan).collect()),
+        (
+            "keystroke to frame",
+            sessions.iter().flat_map(|s| s.keystrokes.clone()).collect(),
+        ),
+    ];
+    for (name, mut samples) in rows {
+        samples.sort_by(f64::total_cmp);
+        let [p50, p90, p99] = [50, 90, 99].map(|p| match percentile(&samples, p) {
+            Some(millis) => format!("{:.1}ms", millis),
+            None => "-".to_string(),
+        });
+        println!("{:<20}{:>10}{:>10}{:>10}", name, p50, p90, p99);
+    }
+    Ok(())
+}
+
+fn log_path() -> Option<PathBuf> {
+    paths::state_dir().map(|dir| dir.join("perf.jsonl"))
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The nearest-rank percentile `p` of sorted `samples`, if there are any.
+pub fn percentile(samples: &[f64], p: usize) -> Option<f64> {
+    let rank = (samples.len() * p).div_ceil(100).max(1);
+    samples.get(rank - 1).copied()
+}
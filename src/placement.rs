@@ -0,0 +1,89 @@
+use std::{
+    env, io, thread,
+    time::{Duration, Instant},
+};
+
+use crate::{compositor, desktop_entry::DesktopEntry, launch, running};
+
+/// How long `launcher place-window` waits for the app to open its window.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Moving the first window an app opens to a workspace, prepared before launching it.
+#[derive(Debug)]
+pub struct Placement {
+    workspace: String,
+    app_ids: Vec<String>,
+    /// The windows open before the launch, which can't be the app's.
+    known: Vec<String>,
+}
+
+impl Placement {
+    pub fn prepare(entry: &DesktopEntry, workspace: &str) -> io::Result<Self> {
+        let compositor = compositor::detect().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::Unsupported, "no compositor integration")
+        })?;
+        Ok(Self {
+            workspace: workspace.to_string(),
+            app_ids: app_ids(entry),
+            known: compositor
+                .windows()?
+                .into_iter()
+                .map(|window| window.id)
+                .collect(),
+        })
+    }
+
+    /// Waits for the window with `launcher place-window` in the background, as the
+    /// launcher usually exits before the app opens one.
+    pub fn start(&self) -> io::Result<()> {
+        let argv = [
+            env::current_exe()?.to_string_lossy().to_string(),
+            "place-window".to_string(),
+            self.workspace.clone(),
+            self.app_ids.join(","),
+            self.known.join(","),
+        ];
+        launch::spawn_background(&argv)
+    }
+}
+
+/// What the windows of `entry` may be identified as by the compositor: its desktop file
+/// ID, the last part of a reverse-DNS one like `org.kde.okular`, and its program.
+fn app_ids(entry: &DesktopEntry) -> Vec<String> {
+    let id = entry.id.trim_end_matches(".desktop").to_lowercase();
+    let mut app_ids = vec![id.clone()];
+    app_ids.extend(id.rsplit_once('.').map(|(_, name)| name.to_string()));
+    app_ids.extend(running::program(&entry.exec).map(|program| program.to_lowercase()));
+    app_ids.sort();
+    app_ids.dedup();
+    app_ids
+}
+
+/// `launcher place-window <workspace> <app ids> <known windows>` moves the first new
+/// window with one of the comma-separated app IDs to the workspace.
+pub fn run(args: &[String]) -> io::Result<()> {
+    let [workspace, app_ids, known] = args else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "usage: launcher place-window <workspace> <app ids> <known windows>",
+        ));
+    };
+    let app_ids = app_ids.split(',').collect::<Vec<&str>>();
+    let known = known.split(',').collect::<Vec<&str>>();
+    let compositor = compositor::detect()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::Unsupported, "no compositor integration"))?;
+    let started = Instant::now();
+    while started.elapsed() < TIMEOUT {
+        let window = compositor.windows()?.into_iter().find(|window| {
+            !known.contains(&window.id.as_str())
+                && app_ids.contains(&window.app_id.to_lowercase().as_str())
+        });
+        if let Some(window) = window {
+            return compositor.move_to_workspace(&window, workspace);
+        }
+        thread::sleep(POLL_INTERVAL);
+    }
+    Err(io::Error::new(io::ErrorKind::TimedOut, "no window opened"))
+}
@@ -0,0 +1,175 @@
+use std::{
+    io::{BufRead, BufReader},
+    os::unix::process::CommandExt,
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver},
+        Arc, Mutex,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    text::{Line, Span},
+    widgets::{Block, Paragraph, Widget},
+};
+
+use crate::ansi;
+
+/// Lines of output kept from a preview command.
+const MAX_LINES: usize = 1000;
+
+/// How long a preview command may run before it's killed, keeping what it printed.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// The output of a command previewing the selected item, shown beside the list.
+#[derive(Debug, Default)]
+pub struct Preview {
+    /// The item previewed, if any.
+    id: Option<String>,
+    lines: Vec<Line<'static>>,
+    scroll: u16,
+    output: Option<Receiver<Vec<Line<'static>>>>,
+    /// The running command and when it started, killed when the selection moves on.
+    process: Option<(Arc<Mutex<Option<Child>>>, Instant)>,
+}
+
+impl Preview {
+    /// Runs `command` in the background to preview the item `id`, unless it already is,
+    /// killing the command previewing the item before.
+    pub fn show(&mut self, id: &str, command: String) {
+        if self.id.as_deref() == Some(id) {
+            return;
+        }
+        self.kill();
+        self.id = Some(id.to_string());
+        self.lines.clear();
+        self.scroll = 0;
+        let (sender, receiver) = mpsc::channel();
+        self.output = Some(receiver);
+        // In its own process group, so killing it takes what it started along.
+        let child = Command::new("sh")
+            .arg("-c")
+            .arg(&command)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .process_group(0)
+            .spawn();
+        let mut child = match child {
+            Ok(child) => child,
+            Err(error) => {
+                let _ = sender.send(vec![Line::from(error.to_string())]);
+                return;
+            }
+        };
+        let stdout = child.stdout.take();
+        let process = Arc::new(Mutex::new(Some(child)));
+        self.process = Some((process.clone(), Instant::now()));
+        thread::spawn(move || {
+            let lines = stdout
+                .map(|stdout| {
+                    BufReader::new(stdout)
+                        .lines()
+                        .map_while(Result::ok)
+                        .take(MAX_LINES)
+                        .map(|line| styled_line(&line))
+                        .collect()
+                })
+                .unwrap_or_default();
+            // Reaped only now, so the process group can't be reused while it's killed.
+            if let Ok(mut process) = process.lock() {
+                if let Some(mut child) = process.take() {
+                    kill_group(&child);
+                    let _ = child.wait();
+                }
+            }
+            let _ = sender.send(lines);
+        });
+    }
+
+    pub fn hide(&mut self) {
+        *self = Self::default();
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.id.is_some()
+    }
+
+    /// Takes the output of the command once it finished, returning whether it did.
+    /// Kills the command once it ran for too long.
+    pub fn apply(&mut self) -> bool {
+        if self
+            .process
+            .as_ref()
+            .is_some_and(|(_, started)| started.elapsed() > TIMEOUT)
+        {
+            self.kill();
+        }
+        let Some(lines) = self
+            .output
+            .as_ref()
+            .and_then(|output| output.try_recv().ok())
+        else {
+            return false;
+        };
+        self.lines = lines;
+        self.output = None;
+        self.process = None;
+        true
+    }
+
+    /// Kills the running command, if any, whose output then ends.
+    fn kill(&mut self) {
+        if let Some((process, _)) = self.process.take() {
+            if let Some(child) = process.lock().ok().as_ref().and_then(|p| p.as_ref()) {
+                kill_group(child);
+            }
+        }
+    }
+
+    /// Scrolls by `lines`, up when negative, without going past the last line.
+    pub fn scroll(&mut self, lines: i16) {
+        let last = self.lines.len().saturating_sub(1) as u16;
+        self.scroll = self.scroll.saturating_add_signed(lines).min(last);
+    }
+
+    pub fn render(&self, block: Block, area: Rect, buf: &mut Buffer) {
+        Paragraph::new(self.lines.clone())
+            .block(block)
+            .scroll((self.scroll, 0))
+            .render(area, buf);
+    }
+}
+
+impl Drop for Preview {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
+
+fn kill_group(child: &Child) {
+    unsafe {
+        libc::kill(-(child.id() as libc::pid_t), libc::SIGKILL);
+    }
+}
+
+/// A line of output in its ANSI colors.
+fn styled_line(text: &str) -> Line<'static> {
+    let (plain, styles) = ansi::parse(&text.replace('\t', "    "));
+    let mut spans = Vec::new();
+    let mut start = 0;
+    let mut style = Default::default();
+    for (offset, next) in styles {
+        if offset > start {
+            spans.push(Span::styled(plain[start..offset].to_string(), style));
+        }
+        start = offset;
+        style = next;
+    }
+    spans.push(Span::styled(plain[start..].to_string(), style));
+    Line::from(spans)
+}
@@ -0,0 +1,72 @@
+use std::{fs, path::PathBuf};
+
+use crate::{desktop_entry::DesktopEntry, paths, running, sources::percent_decode};
+
+/// A document the GTK/Qt recent files list says an app opened.
+#[derive(Debug, Clone)]
+pub struct RecentFile {
+    pub path: PathBuf,
+    /// ISO 8601, so comparing the strings orders files by time.
+    pub modified: String,
+}
+
+/// The `limit` most recently used files opened by `entry`, newest first, read from
+/// `$XDG_DATA_HOME/recently-used.xbel`.
+pub fn for_entry(entry: &DesktopEntry, limit: usize) -> Vec<RecentFile> {
+    if limit == 0 {
+        return Vec::new();
+    }
+    let Some(content) = xbel_path().and_then(|path| fs::read_to_string(path).ok()) else {
+        return Vec::new();
+    };
+    let program = running::program(&entry.exec);
+    let mut files = content
+        .split("<bookmark ")
+        .skip(1)
+        .filter(|bookmark| {
+            bookmark.split("<bookmark:application ").skip(1).any(|app| {
+                let name = attribute(app, "name").unwrap_or_default();
+                let exec = attribute(app, "exec").unwrap_or_default();
+                name.eq_ignore_ascii_case(&entry.name)
+                    || program.is_some() && running::program(exec.trim_matches('\'')) == program
+            })
+        })
+        .filter_map(|bookmark| {
+            let href = attribute(bookmark, "href")?;
+            let path = PathBuf::from(percent_decode(href.strip_prefix("file://")?));
+            Some(RecentFile {
+                modified: attribute(bookmark, "modified").unwrap_or_default(),
+                path,
+            })
+        })
+        .filter(|file| file.path.exists())
+        .collect::<Vec<RecentFile>>();
+    files.sort_by(|a, b| b.modified.cmp(&a.modified));
+    files.truncate(limit);
+    files
+}
+
+fn xbel_path() -> Option<PathBuf> {
+    paths::data_home().map(|dir| dir.join("recently-used.xbel"))
+}
+
+/// The unescaped value of `name="..."` in the tag starting `tag`.
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let tag = &tag[..tag.find('>').unwrap_or(tag.len())];
+    let start = tag
+        .find(&format!(" {}=\"", name))
+        .map(|i| i + name.len() + 3)
+        .or_else(|| {
+            tag.starts_with(&format!("{}=\"", name))
+                .then_some(name.len() + 2)
+        })?;
+    let end = start + tag[start..].find('"')?;
+    Some(
+        tag[start..end]
+            .replace("&apos;", "'")
+            .replace("&quot;", "\"")
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&amp;", "&"),
+    )
+}
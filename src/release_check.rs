@@ -0,0 +1,67 @@
+use std::{
+    io,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crate::{log, network};
+
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/pemattern/launcher/releases/latest";
+
+/// How often a daemon looks for a new release.
+const CHECK_INTERVAL: Duration = Duration::from_secs(24 * 60 * 60);
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+}
+
+/// Looks for a release newer than this build now and then once a day, sending each
+/// newer version once. Makes requests as `update`, which `[network]` must allow.
+pub fn spawn() -> Receiver<String> {
+    let (sender, receiver) = mpsc::channel();
+    if !network::allows("update") {
+        log::info("check_updates needs `update` in the allow list of [network]");
+        return receiver;
+    }
+    thread::spawn(move || {
+        let mut notified = None;
+        loop {
+            match latest_release() {
+                Ok(version) if is_newer(&version) && notified.as_ref() != Some(&version) => {
+                    if sender.send(version.clone()).is_err() {
+                        return;
+                    }
+                    notified = Some(version);
+                }
+                Ok(_) => {}
+                Err(error) => log::info(&format!("checking for a release failed: {}", error)),
+            }
+            thread::sleep(CHECK_INTERVAL);
+        }
+    });
+    receiver
+}
+
+fn latest_release() -> io::Result<String> {
+    let response = network::get("update", LATEST_RELEASE_URL)?;
+    let release = serde_json::from_str::<Release>(&response).map_err(io::Error::other)?;
+    Ok(release.tag_name.trim_start_matches('v').to_string())
+}
+
+/// Whether `version` like `1.2.0` is newer than this build's, false if it isn't one.
+fn is_newer(version: &str) -> bool {
+    let parse = |version: &str| {
+        version
+            .split('.')
+            .map(|part| part.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()
+    };
+    match (parse(version), parse(env!("CARGO_PKG_VERSION"))) {
+        (Some(latest), Some(current)) => latest > current,
+        _ => false,
+    }
+}
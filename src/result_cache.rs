@@ -0,0 +1,50 @@
+use std::collections::VecDeque;
+
+use crate::row::Match;
+
+/// Matches kept across all cached queries before the oldest queries are dropped.
+const MAX_MATCHES: usize = 100_000;
+
+/// Ranked matches of recent queries in the current mode, so backspacing is instant and
+/// extending a query only rescores what matched before, for matchers where every item
+/// matching a query also matches each of its prefixes.
+#[derive(Debug, Default)]
+pub struct ResultCache {
+    entries: VecDeque<(String, Vec<Match>)>,
+    size: usize,
+}
+
+impl ResultCache {
+    pub fn get(&self, query: &str) -> Option<&[Match]> {
+        self.entries
+            .iter()
+            .find(|(cached, _)| cached == query)
+            .map(|(_, matches)| matches.as_slice())
+    }
+
+    /// The matches of the longest cached prefix of `query`, which include all of its own
+    /// unless the matcher doesn't narrow.
+    pub fn superset(&self, query: &str) -> Option<&[Match]> {
+        self.entries
+            .iter()
+            .filter(|(cached, _)| query.starts_with(cached.as_str()))
+            .max_by_key(|(cached, _)| cached.len())
+            .map(|(_, matches)| matches.as_slice())
+    }
+
+    pub fn insert(&mut self, query: &str, matches: Vec<Match>) {
+        self.size += matches.len();
+        self.entries.push_back((query.to_string(), matches));
+        while self.size > MAX_MATCHES {
+            let Some((_, dropped)) = self.entries.pop_front() else {
+                break;
+            };
+            self.size -= dropped.len();
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.size = 0;
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{desktop_entry::DesktopAction, matcher::Score, sources::Item};
+
+/// An entry that passed the filter, with its score and matched positions.
+#[derive(Debug, Clone)]
+pub struct Match {
+    pub index: usize,
+    pub score: Score,
+}
+
+/// A line in the result list. Headers and separators group entries and are never
+/// selected.
+#[derive(Debug)]
+pub enum Row {
+    Header(String),
+    Separator,
+    Entry(Match),
+    /// An action of the entry at `entry`, listed below it once expanded with Right.
+    Action {
+        entry: usize,
+        action: DesktopAction,
+    },
+    /// A source's answer to the query, listed above the applications, e.g. the result
+    /// of `2*(3+4)`.
+    Answer {
+        source: usize,
+        item: Item,
+    },
+}
+
+impl Row {
+    pub fn entry(index: usize) -> Self {
+        Self::Entry(Match {
+            index,
+            score: Score {
+                score: 0,
+                positions: Vec::new(),
+            },
+        })
+    }
+
+    pub fn as_match(&self) -> Option<&Match> {
+        match self {
+            Self::Entry(m) => Some(m),
+            _ => None,
+        }
+    }
+
+    pub fn is_selectable(&self) -> bool {
+        matches!(
+            self,
+            Self::Entry(_) | Self::Action { .. } | Self::Answer { .. }
+        )
+    }
+}
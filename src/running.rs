@@ -0,0 +1,162 @@
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::Path,
+    process,
+    sync::mpsc::{self, Receiver},
+    thread,
+    time::Duration,
+};
+
+use crate::{compositor, desktop_entry::DesktopEntry, launch, sources::command_output};
+
+/// Running processes by the program they run, see `program_of`, read from `/proc`.
+#[derive(Debug, Clone, Default)]
+pub struct Processes {
+    pids: HashMap<String, u32>,
+}
+
+impl Processes {
+    fn scan() -> Self {
+        let mut pids = HashMap::new();
+        let Ok(dir) = fs::read_dir("/proc") else {
+            return Self { pids };
+        };
+        for proc in dir.flatten() {
+            let Some(pid) = proc.file_name().to_str().and_then(|pid| pid.parse().ok()) else {
+                continue;
+            };
+            if pid == process::id() {
+                continue;
+            }
+            let cmdline = fs::read(proc.path().join("cmdline")).unwrap_or_default();
+            let argv = cmdline
+                .split(|&b| b == 0)
+                .filter(|arg| !arg.is_empty())
+                .map(|arg| String::from_utf8_lossy(arg).to_string())
+                .collect::<Vec<String>>();
+            let name = match program_of(&argv) {
+                Some(name) => name,
+                None => match fs::read_to_string(proc.path().join("comm")) {
+                    Ok(comm) => comm.trim().to_string(),
+                    Err(_) => continue,
+                },
+            };
+            pids.entry(name).or_insert(pid);
+        }
+        Self { pids }
+    }
+
+    /// A process running the program of `entry`'s command line.
+    pub fn find(&self, entry: &DesktopEntry) -> Option<u32> {
+        self.pids.get(&program_of(&entry.argv(&[])?)?).copied()
+    }
+}
+
+/// Rescans the running processes every `interval` on a background thread, so the
+/// list never waits for `/proc`.
+pub fn watch(interval: Duration) -> Receiver<Processes> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        while sender.send(Processes::scan()).is_ok() {
+            thread::sleep(interval);
+        }
+    });
+    receiver
+}
+
+/// The program an Exec line runs, see `program_of`.
+pub fn program(exec: &str) -> Option<String> {
+    program_of(&launch::split(exec)?)
+}
+
+/// The program `argv` comes down to: the file name of its executable past `env` with
+/// its options and assignments, `sh -c` and `exec`, or the app ID of `flatpak run`.
+pub fn program_of(argv: &[String]) -> Option<String> {
+    let mut args = argv.iter().map(String::as_str);
+    let mut program = file_name(args.next()?)?;
+    while program == "env" || program == "exec" {
+        let arg = args.next()?;
+        match arg {
+            "-u" | "--unset" | "-C" | "--chdir" => {
+                args.next();
+            }
+            _ if arg.starts_with('-') || arg.contains('=') => {}
+            _ => program = file_name(arg)?,
+        }
+    }
+    let mut args = args.skip_while(|arg| arg.starts_with('-') && *arg != "-c");
+    match program.as_str() {
+        "flatpak" if args.next() == Some("run") => {
+            args.find(|arg| !arg.starts_with('-')).map(String::from)
+        }
+        "sh" | "bash" | "dash" | "zsh" if args.next() == Some("-c") => {
+            program_of(&launch::split(args.next()?)?)
+        }
+        _ => Some(program),
+    }
+}
+
+fn file_name(path: &str) -> Option<String> {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// Raises the window of `pid` with `template`, where `{pid}` expands to the process
+/// ID, or through the compositor integration when no template is configured, or
+/// with xdotool when neither is.
+pub fn focus(template: Option<&str>, pid: u32) -> io::Result<()> {
+    if template.is_none() {
+        if let Some(compositor) = compositor::detect() {
+            return compositor.focus_pid(pid);
+        }
+    }
+    let template = template.unwrap_or("xdotool search --pid {pid} windowactivate");
+    let argv = launch::expand(template, &[("pid", pid.to_string())]).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "unbalanced quotes in the focus command",
+        )
+    })?;
+    let Some((program, args)) = argv.split_first() else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "empty focus command",
+        ));
+    };
+    let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+    command_output(program, &args).map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn wrappers_resolve_to_the_program() {
+        let cases: [&[&str]; 6] = [
+            &["/usr/lib/firefox/firefox", "--new-window"],
+            &["env", "MOZ_ENABLE_WAYLAND=1", "firefox"],
+            &["/usr/bin/env", "-u", "DISPLAY", "firefox"],
+            &["/bin/sh", "-c", "'exec' 'firefox' '%u'"],
+            &["bash", "-c", "env GDK_BACKEND=x11 firefox"],
+            &["sh", "-c", "exec /usr/bin/firefox"],
+        ];
+        for case in cases {
+            assert_eq!(
+                program_of(&argv(case)).as_deref(),
+                Some("firefox"),
+                "{:?}",
+                case
+            );
+        }
+        let flatpak = argv(&["flatpak", "run", "--branch=stable", "org.mozilla.firefox"]);
+        assert_eq!(program_of(&flatpak).as_deref(), Some("org.mozilla.firefox"));
+    }
+}
@@ -0,0 +1,36 @@
+use crossterm::terminal;
+
+use crate::config::Config;
+
+/// Terminals with fewer rows or columns are treated as small drop-downs.
+const SMALL_HEIGHT: u16 = 15;
+const SMALL_WIDTH: u16 = 60;
+/// Terminals with at least this many rows are treated as full-screen.
+const LARGE_HEIGHT: u16 = 40;
+/// Results shown at most in full-screen terminals, so the list stays near the input.
+const LARGE_MAX_RESULTS: usize = 20;
+
+/// Layout options picked for the terminal size at startup, unless the config sets them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Screen {
+    /// Draws the input as a single line and the list with only a top rule.
+    pub compact: bool,
+    pub scrollbar: bool,
+    pub max_results: Option<usize>,
+}
+
+impl Screen {
+    pub fn detect(config: &Config) -> Self {
+        let (width, height) = terminal::size().unwrap_or((80, 24));
+        let small = height < SMALL_HEIGHT || width < SMALL_WIDTH;
+        let large = height >= LARGE_HEIGHT;
+        Self {
+            compact: config.compact.unwrap_or(small),
+            scrollbar: config.show_scrollbar.unwrap_or(!small),
+            max_results: config
+                .max_results
+                .or(large.then_some(LARGE_MAX_RESULTS))
+                .filter(|&max| max > 0),
+        }
+    }
+}
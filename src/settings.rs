@@ -0,0 +1,287 @@
+use std::{fs, io, path::Path};
+
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Layout, Rect},
+    text::Line,
+    widgets::{Clear, List, ListState, StatefulWidget, Widget},
+};
+
+use crate::{config::Config, locale::tr, paths, theme};
+
+/// A config option offered in the settings overlay.
+#[derive(Debug)]
+struct Setting {
+    /// The option's key, after its table and a dot if it isn't top-level.
+    key: &'static str,
+    label: &'static str,
+    /// The values cycled through, as written to the config file.
+    options: &'static [&'static str],
+}
+
+const BOOL: &[&str] = &["true", "false"];
+
+const SETTINGS: [Setting; 8] = [
+    Setting {
+        key: "show_icons",
+        label: "Icons",
+        options: BOOL,
+    },
+    Setting {
+        key: "theme.name",
+        label: "Theme",
+        options: &["\"default\"", "\"light\"", "\"high-contrast\"", "\"mono\""],
+    },
+    Setting {
+        key: "sort",
+        label: "Sort by",
+        options: &["\"relevance\"", "\"name\""],
+    },
+    Setting {
+        key: "wrap_selection",
+        label: "Wrap selection",
+        options: BOOL,
+    },
+    Setting {
+        key: "animations",
+        label: "Animations",
+        options: BOOL,
+    },
+    Setting {
+        key: "show_description",
+        label: "Descriptions",
+        options: BOOL,
+    },
+    Setting {
+        key: "show_recent",
+        label: "Recent apps",
+        options: BOOL,
+    },
+    Setting {
+        key: "show_recently_installed",
+        label: "Recently installed",
+        options: BOOL,
+    },
+];
+
+/// The value `config` has for `key`, formatted like the `options` of its setting.
+fn current(config: &Config, key: &str) -> String {
+    match key {
+        "show_icons" => config.show_icons.to_string(),
+        "theme.name" => {
+            let default = if config.high_contrast {
+                "high-contrast"
+            } else {
+                "default"
+            };
+            format!("\"{}\"", config.theme.name.as_deref().unwrap_or(default))
+        }
+        "sort" => format!("\"{}\"", config.sort.name()),
+        "wrap_selection" => config.wrap_selection.to_string(),
+        "animations" => config.animations.to_string(),
+        "show_description" => config.show_description.to_string(),
+        "show_recent" => config.show_recent.to_string(),
+        "show_recently_installed" => config.show_recently_installed.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// What a key press did to the settings overlay.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SettingsAnswer {
+    /// `key` was set to the TOML `value`.
+    Changed(&'static str, String),
+    Closed,
+    Pending,
+}
+
+/// A modal where Left and Right cycle common options.
+#[derive(Debug)]
+pub struct SettingsMenu {
+    /// The selected option of each setting.
+    values: Vec<usize>,
+    state: ListState,
+}
+
+impl SettingsMenu {
+    pub fn new(config: &Config) -> Self {
+        let values = SETTINGS
+            .iter()
+            .map(|setting| {
+                let value = current(config, setting.key);
+                setting
+                    .options
+                    .iter()
+                    .position(|option| *option == value)
+                    .unwrap_or_default()
+            })
+            .collect();
+        Self {
+            values,
+            state: ListState::default().with_selected(Some(0)),
+        }
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode) -> SettingsAnswer {
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => self.state.select_previous(),
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = SETTINGS.len() - 1;
+                self.state
+                    .select(self.state.selected().map(|i| (i + 1).min(last)));
+            }
+            KeyCode::Left | KeyCode::Right | KeyCode::Enter | KeyCode::Char(' ') => {
+                let Some(i) = self.state.selected() else {
+                    return SettingsAnswer::Pending;
+                };
+                let options = SETTINGS[i].options;
+                self.values[i] = if key == KeyCode::Left {
+                    (self.values[i] + options.len() - 1) % options.len()
+                } else {
+                    (self.values[i] + 1) % options.len()
+                };
+                return SettingsAnswer::Changed(
+                    SETTINGS[i].key,
+                    options[self.values[i]].to_string(),
+                );
+            }
+            KeyCode::Esc => return SettingsAnswer::Closed,
+            _ => {}
+        }
+        SettingsAnswer::Pending
+    }
+}
+
+impl Widget for &SettingsMenu {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let height = SETTINGS.len() as u16 + 2;
+        let [_, area, _] = Layout::vertical([
+            Constraint::Fill(1),
+            Constraint::Length(height),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let [_, area, _] = Layout::horizontal([
+            Constraint::Fill(1),
+            Constraint::Percentage(60),
+            Constraint::Fill(1),
+        ])
+        .areas(area);
+        let theme = theme::get();
+        let [left, right] = &theme.chevrons;
+        let width = area.width.saturating_sub(4) as usize;
+        let list = List::new(SETTINGS.iter().zip(&self.values).map(|(setting, &value)| {
+            let label = tr(setting.label);
+            let value = tr(match setting.options[value] {
+                "true" => "on",
+                "false" => "off",
+                option => option.trim_matches('"'),
+            });
+            let chevrons = left.chars().count() + right.chars().count();
+            let padding =
+                width.saturating_sub(label.chars().count() + value.chars().count() + chevrons + 2);
+            Line::from(format!(
+                " {}{}{} {} {}",
+                label,
+                " ".repeat(padding),
+                left,
+                value,
+                right
+            ))
+        }))
+        .block(
+            theme
+                .block()
+                .title(tr("Settings"))
+                .title_bottom(Line::styled(
+                    format!(
+                        "{}/{} {}{}{}",
+                        theme.arrows[0],
+                        theme.arrows[1],
+                        tr("change"),
+                        theme.hint_separator,
+                        tr("Esc close")
+                    ),
+                    theme.dim,
+                )),
+        )
+        .style(theme.text)
+        .highlight_style(theme.selected)
+        .highlight_symbol(&theme.selector);
+        let mut state = self.state.clone();
+        Clear.render(area, buf);
+        StatefulWidget::render(list, area, buf, &mut state);
+    }
+}
+
+/// Sets `key`, after its table and a dot unless top-level, to the TOML `value` in the
+/// config file, replacing its line or the commented-out example of it so the rest of the
+/// file stays as written. Without a config file yet, starts one from the built-in example.
+pub fn write(path: &Path, key: &str, value: &str) -> io::Result<()> {
+    let content = paths::read_config(Some(path))?;
+    let mut lines = content.lines().map(String::from).collect::<Vec<String>>();
+    let (table, key) = match key.split_once('.') {
+        Some((table, key)) => (Some(table), key),
+        None => (None, key),
+    };
+    let is_table = |line: &String| line.trim_start().starts_with('[');
+    let start = match table {
+        None => 0,
+        Some(table) => {
+            let header = format!("[{}]", table);
+            let found = lines.iter().position(|line| line.trim() == header);
+            let commented = || {
+                lines.iter().position(|line| {
+                    line.strip_prefix('#')
+                        .is_some_and(|line| line.trim() == header)
+                })
+            };
+            match found.or_else(commented) {
+                Some(i) => {
+                    lines[i] = header;
+                    i + 1
+                }
+                None => {
+                    lines.extend([String::new(), header]);
+                    lines.len()
+                }
+            }
+        }
+    };
+    let end = lines[start..]
+        .iter()
+        .position(is_table)
+        .map_or(lines.len(), |i| start + i);
+    // Examples of a commented-out table that follows aren't this table's.
+    let examples_end = lines[start..end]
+        .iter()
+        .position(|line| {
+            line.strip_prefix('#')
+                .is_some_and(|line| is_table(&line.to_string()))
+        })
+        .map_or(end, |i| start + i);
+    let sets_key = |line: &String| {
+        line.strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    };
+    let existing = lines[start..end]
+        .iter()
+        .position(sets_key)
+        .or_else(|| {
+            lines[start..examples_end].iter().position(|line| {
+                line.strip_prefix('#')
+                    .is_some_and(|line| sets_key(&line.trim_start().to_string()))
+            })
+        })
+        .map(|i| start + i);
+    let line = format!("{} = {}", key, value);
+    match existing {
+        Some(i) => lines[i] = line,
+        None => lines.insert(examples_end, line),
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, lines.join("\n") + "\n")
+}
@@ -0,0 +1,39 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use signal_hook::consts::{SIGUSR1, SIGUSR2};
+
+/// Flags raised by signals from outside, e.g. a window manager keybinding running
+/// `pkill -USR1 launcher`.
+#[derive(Debug)]
+pub struct Signals {
+    /// SIGUSR1: reload the config and rescan the applications.
+    reload: Arc<AtomicBool>,
+    /// SIGUSR2: toggle the launcher's visibility, which closes it unless it's a daemon.
+    toggle: Arc<AtomicBool>,
+}
+
+impl Signals {
+    pub fn register() -> io::Result<Self> {
+        let reload = Arc::new(AtomicBool::new(false));
+        let toggle = Arc::new(AtomicBool::new(false));
+        signal_hook::flag::register(SIGUSR1, reload.clone())?;
+        signal_hook::flag::register(SIGUSR2, toggle.clone())?;
+        Ok(Self { reload, toggle })
+    }
+
+    /// Whether SIGUSR1 arrived since the last call.
+    pub fn take_reload(&self) -> bool {
+        self.reload.swap(false, Ordering::Relaxed)
+    }
+
+    /// Whether SIGUSR2 arrived since the last call.
+    pub fn take_toggle(&self) -> bool {
+        self.toggle.swap(false, Ordering::Relaxed)
+    }
+}
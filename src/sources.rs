@@ -0,0 +1,331 @@
+use std::{
+    fmt::Debug,
+    io,
+    path::Path,
+    process::Command,
+    sync::{mpsc::Receiver, Arc},
+};
+
+use serde::Deserialize;
+
+use ratatui::{
+    style::{Color, Style},
+    text::{Line, Span},
+};
+
+use crate::{badge::Badge, compositor, config::Config, matcher::Matcher, theme};
+
+pub mod calculator;
+pub mod dmenu;
+pub mod drives;
+pub mod emoji;
+pub mod files;
+pub mod location;
+pub mod media;
+pub mod quick;
+pub mod run;
+pub mod ssh;
+pub mod trash;
+pub mod vpn;
+pub mod web;
+pub mod windows;
+pub mod workspaces;
+
+/// A row produced by a source, matched and rendered the same way for every source.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct Item {
+    /// Identifies the item within its source.
+    pub id: String,
+    pub name: String,
+    pub icon: Arc<str>,
+    pub comment: Option<String>,
+    /// Dim text shown at the end of the row, e.g. a file size.
+    pub detail: Option<String>,
+    pub badges: Vec<Badge>,
+    /// Marks the row with a dot, like a dock does for open apps.
+    pub running: bool,
+    /// The file behind the item, whose directory Alt+F opens.
+    pub path: Option<Arc<Path>>,
+    /// Shown dim and skipped by the selection.
+    pub disabled: bool,
+    /// Shown highlighted, e.g. a device that needs attention.
+    pub urgent: bool,
+    /// The style of the name from each byte offset on, e.g. from its ANSI colors.
+    pub styles: Vec<(usize, Style)>,
+}
+
+impl Item {
+    pub fn new(id: impl Into<String>, name: impl Into<String>, icon: impl Into<Arc<str>>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            icon: icon.into(),
+            comment: None,
+            detail: None,
+            badges: Vec::new(),
+            running: false,
+            path: None,
+            disabled: false,
+            urgent: false,
+            styles: Vec::new(),
+        }
+    }
+
+    /// Renders the name with the characters at the byte offsets in `positions` highlighted,
+    /// after the icon if `icons` is set.
+    pub(crate) fn get_highlighted_name(&self, positions: &[usize], icons: bool) -> Line<'static> {
+        let theme = theme::get();
+        let dot = if self.running {
+            Span::styled(theme.running_dot.clone(), theme.running)
+        } else {
+            Span::raw(" ".repeat(theme.running_dot.chars().count()))
+        };
+        let mut spans = vec![dot];
+        if icons {
+            spans.push(Span::from(format!("{} ", self.icon)));
+        }
+        let mut run = String::new();
+        let mut run_matched = false;
+        let mut run_style = Style::default();
+        for (i, c) in self.name.char_indices() {
+            let matched = positions.contains(&i);
+            let style = self.style_at(i);
+            if (matched != run_matched || style != run_style) && !run.is_empty() {
+                spans.push(Self::name_span(
+                    std::mem::take(&mut run),
+                    run_matched,
+                    run_style,
+                ));
+            }
+            run_matched = matched;
+            run_style = style;
+            run.push(c);
+        }
+        if !run.is_empty() {
+            spans.push(Self::name_span(run, run_matched, run_style));
+        }
+        let line = Line::from(spans);
+        if self.disabled {
+            line.patch_style(theme.dim)
+        } else if self.urgent {
+            line.patch_style(theme.urgent)
+        } else {
+            line
+        }
+    }
+
+    fn name_span(text: String, matched: bool, style: Style) -> Span<'static> {
+        if matched {
+            Span::styled(text, style.patch(theme::get().matched))
+        } else {
+            Span::styled(text, style)
+        }
+    }
+
+    fn style_at(&self, offset: usize) -> Style {
+        let index = self.styles.partition_point(|&(start, _)| start <= offset);
+        index
+            .checked_sub(1)
+            .map_or(Style::default(), |index| self.styles[index].1)
+    }
+}
+
+/// Per-source overrides, keyed by the source's keyword in the config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct SourceConfig {
+    /// Command run on Enter instead of the source's own action, e.g. `xdg-open {path}`.
+    /// `{id}`, `{name}` and the source's placeholders expand to the selected item's values.
+    pub activate: Option<String>,
+    /// Runs `activate` in the launcher's terminal instead of detached from it.
+    pub terminal: bool,
+    /// Drawn at the start of the source's rows, in `accent` if set or else a bar.
+    pub glyph: Option<String>,
+    /// The color of the source's `glyph`, e.g. `"cyan"` or `"#ff8800"`.
+    pub accent: Option<Color>,
+}
+
+/// Something a source can do, bound to Enter (`key: None`) or Alt+`key`.
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct Action {
+    pub name: &'static str,
+    pub key: Option<char>,
+    /// Runs without a selected item, on the source as a whole.
+    pub bulk: bool,
+    /// Asks for confirmation before running.
+    pub confirm: bool,
+}
+
+impl Action {
+    /// An action bound to Enter that runs on the selected item without confirmation.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            key: None,
+            bulk: false,
+            confirm: false,
+        }
+    }
+}
+
+/// What the launcher does after a source ran an action.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Outcome {
+    Refresh,
+    Exit,
+    /// Runs the command in the launcher's terminal like a terminal app, e.g. `ssh`.
+    Terminal(Vec<String>),
+}
+
+/// A mode listing something other than applications, entered by typing its keyword
+/// followed by a space.
+pub trait Source: Debug {
+    fn keyword(&self) -> &str;
+    fn title(&self) -> &str;
+    /// Whether `query`, typed without any keyword, is meant for this source, e.g. `2+2`.
+    fn detects(&self, _query: &str) -> bool {
+        false
+    }
+    fn items(&mut self) -> Vec<Item>;
+    /// Delivers the items in batches from a background thread instead, so a slow source
+    /// shows what it has so far. The list is complete once the sender is dropped.
+    fn stream(&mut self) -> Option<Receiver<Vec<Item>>> {
+        None
+    }
+    /// Items streamed for `query` itself instead of filtering `items`, restarted whenever
+    /// the query changes, e.g. the output of a command run with it.
+    fn stream_query(&mut self, _query: &str) -> Option<Receiver<Vec<Item>>> {
+        None
+    }
+    /// Items built from `query` itself instead of filtering `items`, e.g. `vol +10`.
+    fn evaluate(&mut self, _query: &str) -> Option<Vec<Item>> {
+        None
+    }
+    /// An item answering `query`, typed without any keyword, listed above the matching
+    /// applications. Enter runs the source's action on it.
+    fn answer(&mut self, _query: &str) -> Option<Item> {
+        None
+    }
+    /// Matches the items instead of the `[matcher]` config, e.g. to rank them by a
+    /// scorer of the source's own.
+    fn matcher(&self) -> Option<&dyn Matcher> {
+        None
+    }
+    /// Whether `item` is selected when it arrives, unless a key was pressed before.
+    fn preselects(&self, _item: &Item) -> bool {
+        false
+    }
+    /// The query Tab replaces the current one with, e.g. a directory's path to descend
+    /// into.
+    fn complete(&self, _item: &Item) -> Option<String> {
+        None
+    }
+    /// The command whose output previews `item` beside the list.
+    fn preview(&self, _item: &Item) -> Option<String> {
+        None
+    }
+    fn actions(&self) -> Vec<Action>;
+    /// Values for the placeholders of an `activate` template besides `{id}` and `{name}`.
+    fn placeholders(&self, _item: &Item) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome>;
+}
+
+/// The built-in sources and then `extra`, unless the launcher is restricted or a dmenu.
+pub fn all(config: &Config, extra: Vec<Box<dyn Source>>) -> Vec<Box<dyn Source>> {
+    if let Some(options) = &config.dmenu {
+        return vec![Box::new(dmenu::Dmenu::new(options.clone()))];
+    }
+    if config.restricted {
+        return Vec::new();
+    }
+    let mut sources: Vec<Box<dyn Source>> = vec![
+        Box::new(trash::Trash::new()),
+        Box::new(drives::Drives::default()),
+        Box::new(vpn::Vpn::new(&config.elevate_command)),
+        Box::new(media::Media),
+        Box::new(calculator::Calculator),
+        Box::new(emoji::Emoji::new(config.matcher.clone())),
+        Box::new(files::Files::new(config.matcher.clone())),
+        Box::new(location::Location),
+        Box::new(run::Run),
+        Box::new(ssh::Ssh),
+        Box::new(web::Web::default()),
+    ];
+    if let Some(compositor) = compositor::detect() {
+        sources.push(Box::new(windows::Windows::new(compositor.clone())));
+        sources.push(Box::new(workspaces::Workspaces::new(compositor)));
+    }
+    for command in &config.quick_commands {
+        sources.push(Box::new(quick::Quick::new(command.clone())));
+    }
+    sources.extend(extra);
+    sources
+}
+
+/// Formats a byte count with a binary unit, e.g. `1.5 MiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+/// Runs `program` and returns its stdout, or its stderr as the error when it fails.
+pub fn command_output(program: &str, args: &[&str]) -> io::Result<String> {
+    let output = Command::new(program).args(args).output()?;
+    if output.status.success() {
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(io::Error::other(stderr.trim().to_string()))
+    }
+}
+
+/// Escapes everything but unreserved characters as `%XX`, e.g. for a URL's query.
+pub fn percent_encode(text: &str) -> String {
+    text.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            byte => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Decodes `%XX` escapes, as found in file URIs.
+pub fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes
+            .get(i + 1..i + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], hex) {
+            (b'%', Some(byte)) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            (byte, _) => {
+                decoded.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
@@ -0,0 +1,156 @@
+use std::{io, iter::Peekable, str::Chars};
+
+use crate::{
+    clipboard,
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// Arithmetic like `2 * (3 + 4)`. Typed without the keyword, the result is listed above
+/// the applications instead.
+#[derive(Debug, Default)]
+pub struct Calculator;
+
+impl Source for Calculator {
+    fn keyword(&self) -> &str {
+        "calc"
+    }
+
+    fn title(&self) -> &str {
+        "Calculator"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.evaluate("").unwrap_or_default()
+    }
+
+    fn evaluate(&mut self, query: &str) -> Option<Vec<Item>> {
+        if query.trim().is_empty() {
+            let mut item = Item::new("", "Calculator", "󰃬");
+            item.comment = Some("Type an expression like 2 * (3 + 4)".to_string());
+            return Some(vec![item]);
+        }
+        let Some(result) = evaluate(query) else {
+            return Some(Vec::new());
+        };
+        let result = format_number(result);
+        let mut item = Item::new(&result, format!("= {}", result), "󰃬");
+        item.comment = Some(query.trim().to_string());
+        Some(vec![item])
+    }
+
+    /// Only for expressions, so that typing a plain number still just searches.
+    fn answer(&mut self, query: &str) -> Option<Item> {
+        if query.trim().parse::<f64>().is_ok() {
+            return None;
+        }
+        evaluate(query)?;
+        self.evaluate(query)?.pop()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "copy",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "copy" && !item.id.is_empty() => {
+                clipboard::copy(&item.id)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
+
+/// Prints whole numbers without a fraction and others with at most ten decimals.
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        let text = format!("{:.10}", value);
+        text.trim_end_matches('0').trim_end_matches('.').to_string()
+    }
+}
+
+/// Evaluates `+ - * / % ^` and parentheses over decimal numbers, or `None` when
+/// `expression` isn't arithmetic or has no finite result.
+fn evaluate(expression: &str) -> Option<f64> {
+    let mut parser = Parser {
+        chars: expression.chars().peekable(),
+    };
+    let value = parser.sum()?;
+    parser.skip_whitespace();
+    (parser.chars.peek().is_none() && value.is_finite()).then_some(value)
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn operator(&mut self, operators: &[char]) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.next_if(|c| operators.contains(c))
+    }
+
+    fn sum(&mut self) -> Option<f64> {
+        let mut value = self.product()?;
+        while let Some(operator) = self.operator(&['+', '-']) {
+            let rhs = self.product()?;
+            value = if operator == '+' {
+                value + rhs
+            } else {
+                value - rhs
+            };
+        }
+        Some(value)
+    }
+
+    fn product(&mut self) -> Option<f64> {
+        let mut value = self.power()?;
+        while let Some(operator) = self.operator(&['*', '/', '%']) {
+            let rhs = self.power()?;
+            value = match operator {
+                '*' => value * rhs,
+                '/' => value / rhs,
+                _ => value % rhs,
+            };
+        }
+        Some(value)
+    }
+
+    /// Right-associative, so `2^3^2` is `2^9`.
+    fn power(&mut self) -> Option<f64> {
+        let base = self.unary()?;
+        if self.operator(&['^']).is_some() {
+            Some(base.powf(self.power()?))
+        } else {
+            Some(base)
+        }
+    }
+
+    fn unary(&mut self) -> Option<f64> {
+        if self.operator(&['-']).is_some() {
+            return Some(-self.unary()?);
+        }
+        if self.operator(&['(']).is_some() {
+            let value = self.sum()?;
+            return self.operator(&[')']).map(|_| value);
+        }
+        self.skip_whitespace();
+        let mut number = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit() || *c == '.') {
+            number.push(c);
+        }
+        number.parse().ok()
+    }
+}
@@ -0,0 +1,477 @@
+use std::{
+    io::{self, BufRead, BufReader},
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::Duration,
+};
+
+use serde::Deserialize;
+
+use crossterm::event::KeyEvent;
+use ratatui::style::Style;
+
+use crate::{
+    ansi, keybindings, launch,
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// The line picked in dmenu mode, printed once the terminal is restored. Bytes, so a
+/// line that isn't UTF-8 is printed back as it was read.
+static SELECTION: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// The index of the `--expect` key the selection was accepted with.
+static EXPECTED: OnceLock<usize> = OnceLock::new();
+
+/// How long the query has to stay the same before the `--dynamic` command runs with it.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+pub fn selection() -> Option<&'static [u8]> {
+    SELECTION.get().map(Vec::as_slice)
+}
+
+/// Records that the selection is accepted with the `--expect` key at `index`.
+pub fn accept_with(index: usize) {
+    let _ = EXPECTED.set(index);
+}
+
+pub fn accepted_with() -> Option<usize> {
+    EXPECTED.get().copied()
+}
+
+/// The command line options that go with `--dmenu`.
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    /// Entries on stdin end with NUL instead of a newline, with `--read0`.
+    pub read0: bool,
+    /// The selection is printed followed by NUL instead of a newline, with `--print0`.
+    pub print0: bool,
+    /// The entry selected once it arrives, with `--selected`: its index counting from 0 if
+    /// the value is a number, or else its text.
+    pub selected: Option<String>,
+    /// The index of the selection is printed instead of its text, with `--print-index`.
+    pub print_index: bool,
+    /// Separates the fields of an entry, with `--delimiter`. Runs of whitespace if unset.
+    pub delimiter: Option<char>,
+    /// The fields shown and searched, with `--with-nth`. The whole entry if empty.
+    pub with_nth: Vec<FieldRange>,
+    /// Each entry is a JSON object, printed back whole on selection, with `--entries-json`.
+    pub entries_json: bool,
+    /// A command run with every query instead of reading stdin, listing its output, with
+    /// `--dynamic`. `{q}` expands to the query, quoted for the shell.
+    pub dynamic: Option<String>,
+    /// Entries are shown in their ANSI colors and printed without them, with `--ansi`.
+    pub ansi: bool,
+    /// A command previewing the selected entry beside the list, with `--preview`. `{}`
+    /// expands to the entry, quoted for the shell.
+    pub preview: Option<String>,
+    /// Keys that accept the selection besides Enter, with `--expect`, e.g. `ctrl-o,ctrl-e`.
+    /// The key is printed on the line before the selection, empty for Enter, and the
+    /// exit code is 10 plus its index.
+    pub expect: Vec<String>,
+}
+
+/// An entry of `--entries-json`. Other fields, like a `value` for the script, are kept in
+/// the object printed back.
+#[derive(Debug, Deserialize)]
+struct Entry {
+    name: String,
+    #[serde(default)]
+    icon: String,
+    comment: Option<String>,
+    /// Left out of the list.
+    #[serde(default)]
+    hidden: bool,
+    #[serde(default)]
+    disabled: bool,
+    #[serde(default)]
+    urgent: bool,
+}
+
+impl Options {
+    /// The options in `args`, or `None` without `--dmenu` or `--dynamic`.
+    pub fn from_args(args: &[String]) -> io::Result<Option<Self>> {
+        let flag = |name: &str| args.iter().any(|arg| arg == name);
+        let value = |name: &str| {
+            let prefix = format!("{}=", name);
+            if let Some(value) = args.iter().find_map(|arg| arg.strip_prefix(&prefix)) {
+                return Some(value.to_string());
+            }
+            let position = args.iter().position(|arg| arg == name)?;
+            args.get(position + 1).cloned()
+        };
+        if !flag("--dmenu") && value("--dynamic").is_none() {
+            return Ok(None);
+        }
+        let delimiter = match value("--delimiter") {
+            Some(delimiter) => {
+                let mut chars = delimiter.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some(c),
+                    _ => return Err(invalid(&format!("--delimiter {}", delimiter))),
+                }
+            }
+            None => None,
+        };
+        let with_nth = match value("--with-nth") {
+            Some(fields) => fields
+                .split(',')
+                .map(|range| {
+                    FieldRange::parse(range)
+                        .ok_or_else(|| invalid(&format!("--with-nth {}", fields)))
+                })
+                .collect::<io::Result<Vec<FieldRange>>>()?,
+            None => Vec::new(),
+        };
+        Ok(Some(Self {
+            read0: flag("--read0"),
+            print0: flag("--print0"),
+            selected: value("--selected"),
+            print_index: flag("--print-index"),
+            delimiter,
+            with_nth,
+            entries_json: flag("--entries-json"),
+            dynamic: value("--dynamic"),
+            ansi: flag("--ansi"),
+            preview: value("--preview"),
+            expect: value("--expect")
+                .map(|keys| keys.split(',').map(String::from).collect())
+                .unwrap_or_default(),
+        }))
+    }
+
+    /// The index of `key` among the `--expect` keys, named like `ctrl-o` or `Ctrl+O`.
+    pub fn expected(&self, key: &KeyEvent) -> Option<usize> {
+        self.expect.iter().position(|name| {
+            let name = match name.as_str() {
+                "-" => name.clone(),
+                name => name.replace('-', "+"),
+            };
+            keybindings::matches(&name, key)
+        })
+    }
+
+    pub fn terminator(&self) -> char {
+        if self.print0 {
+            '\0'
+        } else {
+            '\n'
+        }
+    }
+
+    /// Identifies the entry by its index on stdin, showing the fields picked with
+    /// `--with-nth` and the line breaks of a NUL-delimited entry as `↵`. Entries that
+    /// aren't valid JSON objects or are hidden are left out with `--entries-json`.
+    fn item(&self, index: usize, line: &str) -> Option<Item> {
+        if !self.entries_json {
+            let (name, styles) = self.name(line);
+            let mut item = Item::new(index.to_string(), name, "");
+            item.styles = styles;
+            return Some(item);
+        }
+        let entry = serde_json::from_str::<Entry>(line)
+            .ok()
+            .filter(|entry| !entry.hidden)?;
+        let (name, styles) = self.name(&entry.name);
+        let mut item = Item::new(index.to_string(), name, entry.icon);
+        item.styles = styles;
+        item.comment = entry.comment;
+        item.disabled = entry.disabled;
+        item.urgent = entry.urgent;
+        Some(item)
+    }
+
+    /// The name shown for `text`, with the styles of its ANSI colors with `--ansi`.
+    fn name(&self, text: &str) -> (String, Vec<(usize, Style)>) {
+        let name = self.fields(text).replace('\n', "↵");
+        if self.ansi {
+            ansi::parse(&name)
+        } else {
+            (name, Vec::new())
+        }
+    }
+
+    /// The fields of `line` picked with `--with-nth`, joined by the delimiter.
+    fn fields(&self, line: &str) -> String {
+        if self.with_nth.is_empty() {
+            return line.to_string();
+        }
+        let fields = match self.delimiter {
+            Some(delimiter) => line.split(delimiter).collect::<Vec<&str>>(),
+            None => line.split_whitespace().collect(),
+        };
+        let picked = self
+            .with_nth
+            .iter()
+            .flat_map(|range| range.indices(fields.len()))
+            .map(|index| fields[index])
+            .collect::<Vec<&str>>();
+        let separator = self.delimiter.map_or(" ".to_string(), String::from);
+        picked.join(&separator)
+    }
+}
+
+fn invalid(option: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, format!("invalid {}", option))
+}
+
+/// Fields counting from 1, or from the end when negative, e.g. `2`, `2..`, `..-2` or `1..3`.
+#[derive(Debug, Clone, Copy)]
+pub struct FieldRange {
+    start: Option<isize>,
+    end: Option<isize>,
+}
+
+impl FieldRange {
+    fn parse(range: &str) -> Option<Self> {
+        let bound = |bound: &str| match bound {
+            "" => Some(None),
+            bound => bound.parse::<isize>().ok().filter(|&n| n != 0).map(Some),
+        };
+        let (start, end) = match range.split_once("..") {
+            Some((start, end)) => (bound(start)?, bound(end)?),
+            None => {
+                let field = bound(range)??;
+                (Some(field), Some(field))
+            }
+        };
+        Some(Self { start, end })
+    }
+
+    /// The indices of the fields in the range, among `count` fields.
+    fn indices(&self, count: usize) -> std::ops::Range<usize> {
+        let index = |n: isize| {
+            if n > 0 {
+                n - 1
+            } else {
+                count as isize + n
+            }
+        };
+        let start = self.start.map_or(0, index).clamp(0, count as isize);
+        let end = self.end.map_or(count as isize, |n| index(n) + 1);
+        start as usize..end.clamp(start, count as isize) as usize
+    }
+}
+
+/// The `--dynamic` command running for the latest query.
+#[derive(Debug, Default)]
+struct Process {
+    /// Counts the queries, so a command waiting out the debounce for an older one
+    /// doesn't start.
+    generation: usize,
+    child: Option<Child>,
+}
+
+impl Process {
+    fn kill(&mut self) {
+        if let Some(mut child) = self.child.take() {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+/// Lines read from stdin for `--dmenu`, shown as they arrive like `fzf` does.
+#[derive(Debug)]
+pub struct Dmenu {
+    options: Options,
+    /// Every line read so far, as stdin can only be streamed once.
+    lines: Arc<Mutex<Vec<Vec<u8>>>>,
+    streaming: bool,
+    process: Arc<Mutex<Process>>,
+}
+
+impl Dmenu {
+    pub fn new(options: Options) -> Self {
+        Self {
+            options,
+            lines: Arc::default(),
+            streaming: false,
+            process: Arc::default(),
+        }
+    }
+}
+
+impl Dmenu {
+    /// The line read for `item`.
+    fn line(&self, item: &Item) -> Option<String> {
+        self.raw_line(item)
+            .map(|line| String::from_utf8_lossy(&line).to_string())
+    }
+
+    /// The line read for `item` as it was read, even if it isn't UTF-8.
+    fn raw_line(&self, item: &Item) -> Option<Vec<u8>> {
+        let index = item.id.parse::<usize>().ok()?;
+        self.lines.lock().ok()?.get(index).cloned()
+    }
+}
+
+impl Source for Dmenu {
+    fn keyword(&self) -> &str {
+        "dmenu"
+    }
+
+    fn title(&self) -> &str {
+        "dmenu"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        let lines = self
+            .lines
+            .lock()
+            .map(|lines| lines.clone())
+            .unwrap_or_default();
+        lines
+            .iter()
+            .enumerate()
+            .filter_map(|(index, line)| self.options.item(index, &String::from_utf8_lossy(line)))
+            .collect()
+    }
+
+    fn stream(&mut self) -> Option<Receiver<Vec<Item>>> {
+        if self.streaming || self.options.dynamic.is_some() {
+            return None;
+        }
+        self.streaming = true;
+        let lines = self.lines.clone();
+        let options = self.options.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || read_entries(io::stdin().lock(), &options, &lines, &sender));
+        Some(receiver)
+    }
+
+    fn stream_query(&mut self, query: &str) -> Option<Receiver<Vec<Item>>> {
+        let command = self
+            .options
+            .dynamic
+            .as_ref()?
+            .replace("{q}", &launch::quote(query));
+        let generation = {
+            let mut process = self.process.lock().ok()?;
+            process.kill();
+            process.generation += 1;
+            process.generation
+        };
+        self.lines = Arc::default();
+        let lines = self.lines.clone();
+        let options = self.options.clone();
+        let process = self.process.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            thread::sleep(DEBOUNCE);
+            let stdout = {
+                let Ok(mut process) = process.lock() else {
+                    return;
+                };
+                if process.generation != generation {
+                    return;
+                }
+                let child = Command::new("sh")
+                    .arg("-c")
+                    .arg(&command)
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn();
+                let Ok(mut child) = child else {
+                    return;
+                };
+                let stdout = child.stdout.take();
+                process.child = Some(child);
+                stdout
+            };
+            if let Some(stdout) = stdout {
+                read_entries(BufReader::new(stdout), &options, &lines, &sender);
+            }
+            if let Ok(mut process) = process.lock() {
+                if process.generation == generation {
+                    process.kill();
+                }
+            }
+        });
+        Some(receiver)
+    }
+
+    fn preselects(&self, item: &Item) -> bool {
+        let Some(selected) = &self.options.selected else {
+            return false;
+        };
+        match selected.parse::<usize>() {
+            Ok(index) => item.id == index.to_string(),
+            Err(_) => self.line(item).is_some_and(|line| &line == selected),
+        }
+    }
+
+    fn preview(&self, item: &Item) -> Option<String> {
+        let command = self.options.preview.as_ref()?;
+        Some(command.replace("{}", &launch::quote(&self.line(item)?)))
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "select",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "select" => {
+                if self.options.print_index {
+                    let _ = SELECTION.set(item.id.clone().into_bytes());
+                } else if let Some(line) = self.raw_line(item) {
+                    let line = if self.options.ansi {
+                        ansi::parse(&String::from_utf8_lossy(&line)).0.into_bytes()
+                    } else {
+                        line
+                    };
+                    let _ = SELECTION.set(line);
+                }
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
+
+impl Drop for Dmenu {
+    fn drop(&mut self) {
+        if let Ok(mut process) = self.process.lock() {
+            process.kill();
+        }
+    }
+}
+
+/// Sends an item for every entry in `reader` as soon as it is read, until the
+/// receiver is gone.
+fn read_entries(
+    reader: impl BufRead,
+    options: &Options,
+    lines: &Mutex<Vec<Vec<u8>>>,
+    sender: &Sender<Vec<Item>>,
+) {
+    let delimiter = if options.read0 { b'\0' } else { b'\n' };
+    for (index, line) in reader.split(delimiter).enumerate() {
+        let Ok(mut line) = line else {
+            return;
+        };
+        if delimiter == b'\n' && line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        let item = options.item(index, &String::from_utf8_lossy(&line));
+        if let Ok(mut lines) = lines.lock() {
+            lines.push(line);
+        }
+        let Some(item) = item else {
+            continue;
+        };
+        if sender.send(vec![item]).is_err() {
+            return;
+        }
+    }
+}
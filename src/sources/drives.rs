@@ -0,0 +1,175 @@
+use std::io;
+
+use serde_json::Value;
+
+use crate::{
+    open,
+    sources::{command_output, Action, Item, Outcome, Source},
+};
+
+/// A removable disk or partition as reported by `lsblk`.
+#[derive(Debug, Clone)]
+struct Drive {
+    device: String,
+    /// The whole disk the device belongs to, which is what gets powered off on eject.
+    disk: String,
+    mountpoint: Option<String>,
+}
+
+/// Removable drives and their partitions, mounted and unmounted through udisks2
+/// with `udisksctl`.
+#[derive(Debug, Default)]
+pub struct Drives {
+    drives: Vec<Drive>,
+}
+
+impl Drives {
+    fn drive(&self, device: &str) -> io::Result<&Drive> {
+        self.drives
+            .iter()
+            .find(|drive| drive.device == device)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "drive is gone"))
+    }
+}
+
+impl Source for Drives {
+    fn keyword(&self) -> &str {
+        "drives"
+    }
+
+    fn title(&self) -> &str {
+        "Drives"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        let output = command_output(
+            "lsblk",
+            &[
+                "-J",
+                "-o",
+                "NAME,PATH,LABEL,SIZE,MOUNTPOINT,RM,HOTPLUG,TYPE",
+            ],
+        );
+        let Some(json) = output
+            .ok()
+            .and_then(|output| serde_json::from_str::<Value>(&output).ok())
+        else {
+            return Vec::new();
+        };
+        self.drives.clear();
+        let mut items = Vec::new();
+        let disks = json["blockdevices"].as_array().cloned().unwrap_or_default();
+        for disk in disks.iter().filter(|disk| is_removable(disk)) {
+            let disk_path = text(disk, "path").unwrap_or_default();
+            let partitions = match disk["children"].as_array() {
+                Some(children) if !children.is_empty() => children.clone(),
+                _ => vec![disk.clone()],
+            };
+            for partition in &partitions {
+                let Some(device) = text(partition, "path") else {
+                    continue;
+                };
+                let mountpoint = text(partition, "mountpoint");
+                let name = text(partition, "label")
+                    .unwrap_or_else(|| text(partition, "name").unwrap_or_default());
+                let icon = if mountpoint.is_some() { "󰋊" } else { "󰕓" };
+                let mut item = Item::new(device.clone(), name, icon);
+                item.comment = Some(device.clone());
+                let size = text(partition, "size").unwrap_or_default();
+                item.detail = Some(match &mountpoint {
+                    Some(mountpoint) => format!("{} {}", mountpoint, size),
+                    None => size,
+                });
+                items.push(item);
+                self.drives.push(Drive {
+                    device,
+                    disk: disk_path.clone(),
+                    mountpoint,
+                });
+            }
+        }
+        items
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action {
+                name: "mount/unmount",
+                key: None,
+                bulk: false,
+                confirm: false,
+            },
+            Action {
+                name: "open",
+                key: Some('o'),
+                bulk: false,
+                confirm: false,
+            },
+            Action {
+                name: "eject",
+                key: Some('e'),
+                bulk: false,
+                confirm: false,
+            },
+        ]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        let Ok(drive) = self.drive(&item.id) else {
+            return Vec::new();
+        };
+        vec![
+            ("device", drive.device.clone()),
+            ("mountpoint", drive.mountpoint.clone().unwrap_or_default()),
+        ]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let Some(item) = item else {
+            return Ok(Outcome::Refresh);
+        };
+        let drive = self.drive(&item.id)?.clone();
+        match action {
+            "mount/unmount" if drive.mountpoint.is_some() => {
+                command_output("udisksctl", &["unmount", "-b", &drive.device])?;
+            }
+            "mount/unmount" => {
+                command_output("udisksctl", &["mount", "-b", &drive.device])?;
+            }
+            "open" => match &drive.mountpoint {
+                Some(mountpoint) => open::open(mountpoint)?,
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("{} is not mounted", drive.device),
+                    ))
+                }
+            },
+            "eject" => {
+                let mounted = self
+                    .drives
+                    .iter()
+                    .filter(|other| other.disk == drive.disk && other.mountpoint.is_some());
+                for partition in mounted {
+                    command_output("udisksctl", &["unmount", "-b", &partition.device])?;
+                }
+                command_output("udisksctl", &["power-off", "-b", &drive.disk])?;
+            }
+            _ => {}
+        }
+        Ok(Outcome::Refresh)
+    }
+}
+
+fn is_removable(device: &Value) -> bool {
+    [&device["rm"], &device["hotplug"]]
+        .iter()
+        .any(|flag| flag.as_bool() == Some(true) || flag.as_str() == Some("1"))
+}
+
+fn text(device: &Value, key: &str) -> Option<String> {
+    device[key]
+        .as_str()
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
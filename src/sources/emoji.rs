@@ -0,0 +1,95 @@
+use std::{cmp::Reverse, io};
+
+use crate::{
+    clipboard,
+    matcher::{self, MatchWeights},
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// Lines of a glyph, its name and keywords, separated by tabs.
+const DATABASE: &str = include_str!("emoji.tsv");
+
+/// Emoji and symbols like arrows or Greek letters, copied to the clipboard on Enter.
+#[derive(Debug)]
+pub struct Emoji {
+    weights: MatchWeights,
+}
+
+impl Emoji {
+    pub fn new(weights: MatchWeights) -> Self {
+        Self { weights }
+    }
+}
+
+/// The glyphs in the database with their names and keywords, emoji first.
+fn glyphs() -> impl Iterator<Item = (&'static str, &'static str, &'static str)> {
+    DATABASE
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| {
+            let mut fields = line.split('\t');
+            Some((
+                fields.next()?,
+                fields.next()?,
+                fields.next().unwrap_or_default(),
+            ))
+        })
+}
+
+impl Source for Emoji {
+    fn keyword(&self) -> &str {
+        "emoji"
+    }
+
+    fn title(&self) -> &str {
+        "Emoji"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.evaluate("").unwrap_or_default()
+    }
+
+    /// By how well the name matches, or else the keywords like `arrows` or `face
+    /// smiling`, which count for half.
+    fn evaluate(&mut self, query: &str) -> Option<Vec<Item>> {
+        let query = query.trim();
+        let mut matches = glyphs()
+            .filter_map(|(glyph, name, keywords)| {
+                let score = match matcher::score(name, query, &self.weights) {
+                    Some(score) => score.score,
+                    None => matcher::score(keywords, query, &self.weights)?.score / 2,
+                };
+                Some((Reverse(score), glyph, name))
+            })
+            .collect::<Vec<_>>();
+        matches.sort_by_key(|(score, _, _)| *score);
+        let items = matches
+            .into_iter()
+            .map(|(_, glyph, name)| Item::new(glyph, name, glyph))
+            .collect();
+        Some(items)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "copy",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("glyph", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "copy" => {
+                clipboard::copy(&item.id)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::{cmp::Reverse, fs, io, path::PathBuf};
+
+use crate::{
+    matcher::{self, MatchWeights},
+    open, paths,
+    sources::{format_size, Action, Item, Outcome, Source},
+};
+
+/// Browses the filesystem from a path typed like `~/Documents/rep`, listing the entries
+/// of `~/Documents` matching `rep`. Tab descends into the selected directory.
+#[derive(Debug)]
+pub struct Files {
+    weights: MatchWeights,
+    /// The directory part of the last query as typed, e.g. `~/Documents/`, which
+    /// completions extend.
+    typed_dir: String,
+}
+
+impl Files {
+    pub fn new(weights: MatchWeights) -> Self {
+        Self {
+            weights,
+            typed_dir: String::new(),
+        }
+    }
+}
+
+/// Whether `query` is a path to browse: absolute or in the home directory.
+fn is_path(query: &str) -> bool {
+    query.starts_with('/') || query.starts_with("~/")
+}
+
+/// The directory `query` browses as typed, up to its last slash, and the pattern after it.
+fn split(query: &str) -> (&str, &str) {
+    match query.rfind('/') {
+        Some(i) => query.split_at(i + 1),
+        None => ("", query),
+    }
+}
+
+/// `dir` with a leading `~` expanded to the home directory.
+fn expand(dir: &str) -> Option<PathBuf> {
+    match dir.strip_prefix('~') {
+        Some(rest) => Some(paths::home()?.join(rest.trim_start_matches('/'))),
+        None => Some(PathBuf::from(dir)),
+    }
+}
+
+impl Source for Files {
+    fn keyword(&self) -> &str {
+        "files"
+    }
+
+    fn title(&self) -> &str {
+        "Files"
+    }
+
+    fn detects(&self, query: &str) -> bool {
+        is_path(query)
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.evaluate("").unwrap_or_default()
+    }
+
+    /// By how well the name matches, directories first among equals, then alphabetically.
+    /// Hidden entries are only listed once the pattern starts with a dot.
+    fn evaluate(&mut self, query: &str) -> Option<Vec<Item>> {
+        let query = if is_path(query) { query } else { "~/" };
+        let (typed_dir, pattern) = split(query);
+        self.typed_dir = typed_dir.to_string();
+        let dir = expand(typed_dir)?;
+        let mut entries = Vec::new();
+        for entry in fs::read_dir(&dir).into_iter().flatten().flatten() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.starts_with('.') && !pattern.starts_with('.') {
+                continue;
+            }
+            let Some(score) = matcher::score(&name, pattern, &self.weights) else {
+                continue;
+            };
+            let metadata = entry.metadata().ok();
+            let is_dir = entry.path().is_dir();
+            let mut item = if is_dir {
+                Item::new(
+                    entry.path().display().to_string(),
+                    format!("{}/", name),
+                    "󰉋",
+                )
+            } else {
+                Item::new(entry.path().display().to_string(), &name, "󰈔")
+            };
+            if !is_dir {
+                item.detail = metadata.map(|metadata| format_size(metadata.len()));
+            }
+            item.path = Some(entry.path().into());
+            entries.push((Reverse(score.score), !is_dir, name.to_lowercase(), item));
+        }
+        entries.sort_by(|a, b| (&a.0, a.1, &a.2).cmp(&(&b.0, b.1, &b.2)));
+        Some(entries.into_iter().map(|(_, _, _, item)| item).collect())
+    }
+
+    fn complete(&self, item: &Item) -> Option<String> {
+        Some(format!("{}{}", self.typed_dir, item.name))
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "open",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("path", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "open" => {
+                open::open(&item.id)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
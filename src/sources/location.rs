@@ -0,0 +1,107 @@
+use std::{io, path::PathBuf};
+
+use crate::{
+    open, paths,
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// URLs and absolute paths, opened with the default app. URLs are also picked up without
+/// the keyword, while paths typed without it are browsed by `Files`.
+#[derive(Debug, Default)]
+pub struct Location;
+
+impl Location {
+    /// The URL or path `query` refers to, with `www.` URLs given a scheme and `~` expanded.
+    fn target(query: &str) -> Option<String> {
+        let query = query.trim();
+        if query.contains(char::is_whitespace) {
+            return None;
+        }
+        if is_url(query) || query.starts_with('/') {
+            Some(query.to_string())
+        } else if query.starts_with("www.") && query.len() > 4 {
+            Some(format!("https://{}", query))
+        } else if let Some(rest) = query.strip_prefix("~/") {
+            Some(paths::home()?.join(rest).display().to_string())
+        } else {
+            None
+        }
+    }
+}
+
+/// Whether `text` starts with a scheme like `https://`.
+fn is_url(text: &str) -> bool {
+    text.split_once("://").is_some_and(|(scheme, rest)| {
+        !scheme.is_empty()
+            && !rest.is_empty()
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+.-".contains(c))
+    })
+}
+
+impl Source for Location {
+    fn keyword(&self) -> &str {
+        "open"
+    }
+
+    fn title(&self) -> &str {
+        "Open"
+    }
+
+    fn detects(&self, query: &str) -> bool {
+        Self::target(query).is_some_and(|target| is_url(&target))
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.evaluate("").unwrap_or_default()
+    }
+
+    fn evaluate(&mut self, query: &str) -> Option<Vec<Item>> {
+        if query.trim().is_empty() {
+            let mut item = Item::new("", "Open", "󰏌");
+            item.comment = Some("Type a URL or a path like ~/Downloads".to_string());
+            return Some(vec![item]);
+        }
+        let Some(target) = Self::target(query) else {
+            return Some(Vec::new());
+        };
+        let mut item = if is_url(&target) {
+            Item::new(&target, &target, "󰖟")
+        } else {
+            let path = PathBuf::from(&target);
+            let icon = if path.is_dir() { "󰉋" } else { "󰈔" };
+            let mut item = Item::new(&target, &target, icon);
+            if !path.exists() {
+                item.detail = Some("not found".to_string());
+            }
+            item.path = Some(path.into());
+            item
+        };
+        item.comment = Some("Open with the default app".to_string());
+        Some(vec![item])
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "open",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("target", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "open" && !item.id.is_empty() => {
+                open::open(&item.id)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use std::io;
+
+use crate::sources::{command_output, Action, Item, Outcome, Source};
+
+/// MPRIS media players, controlled through `playerctl`.
+#[derive(Debug, Default)]
+pub struct Media;
+
+impl Media {
+    /// The playback status and now-playing text of `player`.
+    fn now_playing(player: &str) -> (String, String) {
+        let format = "{{status}}\t{{artist}}\t{{title}}";
+        let output = command_output("playerctl", &["-p", player, "metadata", "--format", format])
+            .unwrap_or_default();
+        let mut fields = output.trim_end_matches('\n').split('\t');
+        let status = fields.next().unwrap_or_default().to_string();
+        let playing = fields
+            .filter(|field| !field.is_empty())
+            .collect::<Vec<&str>>()
+            .join(" - ");
+        (status, playing)
+    }
+}
+
+impl Source for Media {
+    fn keyword(&self) -> &str {
+        "media"
+    }
+
+    fn title(&self) -> &str {
+        "Media"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        let Ok(players) = command_output("playerctl", &["-l"]) else {
+            return Vec::new();
+        };
+        players
+            .lines()
+            .map(|player| {
+                let (status, playing) = Self::now_playing(player);
+                let icon = match status.as_str() {
+                    "Playing" => "󰐊",
+                    "Paused" => "󰏤",
+                    _ => "󰓛",
+                };
+                let name = if playing.is_empty() {
+                    player.to_string()
+                } else {
+                    playing
+                };
+                let mut item = Item::new(player, name, icon);
+                item.comment = Some(player.to_string());
+                item.detail = Some(status.to_lowercase());
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        [
+            ("play/pause", None),
+            ("next", Some('n')),
+            ("previous", Some('p')),
+        ]
+        .into_iter()
+        .map(|(name, key)| Action {
+            name,
+            key,
+            bulk: false,
+            confirm: false,
+        })
+        .collect()
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("player", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let command = match action {
+            "play/pause" => "play-pause",
+            "next" => "next",
+            "previous" => "previous",
+            _ => return Ok(Outcome::Refresh),
+        };
+        if let Some(item) = item {
+            command_output("playerctl", &["-p", &item.id, command])?;
+        }
+        Ok(Outcome::Refresh)
+    }
+}
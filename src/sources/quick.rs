@@ -0,0 +1,122 @@
+use std::io;
+
+use serde::Deserialize;
+
+use crate::sources::{command_output, Action, Item, Outcome, Source};
+
+/// A percentage setting adjusted by typing e.g. `vol 40` or `bright +10`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuickCommand {
+    pub keyword: String,
+    pub name: String,
+    /// Shell command printing the current value as a percentage.
+    pub get: String,
+    /// Shell command applying a new value, where `{value}` expands to the percentage.
+    pub set: String,
+}
+
+pub fn default_quick_commands() -> Vec<QuickCommand> {
+    vec![
+        QuickCommand {
+            keyword: "vol".to_string(),
+            name: "Volume".to_string(),
+            get: "wpctl get-volume @DEFAULT_AUDIO_SINK@ | awk '{print int($2 * 100)}'".to_string(),
+            set: "wpctl set-volume @DEFAULT_AUDIO_SINK@ {value}%".to_string(),
+        },
+        QuickCommand {
+            keyword: "bright".to_string(),
+            name: "Brightness".to_string(),
+            get: "brightnessctl -m | cut -d, -f4 | tr -d %".to_string(),
+            set: "brightnessctl set {value}%".to_string(),
+        },
+    ]
+}
+
+#[derive(Debug)]
+pub struct Quick {
+    command: QuickCommand,
+    current: Option<u32>,
+}
+
+impl Quick {
+    pub fn new(command: QuickCommand) -> Self {
+        Self {
+            command,
+            current: None,
+        }
+    }
+
+    /// The value `query` asks for: absolute like `40`, or relative like `+10` and `-5`.
+    fn target(&self, query: &str) -> Option<u32> {
+        let query = query.trim().trim_end_matches('%');
+        let value = if let Some(delta) = query.strip_prefix('+') {
+            self.current? as i64 + delta.parse::<i64>().ok()?
+        } else if let Some(delta) = query.strip_prefix('-') {
+            self.current? as i64 - delta.parse::<i64>().ok()?
+        } else {
+            query.parse::<i64>().ok()?
+        };
+        Some(value.clamp(0, 100) as u32)
+    }
+
+    fn current_text(&self) -> String {
+        self.current
+            .map(|current| format!("{}%", current))
+            .unwrap_or_else(|| "?".to_string())
+    }
+}
+
+impl Source for Quick {
+    fn keyword(&self) -> &str {
+        &self.command.keyword
+    }
+
+    fn title(&self) -> &str {
+        &self.command.name
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.current = command_output("sh", &["-c", &self.command.get])
+            .ok()
+            .and_then(|output| output.trim().parse::<f64>().ok())
+            .map(|value| value.round() as u32);
+        self.evaluate("").unwrap_or_default()
+    }
+
+    fn evaluate(&mut self, query: &str) -> Option<Vec<Item>> {
+        if query.is_empty() {
+            let name = format!("{} {}", self.command.name, self.current_text());
+            let mut item = Item::new("", name, "󰓅");
+            item.comment = Some("Type a value like 40, +10 or -10".to_string());
+            return Some(vec![item]);
+        }
+        let Some(target) = self.target(query) else {
+            return Some(Vec::new());
+        };
+        let name = format!(
+            "{} {} → {}%",
+            self.command.name,
+            self.current_text(),
+            target
+        );
+        Some(vec![Item::new(target.to_string(), name, "󰓅")])
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "set",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let value = item.map(|item| item.id.as_str()).unwrap_or_default();
+        if action == "set" && !value.is_empty() {
+            let command = self.command.set.replace("{value}", value);
+            command_output("sh", &["-c", &command])?;
+        }
+        Ok(Outcome::Refresh)
+    }
+}
@@ -0,0 +1,83 @@
+use std::{
+    collections::HashSet,
+    env, fs, io,
+    os::unix::fs::PermissionsExt,
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    launch,
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// The executables on `$PATH`, for programs without a desktop file.
+#[derive(Debug, Default)]
+pub struct Run;
+
+/// Executables in `dirs` by name, keeping the first of each name like the shell does.
+fn executables(dirs: impl Iterator<Item = PathBuf>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    let mut executables = Vec::new();
+    for dir in dirs {
+        for file in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = file.path();
+            if is_executable(&path) && seen.insert(file.file_name()) {
+                executables.push(path);
+            }
+        }
+    }
+    executables.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    executables
+}
+
+pub fn is_executable(path: &Path) -> bool {
+    fs::metadata(path)
+        .is_ok_and(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+}
+
+impl Source for Run {
+    fn keyword(&self) -> &str {
+        "run"
+    }
+
+    fn title(&self) -> &str {
+        "Run"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        let path = env::var_os("PATH").unwrap_or_default();
+        executables(env::split_paths(&path))
+            .into_iter()
+            .map(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                let mut item = Item::new(path.display().to_string(), name, "");
+                item.detail = path.parent().map(|dir| dir.display().to_string());
+                item.path = Some(path.into());
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "run",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("path", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "run" => {
+                launch::spawn_detached(std::slice::from_ref(&item.id))?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
@@ -0,0 +1,194 @@
+use std::{collections::HashSet, fs, io, path::Path};
+
+use crate::{
+    paths,
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// The hosts in `~/.ssh/config` and `~/.ssh/known_hosts`, connected to with `ssh` in the
+/// launcher's terminal.
+#[derive(Debug, Default)]
+pub struct Ssh;
+
+/// A host to connect to, with the destination passed to `ssh`.
+#[derive(Debug)]
+struct Host {
+    name: String,
+    destination: String,
+    /// Where the alias of a config host leads, e.g. `alice@example.org`.
+    detail: Option<String>,
+}
+
+/// The `Host` aliases in `config` without patterns, with the `User` and `HostName`
+/// they were given.
+fn config_hosts(config: &str) -> Vec<Host> {
+    let mut hosts = Vec::new();
+    // Where the hosts of the current `Host` block start in `hosts`.
+    let mut block = 0;
+    let (mut user, mut hostname) = (None, None);
+    for line in config.lines() {
+        let Some((key, value)) = line.trim().split_once([' ', '\t', '=']) else {
+            continue;
+        };
+        let value = value.trim_start_matches([' ', '\t', '=']).trim();
+        match key.to_lowercase().as_str() {
+            "host" | "match" => {
+                describe(&mut hosts[block..], user.take(), hostname.take());
+                block = hosts.len();
+                if key.eq_ignore_ascii_case("match") {
+                    continue;
+                }
+                let aliases = value
+                    .split_whitespace()
+                    .filter(|alias| !alias.contains(['*', '?', '!']));
+                hosts.extend(aliases.map(|alias| Host {
+                    name: alias.to_string(),
+                    destination: alias.to_string(),
+                    detail: None,
+                }));
+            }
+            "user" if user.is_none() => user = Some(value.to_string()),
+            "hostname" if hostname.is_none() => hostname = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    describe(&mut hosts[block..], user, hostname);
+    hosts
+}
+
+/// Sets the detail of the hosts of a block to where it leads, e.g. `alice@example.org`.
+fn describe(hosts: &mut [Host], user: Option<String>, hostname: Option<String>) {
+    let detail = match (user, hostname) {
+        (Some(user), Some(hostname)) => format!("{}@{}", user, hostname),
+        (None, Some(hostname)) => hostname,
+        (Some(user), None) => format!("{}@", user),
+        (None, None) => return,
+    };
+    for host in hosts {
+        host.detail = Some(detail.clone());
+    }
+}
+
+/// The hosts `known_hosts` lists by name, skipping hashed ones. Hosts on another port
+/// than 22, written `[example.org]:2222`, are connected to with an `ssh://` URI.
+fn known_hosts(known_hosts: &str) -> Vec<Host> {
+    let mut hosts = Vec::new();
+    for line in known_hosts.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with(['#', '@', '|']) {
+            continue;
+        }
+        let Some(names) = line.split_whitespace().next() else {
+            continue;
+        };
+        for name in names.split(',') {
+            let host = match name
+                .strip_prefix('[')
+                .and_then(|name| name.split_once("]:"))
+            {
+                Some((host, port)) => Host {
+                    name: format!("{}:{}", host, port),
+                    destination: format!("ssh://{}:{}", host, port),
+                    detail: None,
+                },
+                None => Host {
+                    name: name.to_string(),
+                    destination: name.to_string(),
+                    detail: None,
+                },
+            };
+            hosts.push(host);
+        }
+    }
+    hosts
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_default()
+}
+
+impl Source for Ssh {
+    fn keyword(&self) -> &str {
+        "ssh"
+    }
+
+    fn title(&self) -> &str {
+        "SSH"
+    }
+
+    /// The config's hosts in their order, then the known hosts not among them.
+    fn items(&mut self) -> Vec<Item> {
+        let Some(dir) = paths::home().map(|home| home.join(".ssh")) else {
+            return Vec::new();
+        };
+        let mut hosts = config_hosts(&read(&dir.join("config")));
+        let mut known = known_hosts(&read(&dir.join("known_hosts")));
+        known.sort_by(|a, b| a.name.cmp(&b.name));
+        hosts.extend(known);
+        let mut seen = HashSet::new();
+        hosts
+            .into_iter()
+            .filter(|host| seen.insert(host.name.clone()))
+            .map(|host| {
+                let mut item = Item::new(host.destination, host.name, "󰣀");
+                item.detail = host.detail;
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "connect",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        vec![("host", item.id.clone())]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "connect" => {
+                Ok(Outcome::Terminal(vec!["ssh".to_string(), item.id.clone()]))
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_hosts_skip_patterns() {
+        let config = "Host *\n  ServerAliveInterval 30\n\nHost web web2\n  HostName example.org\n  User alice\n\nHost=pi\n\tHostName=192.168.1.2\n";
+        let hosts = config_hosts(config);
+        let names = hosts
+            .iter()
+            .map(|host| host.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["web", "web2", "pi"]);
+        assert_eq!(hosts[1].detail.as_deref(), Some("alice@example.org"));
+        assert_eq!(hosts[2].detail.as_deref(), Some("192.168.1.2"));
+    }
+
+    #[test]
+    fn known_hosts_skip_hashed() {
+        let known = "example.org,93.184.216.34 ssh-ed25519 AAAA\n|1|abc=|def= ssh-rsa AAAA\n@revoked bad.org ssh-rsa AAAA\n[git.example.org]:2222 ssh-ed25519 AAAA\n";
+        let hosts = known_hosts(known);
+        let names = hosts
+            .iter()
+            .map(|host| host.name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            names,
+            ["example.org", "93.184.216.34", "git.example.org:2222"]
+        );
+        assert_eq!(hosts[2].destination, "ssh://git.example.org:2222");
+    }
+}
@@ -0,0 +1,198 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use ini::Ini;
+
+use crate::{
+    paths,
+    sources::{format_size, percent_decode, Action, Item, Outcome, Source},
+};
+
+/// Trashed files sent to the list at once while streaming.
+const BATCH_SIZE: usize = 16;
+
+/// Files in the XDG trash (`$XDG_DATA_HOME/Trash`), which can be restored to their
+/// original location or deleted for good.
+#[derive(Debug, Clone)]
+pub struct Trash {
+    dir: Option<PathBuf>,
+}
+
+impl Trash {
+    pub fn new() -> Self {
+        Self {
+            dir: paths::data_home().map(|dir| dir.join("Trash")),
+        }
+    }
+
+    fn files_dir(&self) -> io::Result<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join("files"))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no trash directory"))
+    }
+
+    fn info_path(&self, name: &str) -> io::Result<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join("info").join(format!("{}.trashinfo", name)))
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "no trash directory"))
+    }
+
+    fn original_path(&self, name: &str) -> Option<PathBuf> {
+        let info = Ini::load_from_file(self.info_path(name).ok()?).ok()?;
+        let path = info.section(Some("Trash Info"))?.get("Path")?;
+        Some(PathBuf::from(percent_decode(path)))
+    }
+
+    /// The names of the trashed files, sorted.
+    fn names(&self) -> Vec<String> {
+        let Ok(files) = self.files_dir().and_then(fs::read_dir) else {
+            return Vec::new();
+        };
+        let mut names = files
+            .flatten()
+            .map(|file| file.file_name().to_string_lossy().to_string())
+            .collect::<Vec<String>>();
+        names.sort();
+        names
+    }
+
+    /// Builds the item for a trashed file, which walks all of a directory for its size.
+    fn item(&self, name: &str) -> Item {
+        let path = self.files_dir().unwrap_or_default().join(name);
+        let icon = if path.is_dir() { "󰉋" } else { "󰈔" };
+        let mut item = Item::new(name, name, icon);
+        item.comment = self
+            .original_path(name)
+            .map(|original| original.display().to_string());
+        item.detail = Some(format_size(disk_usage(&path)));
+        item.path = Some(path.into());
+        item
+    }
+
+    fn restore(&self, name: &str) -> io::Result<()> {
+        let original = self
+            .original_path(name)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "missing trash info"))?;
+        if original.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!("{} already exists", original.display()),
+            ));
+        }
+        if let Some(parent) = original.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::rename(self.files_dir()?.join(name), &original)?;
+        fs::remove_file(self.info_path(name)?)
+    }
+
+    fn delete(&self, name: &str) -> io::Result<()> {
+        let path = self.files_dir()?.join(name);
+        if path.is_dir() && !path.is_symlink() {
+            fs::remove_dir_all(&path)?;
+        } else {
+            fs::remove_file(&path)?;
+        }
+        let _ = fs::remove_file(self.info_path(name)?);
+        Ok(())
+    }
+}
+
+impl Source for Trash {
+    fn keyword(&self) -> &str {
+        "trash"
+    }
+
+    fn title(&self) -> &str {
+        "Trash"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.names().iter().map(|name| self.item(name)).collect()
+    }
+
+    fn stream(&mut self) -> Option<Receiver<Vec<Item>>> {
+        let trash = self.clone();
+        let (sender, receiver) = mpsc::channel();
+        thread::spawn(move || {
+            for names in trash.names().chunks(BATCH_SIZE) {
+                let batch = names.iter().map(|name| trash.item(name)).collect();
+                if sender.send(batch).is_err() {
+                    return;
+                }
+            }
+        });
+        Some(receiver)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![
+            Action {
+                name: "restore",
+                key: None,
+                bulk: false,
+                confirm: false,
+            },
+            Action {
+                name: "delete",
+                key: Some('d'),
+                bulk: false,
+                confirm: true,
+            },
+            Action {
+                name: "empty trash",
+                key: Some('x'),
+                bulk: true,
+                confirm: true,
+            },
+        ]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        let mut values = Vec::new();
+        if let Ok(files) = self.files_dir() {
+            values.push(("path", files.join(&item.id).display().to_string()));
+        }
+        if let Some(original) = &item.comment {
+            values.push(("original", original.clone()));
+        }
+        values
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match (action, item) {
+            ("restore", Some(item)) => self.restore(&item.id)?,
+            ("delete", Some(item)) => self.delete(&item.id)?,
+            ("empty trash", _) => {
+                for name in self.names() {
+                    self.delete(&name)?;
+                }
+            }
+            _ => {}
+        }
+        Ok(Outcome::Refresh)
+    }
+}
+
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return 0;
+    };
+    if !metadata.is_dir() {
+        return metadata.len();
+    }
+    fs::read_dir(path)
+        .map(|entries| {
+            entries
+                .flatten()
+                .map(|entry| disk_usage(&entry.path()))
+                .sum()
+        })
+        .unwrap_or_default()
+}
@@ -0,0 +1,171 @@
+use std::{fs, io, path::Path};
+
+use crate::{
+    launch,
+    sources::{command_output, Action, Item, Outcome, Source},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Backend {
+    NetworkManager,
+    WgQuick,
+}
+
+#[derive(Debug, Clone)]
+struct Connection {
+    name: String,
+    backend: Backend,
+    active: bool,
+}
+
+/// NetworkManager VPN connections and wg-quick interfaces, brought up and down with
+/// `nmcli` and `wg-quick`.
+#[derive(Debug)]
+pub struct Vpn {
+    /// Wraps `wg-quick`, which needs root.
+    elevate_command: String,
+    connections: Vec<Connection>,
+}
+
+impl Vpn {
+    pub fn new(elevate_command: &str) -> Self {
+        Self {
+            elevate_command: elevate_command.to_string(),
+            connections: Vec::new(),
+        }
+    }
+
+    fn network_manager() -> Vec<Connection> {
+        let Ok(output) = command_output("nmcli", &["-t", "-f", "NAME,TYPE,STATE", "con", "show"])
+        else {
+            return Vec::new();
+        };
+        output
+            .lines()
+            .filter_map(|line| {
+                let fields = split_terse(line);
+                let [name, kind, state] = fields.as_slice() else {
+                    return None;
+                };
+                (kind == "vpn" || kind == "wireguard").then(|| Connection {
+                    name: name.clone(),
+                    backend: Backend::NetworkManager,
+                    active: state == "activated",
+                })
+            })
+            .collect()
+    }
+
+    fn wg_quick() -> Vec<Connection> {
+        let Ok(configs) = fs::read_dir("/etc/wireguard") else {
+            return Vec::new();
+        };
+        configs
+            .flatten()
+            .filter_map(|config| {
+                let path = config.path();
+                if path.extension()? != "conf" {
+                    return None;
+                }
+                let name = path.file_stem()?.to_string_lossy().to_string();
+                let active = Path::new("/sys/class/net").join(&name).exists();
+                Some(Connection {
+                    name,
+                    backend: Backend::WgQuick,
+                    active,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Source for Vpn {
+    fn keyword(&self) -> &str {
+        "vpn"
+    }
+
+    fn title(&self) -> &str {
+        "VPN"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.connections = Self::network_manager();
+        self.connections.extend(Self::wg_quick());
+        self.connections.sort_by(|a, b| a.name.cmp(&b.name));
+        self.connections
+            .iter()
+            .enumerate()
+            .map(|(i, connection)| {
+                let icon = if connection.active { "󰦝" } else { "󰦞" };
+                let mut item = Item::new(i.to_string(), connection.name.clone(), icon);
+                item.comment = Some(match connection.backend {
+                    Backend::NetworkManager => "NetworkManager".to_string(),
+                    Backend::WgQuick => "wg-quick".to_string(),
+                });
+                let state = if connection.active { "up" } else { "down" };
+                item.detail = Some(state.to_string());
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "toggle",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let connection = item
+            .and_then(|item| item.id.parse::<usize>().ok())
+            .and_then(|i| self.connections.get(i))
+            .filter(|_| action == "toggle");
+        let Some(connection) = connection else {
+            return Ok(Outcome::Refresh);
+        };
+        let direction = if connection.active { "down" } else { "up" };
+        match connection.backend {
+            Backend::NetworkManager => {
+                command_output("nmcli", &["con", direction, "id", &connection.name])?;
+            }
+            Backend::WgQuick => {
+                let argv = launch::wrap(
+                    &self.elevate_command,
+                    vec![
+                        "wg-quick".to_string(),
+                        direction.to_string(),
+                        connection.name.clone(),
+                    ],
+                )
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "unbalanced quotes in elevate_command",
+                    )
+                })?;
+                if let Some((program, args)) = argv.split_first() {
+                    let args = args.iter().map(String::as_str).collect::<Vec<&str>>();
+                    command_output(program, &args)?;
+                }
+            }
+        }
+        Ok(Outcome::Refresh)
+    }
+}
+
+/// Splits a line of `nmcli -t` output on unescaped colons.
+fn split_terse(line: &str) -> Vec<String> {
+    let mut fields = vec![String::new()];
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => fields.last_mut().unwrap().extend(chars.next()),
+            ':' => fields.push(String::new()),
+            c => fields.last_mut().unwrap().push(c),
+        }
+    }
+    fields
+}
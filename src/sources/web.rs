@@ -0,0 +1,111 @@
+use std::{
+    io,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    log, network, open,
+    sources::{percent_encode, Action, Item, Outcome, Source},
+};
+
+const SEARCH_URL: &str = "https://duckduckgo.com/?q=";
+
+const SUGGESTIONS_URL: &str = "https://duckduckgo.com/ac/?type=list&q=";
+
+/// How long typing must pause before suggestions are requested.
+const DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Searches the web for the query, listing search suggestions below it when the
+/// `[network]` config allows `web`.
+#[derive(Debug, Default)]
+pub struct Web {
+    /// Counts queries, so suggestions for an outdated one aren't requested.
+    generation: Arc<AtomicU64>,
+}
+
+fn search_item(query: &str) -> Item {
+    let mut item = Item::new(query, query, "󰖟");
+    item.comment = Some("Search the web".to_string());
+    item
+}
+
+/// The suggestions in a response like `["rust", ["rust lang", "rustup"]]`.
+fn parse_suggestions(response: &str) -> Vec<String> {
+    let Ok((_, suggestions)) = serde_json::from_str::<(String, Vec<String>)>(response) else {
+        return Vec::new();
+    };
+    suggestions
+}
+
+impl Source for Web {
+    fn keyword(&self) -> &str {
+        "web"
+    }
+
+    fn title(&self) -> &str {
+        "Web"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        let mut item = Item::new("", "Web", "󰖟");
+        item.comment = Some("Type something to search the web for".to_string());
+        vec![item]
+    }
+
+    fn stream_query(&mut self, query: &str) -> Option<Receiver<Vec<Item>>> {
+        let query = query.trim().to_string();
+        if query.is_empty() {
+            return None;
+        }
+        let generation = self.generation.fetch_add(1, Ordering::SeqCst) + 1;
+        let current = self.generation.clone();
+        let (sender, receiver) = mpsc::channel();
+        let _ = sender.send(vec![search_item(&query)]);
+        if network::allows("web") {
+            thread::spawn(move || {
+                thread::sleep(DEBOUNCE);
+                if current.load(Ordering::SeqCst) != generation {
+                    return;
+                }
+                let url = format!("{}{}", SUGGESTIONS_URL, percent_encode(&query));
+                match network::get("web", &url) {
+                    Ok(response) => {
+                        let items = parse_suggestions(&response)
+                            .into_iter()
+                            .filter(|suggestion| *suggestion != query)
+                            .map(|suggestion| search_item(&suggestion))
+                            .collect();
+                        let _ = sender.send(items);
+                    }
+                    Err(error) => log::info(&format!("web suggestions: {}", error)),
+                }
+            });
+        }
+        Some(receiver)
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "search",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        match item {
+            Some(item) if action == "search" && !item.id.is_empty() => {
+                open::open(&format!("{}{}", SEARCH_URL, percent_encode(&item.id)))?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use std::{io, rc::Rc};
+
+use crate::{
+    compositor::{Compositor, Window},
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// Open windows, focused through the compositor.
+#[derive(Debug)]
+pub struct Windows {
+    compositor: Rc<dyn Compositor>,
+    windows: Vec<Window>,
+}
+
+impl Windows {
+    pub fn new(compositor: Rc<dyn Compositor>) -> Self {
+        Self {
+            compositor,
+            windows: Vec::new(),
+        }
+    }
+}
+
+impl Source for Windows {
+    fn keyword(&self) -> &str {
+        "win"
+    }
+
+    fn title(&self) -> &str {
+        "Windows"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.windows = self.compositor.windows().unwrap_or_default();
+        self.windows
+            .iter()
+            .enumerate()
+            .map(|(i, window)| {
+                let icon = if window.focused { "󰖯" } else { "󰖰" };
+                let mut item = Item::new(i.to_string(), window.title.clone(), icon);
+                item.comment = Some(window.app_id.clone());
+                item.detail = Some(window.workspace.clone());
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "focus",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn placeholders(&self, item: &Item) -> Vec<(&'static str, String)> {
+        let Some(window) = item
+            .id
+            .parse::<usize>()
+            .ok()
+            .and_then(|i| self.windows.get(i))
+        else {
+            return Vec::new();
+        };
+        vec![
+            ("window", window.id.clone()),
+            (
+                "pid",
+                window.pid.map(|pid| pid.to_string()).unwrap_or_default(),
+            ),
+        ]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let window = item
+            .and_then(|item| item.id.parse::<usize>().ok())
+            .and_then(|i| self.windows.get(i));
+        match (action, window) {
+            ("focus", Some(window)) => {
+                self.compositor.focus_window(window)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
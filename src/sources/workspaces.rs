@@ -0,0 +1,69 @@
+use std::{io, rc::Rc};
+
+use crate::{
+    compositor::{Compositor, Workspace},
+    sources::{Action, Item, Outcome, Source},
+};
+
+/// The compositor's workspaces, switched to on Enter.
+#[derive(Debug)]
+pub struct Workspaces {
+    compositor: Rc<dyn Compositor>,
+    workspaces: Vec<Workspace>,
+}
+
+impl Workspaces {
+    pub fn new(compositor: Rc<dyn Compositor>) -> Self {
+        Self {
+            compositor,
+            workspaces: Vec::new(),
+        }
+    }
+}
+
+impl Source for Workspaces {
+    fn keyword(&self) -> &str {
+        "ws"
+    }
+
+    fn title(&self) -> &str {
+        "Workspaces"
+    }
+
+    fn items(&mut self) -> Vec<Item> {
+        self.workspaces = self.compositor.workspaces().unwrap_or_default();
+        self.workspaces
+            .iter()
+            .enumerate()
+            .map(|(i, workspace)| {
+                let icon = if workspace.focused { "󰍹" } else { "󰍺" };
+                let mut item = Item::new(i.to_string(), workspace.name.clone(), icon);
+                let plural = if workspace.windows == 1 { "" } else { "s" };
+                item.detail = Some(format!("{} window{}", workspace.windows, plural));
+                item
+            })
+            .collect()
+    }
+
+    fn actions(&self) -> Vec<Action> {
+        vec![Action {
+            name: "switch",
+            key: None,
+            bulk: false,
+            confirm: false,
+        }]
+    }
+
+    fn run(&mut self, action: &str, item: Option<&Item>) -> io::Result<Outcome> {
+        let workspace = item
+            .and_then(|item| item.id.parse::<usize>().ok())
+            .and_then(|i| self.workspaces.get(i));
+        match (action, workspace) {
+            ("switch", Some(workspace)) => {
+                self.compositor.switch_workspace(workspace)?;
+                Ok(Outcome::Exit)
+            }
+            _ => Ok(Outcome::Refresh),
+        }
+    }
+}
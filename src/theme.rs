@@ -0,0 +1,485 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::PathBuf,
+    sync::{OnceLock, RwLock},
+};
+
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style, Stylize},
+    symbols::border,
+    widgets::Block,
+};
+use serde::Deserialize;
+
+use crate::{config::Config, log, paths};
+
+static THEME: RwLock<Option<&'static Theme>> = RwLock::new(None);
+
+static DEFAULT: OnceLock<Theme> = OnceLock::new();
+
+const ASCII_BORDER: border::Set = border::Set {
+    top_left: "+",
+    top_right: "+",
+    bottom_left: "+",
+    bottom_right: "+",
+    vertical_left: "|",
+    vertical_right: "|",
+    horizontal_top: "-",
+    horizontal_bottom: "-",
+};
+
+/// The styles and symbols every widget draws with.
+#[derive(Debug, Clone)]
+pub struct Theme {
+    /// Off when `NO_COLOR` is set, which leaves only bold, reversed and the like.
+    pub colors: bool,
+    /// Nerd Font icons before names, off in ASCII mode.
+    pub icons: bool,
+    /// Fades the selection in when `animations` is on.
+    pub fade: bool,
+    pub text: Style,
+    pub selected: Style,
+    /// Hints, details and other secondary text.
+    pub dim: Style,
+    /// Completions and the values of empty fields.
+    pub placeholder: Style,
+    /// The group titles of a sectioned list.
+    pub header: Style,
+    pub title: Style,
+    pub border: Style,
+    /// The border of widgets without focus.
+    pub border_unfocused: Style,
+    /// The characters of a name that match the query.
+    pub matched: Style,
+    /// The detected mode and overlay titles.
+    pub accent: Style,
+    pub error: Style,
+    /// The dot of running apps.
+    pub running: Style,
+    pub urgent: Style,
+    /// The background of each badge, by its label.
+    pub badges: BTreeMap<String, Color>,
+    pub border_set: border::Set,
+    /// Before the query in the compact layout.
+    pub prompt: String,
+    /// Before the selected row.
+    pub selector: String,
+    pub scrollbar_thumb: String,
+    pub running_dot: String,
+    /// Drawn before a mode's rows when it has an accent but no glyph.
+    pub gutter: String,
+    /// Between the groups of a sectioned list.
+    pub separator: String,
+    /// Before the desktop actions listed under an entry.
+    pub action_arrow: String,
+    /// After the focused field of a form.
+    pub cursor: String,
+    /// Between the key hints of a block title.
+    pub hint_separator: String,
+    /// Left and right, in key hints.
+    pub arrows: [String; 2],
+    /// Around a value cycled with Left and Right.
+    pub chevrons: [String; 2],
+    pub spinner: Vec<char>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            colors: true,
+            icons: true,
+            fade: true,
+            text: Style::new().fg(Color::White),
+            selected: Style::new()
+                .fg(Color::Black)
+                .bg(Color::White)
+                .not_reversed(),
+            dim: Style::new().dim(),
+            placeholder: Style::new().dim(),
+            header: Style::new().bold().dim(),
+            title: Style::new(),
+            border: Style::new(),
+            border_unfocused: Style::new().dim(),
+            matched: Style::new().bold().reversed(),
+            accent: Style::new().cyan(),
+            error: Style::new().red(),
+            running: Style::new().green(),
+            urgent: Style::new().light_red(),
+            badges: BTreeMap::from(
+                [
+                    ("flatpak", Color::Blue),
+                    ("terminal", Color::Green),
+                    ("sandboxed", Color::Yellow),
+                    ("new", Color::Magenta),
+                    ("running", Color::Cyan),
+                ]
+                .map(|(label, color)| (label.to_string(), color)),
+            ),
+            border_set: border::PLAIN,
+            prompt: "› ".to_string(),
+            selector: String::new(),
+            scrollbar_thumb: "┃".to_string(),
+            running_dot: "•".to_string(),
+            gutter: "▎".to_string(),
+            separator: "───".to_string(),
+            action_arrow: "↳".to_string(),
+            cursor: "▏".to_string(),
+            hint_separator: " · ".to_string(),
+            arrows: ["←".to_string(), "→".to_string()],
+            chevrons: ["‹".to_string(), "›".to_string()],
+            spinner: vec!['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'],
+        }
+    }
+}
+
+impl Theme {
+    /// The theme `config` asks for, without colors if `NO_COLOR` is set.
+    pub fn new(config: &Config) -> Self {
+        let name = config
+            .theme
+            .name
+            .as_deref()
+            .unwrap_or(if config.high_contrast {
+                "high-contrast"
+            } else {
+                "default"
+            });
+        let mut theme = config.theme.apply(Self::named(name));
+        if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+            theme = theme.without_colors();
+        }
+        if config.ascii {
+            theme = theme.ascii();
+        }
+        theme
+    }
+
+    /// A built-in theme, or else the theme file `name` in the themes dir or at the path
+    /// `name`. Falls back to the default theme.
+    fn named(name: &str) -> Self {
+        if let Some(theme) = Self::built_in(name) {
+            return theme;
+        }
+        let path = if name.contains('/') {
+            Some(PathBuf::from(name))
+        } else {
+            paths::config_home().map(|dir| dir.join(format!("launcher/themes/{}.toml", name)))
+        };
+        let file = path
+            .and_then(|path| fs::read_to_string(path).ok())
+            .ok_or_else(|| "not found".to_string())
+            .and_then(|content| {
+                toml::from_str::<ThemeConfig>(&content).map_err(|error| error.to_string())
+            });
+        match file {
+            Ok(file) => {
+                let base = file.name.as_deref().and_then(Self::built_in);
+                file.apply(base.unwrap_or_default())
+            }
+            Err(error) => {
+                log::info(&format!("theme {}: {}", name, error));
+                Self::default()
+            }
+        }
+    }
+
+    fn built_in(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "light" => Some(Self::light()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "mono" => Some(Self::default().without_colors()),
+            _ => None,
+        }
+    }
+
+    /// Dark text for terminals with a light background.
+    pub fn light() -> Self {
+        Self {
+            fade: false,
+            text: Style::new().fg(Color::Black),
+            selected: Style::new().fg(Color::White).bg(Color::Blue).not_reversed(),
+            accent: Style::new().blue(),
+            running: Style::new().green(),
+            urgent: Style::new().red(),
+            ..Self::default()
+        }
+    }
+
+    /// Bright colors on black, bold where the default theme dims.
+    pub fn high_contrast() -> Self {
+        Self {
+            fade: false,
+            text: Style::new().fg(Color::White).bg(Color::Black),
+            selected: Style::new()
+                .fg(Color::Black)
+                .bg(Color::LightYellow)
+                .bold()
+                .not_reversed(),
+            dim: Style::new().fg(Color::White).italic(),
+            placeholder: Style::new().fg(Color::White).italic(),
+            header: Style::new().fg(Color::LightYellow).bold(),
+            border: Style::new().fg(Color::LightYellow).bold(),
+            border_unfocused: Style::new().fg(Color::White),
+            matched: Style::new().bold().underlined().reversed(),
+            accent: Style::new().light_cyan().bold(),
+            error: Style::new().light_red().bold(),
+            running: Style::new().light_green().bold(),
+            urgent: Style::new().light_red().bold().underlined(),
+            ..Self::default()
+        }
+    }
+
+    /// Tells things apart by bold, underline and reversed video alone.
+    pub fn without_colors(self) -> Self {
+        Self {
+            colors: false,
+            fade: false,
+            text: Style::new(),
+            selected: Style::new().reversed(),
+            dim: uncolored(self.dim),
+            placeholder: uncolored(self.placeholder),
+            header: uncolored(self.header),
+            title: uncolored(self.title),
+            border: uncolored(self.border),
+            border_unfocused: uncolored(self.border_unfocused),
+            matched: Style::new().bold().underlined(),
+            accent: Style::new().bold(),
+            error: Style::new().bold(),
+            running: Style::new(),
+            urgent: Style::new().bold().underlined(),
+            ..self
+        }
+    }
+
+    /// Draws with ASCII characters only, for terminals and fonts without box drawing or
+    /// Nerd Font glyphs.
+    pub fn ascii(self) -> Self {
+        Self {
+            icons: false,
+            border_set: ASCII_BORDER,
+            prompt: "> ".to_string(),
+            selector: "> ".to_string(),
+            scrollbar_thumb: "|".to_string(),
+            running_dot: "*".to_string(),
+            gutter: "|".to_string(),
+            separator: "---".to_string(),
+            action_arrow: "->".to_string(),
+            cursor: "_".to_string(),
+            hint_separator: " - ".to_string(),
+            arrows: ["Left".to_string(), "Right".to_string()],
+            chevrons: ["<".to_string(), ">".to_string()],
+            spinner: vec!['|', '/', '-', '\\'],
+            ..self
+        }
+    }
+
+    /// A chip in `color`, like the badges at the end of rows.
+    pub fn chip(&self, color: Color) -> Style {
+        if self.colors {
+            self.selected.bg(color)
+        } else {
+            Style::new().reversed()
+        }
+    }
+
+    /// A bordered block drawn with the theme's border symbols.
+    pub fn block(&self) -> Block<'static> {
+        Block::bordered()
+            .border_set(self.border_set)
+            .border_style(self.border)
+            .title_style(self.title)
+    }
+}
+
+/// The `[theme]` section, and the content of theme files: a base theme with some of its
+/// styles and symbols replaced.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ThemeConfig {
+    /// A built-in theme, `default`, `light`, `high-contrast` or `mono`, or the name of a
+    /// file in `$XDG_CONFIG_HOME/launcher/themes` without `.toml`, or a path to one.
+    /// Theme files can only name a built-in theme.
+    pub name: Option<String>,
+    pub fade: Option<bool>,
+    pub text: StyleConfig,
+    pub selected: StyleConfig,
+    pub dim: StyleConfig,
+    pub placeholder: StyleConfig,
+    pub header: StyleConfig,
+    pub title: StyleConfig,
+    pub border: StyleConfig,
+    pub border_unfocused: StyleConfig,
+    pub matched: StyleConfig,
+    pub accent: StyleConfig,
+    pub error: StyleConfig,
+    pub running: StyleConfig,
+    pub urgent: StyleConfig,
+    pub badges: BTreeMap<String, Color>,
+    pub border_type: Option<BorderType>,
+    pub prompt: Option<String>,
+    pub selector: Option<String>,
+    pub scrollbar_thumb: Option<String>,
+    pub running_dot: Option<String>,
+    pub gutter: Option<String>,
+    pub separator: Option<String>,
+    pub action_arrow: Option<String>,
+    pub cursor: Option<String>,
+    pub hint_separator: Option<String>,
+    pub arrows: Option<[String; 2]>,
+    pub chevrons: Option<[String; 2]>,
+    /// The frames of the spinner, one character each.
+    pub spinner: Option<String>,
+}
+
+impl ThemeConfig {
+    fn apply(&self, mut theme: Theme) -> Theme {
+        for (style, config) in [
+            (&mut theme.text, self.text),
+            (&mut theme.selected, self.selected),
+            (&mut theme.dim, self.dim),
+            (&mut theme.placeholder, self.placeholder),
+            (&mut theme.header, self.header),
+            (&mut theme.title, self.title),
+            (&mut theme.border, self.border),
+            (&mut theme.border_unfocused, self.border_unfocused),
+            (&mut theme.matched, self.matched),
+            (&mut theme.accent, self.accent),
+            (&mut theme.error, self.error),
+            (&mut theme.running, self.running),
+            (&mut theme.urgent, self.urgent),
+        ] {
+            *style = config.patch(*style);
+        }
+        for (symbol, config) in [
+            (&mut theme.prompt, &self.prompt),
+            (&mut theme.selector, &self.selector),
+            (&mut theme.scrollbar_thumb, &self.scrollbar_thumb),
+            (&mut theme.running_dot, &self.running_dot),
+            (&mut theme.gutter, &self.gutter),
+            (&mut theme.separator, &self.separator),
+            (&mut theme.action_arrow, &self.action_arrow),
+            (&mut theme.cursor, &self.cursor),
+            (&mut theme.hint_separator, &self.hint_separator),
+        ] {
+            if let Some(value) = config {
+                symbol.clone_from(value);
+            }
+        }
+        if let Some(fade) = self.fade {
+            theme.fade = fade;
+        }
+        theme.badges.extend(self.badges.clone());
+        if let Some(border_type) = self.border_type {
+            theme.border_set = border_type.set();
+        }
+        if let Some(arrows) = &self.arrows {
+            theme.arrows = arrows.clone();
+        }
+        if let Some(chevrons) = &self.chevrons {
+            theme.chevrons = chevrons.clone();
+        }
+        if let Some(spinner) = &self.spinner {
+            theme.spinner = spinner.chars().collect();
+        }
+        theme
+    }
+}
+
+/// A style in the config, e.g. `{ fg = "black", bg = "#ffcc00", bold = true }`. Colors
+/// replace those of the base style, and modifiers set to `false` are removed from it.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(default)]
+pub struct StyleConfig {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+    pub bold: Option<bool>,
+    pub dim: Option<bool>,
+    pub italic: Option<bool>,
+    pub underlined: Option<bool>,
+    pub reversed: Option<bool>,
+}
+
+impl StyleConfig {
+    fn patch(self, mut style: Style) -> Style {
+        if let Some(fg) = self.fg {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg {
+            style = style.bg(bg);
+        }
+        for (set, modifier) in [
+            (self.bold, Modifier::BOLD),
+            (self.dim, Modifier::DIM),
+            (self.italic, Modifier::ITALIC),
+            (self.underlined, Modifier::UNDERLINED),
+            (self.reversed, Modifier::REVERSED),
+        ] {
+            style = match set {
+                Some(true) => style.add_modifier(modifier),
+                Some(false) => style.remove_modifier(modifier),
+                None => style,
+            };
+        }
+        style
+    }
+}
+
+/// The lines borders are drawn with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BorderType {
+    Plain,
+    Rounded,
+    Double,
+    Thick,
+    Ascii,
+}
+
+impl BorderType {
+    fn set(self) -> border::Set {
+        match self {
+            Self::Plain => border::PLAIN,
+            Self::Rounded => border::ROUNDED,
+            Self::Double => border::DOUBLE,
+            Self::Thick => border::THICK,
+            Self::Ascii => ASCII_BORDER,
+        }
+    }
+}
+
+/// Sets the theme `get` returns, again whenever the config changes; widgets draw with the
+/// default theme until then. A replaced theme stays allocated, as widgets may hold it.
+pub fn init(config: &Config) {
+    let theme = Box::leak(Box::new(Theme::new(config)));
+    *THEME.write().unwrap_or_else(|error| error.into_inner()) = Some(theme);
+}
+
+pub fn get() -> &'static Theme {
+    let theme = *THEME.read().unwrap_or_else(|error| error.into_inner());
+    theme.unwrap_or_else(|| DEFAULT.get_or_init(Theme::default))
+}
+
+/// Removes the colors left in `area` by widgets drawing colors of their own, e.g. the
+/// ANSI colors of a command's output or a source's accent, when the theme has none.
+pub fn strip_colors(area: Rect, buf: &mut Buffer) {
+    if get().colors {
+        return;
+    }
+    for y in area.top()..area.bottom() {
+        for x in area.left()..area.right() {
+            buf[(x, y)].set_fg(Color::Reset).set_bg(Color::Reset);
+        }
+    }
+}
+
+fn uncolored(style: Style) -> Style {
+    Style {
+        fg: None,
+        bg: None,
+        ..style
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    fs,
+    io::{self, Read, Write},
+    path::Path,
+    process::{Command, Stdio},
+    sync::OnceLock,
+};
+
+use chacha20poly1305::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    ChaCha20Poly1305, Key, Nonce,
+};
+
+use crate::{config::Config, log, paths};
+
+/// Starts every encrypted file, followed by the nonce and the ciphertext.
+const MAGIC: &[u8] = b"launcher-sealed-1\n";
+
+const NONCE_LENGTH: usize = 12;
+
+/// The `secret-tool` attributes the key is stored under in the keyring.
+const ATTRIBUTES: [&str; 4] = ["application", "launcher", "purpose", "history-key"];
+
+/// Whether the history files are written encrypted, set by `encrypt_history`.
+static ENABLED: OnceLock<bool> = OnceLock::new();
+
+/// The key from the keyring, fetched on first use as that may prompt to unlock it.
+/// `None` when the keyring can't be reached.
+static KEY: OnceLock<Option<Key>> = OnceLock::new();
+
+pub fn init(config: &Config) {
+    let _ = ENABLED.set(config.encrypt_history);
+}
+
+/// Reads the history file at `path`, decrypting it if it was written encrypted, even
+/// when `encrypt_history` was turned off since.
+pub fn read(path: &Path) -> io::Result<String> {
+    let content = fs::read(path)?;
+    let Some(sealed) = content.strip_prefix(MAGIC) else {
+        return String::from_utf8(content).map_err(io::Error::other);
+    };
+    if sealed.len() < NONCE_LENGTH {
+        return Err(io::Error::new(io::ErrorKind::InvalidData, "truncated"));
+    }
+    let (nonce, ciphertext) = sealed.split_at(NONCE_LENGTH);
+    let plaintext = cipher()?
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| io::Error::new(io::ErrorKind::InvalidData, "decryption failed"))?;
+    String::from_utf8(plaintext).map_err(io::Error::other)
+}
+
+/// Writes the history file at `path`, encrypted with `encrypt_history`. Fails without
+/// writing when the key is unavailable, rather than leaving the history in plaintext.
+/// Goes through a temporary file so a crash never leaves a truncated file.
+pub fn write(path: &Path, content: &str) -> io::Result<()> {
+    let bytes = if ENABLED.get().copied().unwrap_or_default() {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = cipher()?
+            .encrypt(&nonce, content.as_bytes())
+            .map_err(|_| io::Error::other("encryption failed"))?;
+        [MAGIC, &nonce, &ciphertext].concat()
+    } else {
+        content.as_bytes().to_vec()
+    };
+    let mut temporary = path.as_os_str().to_owned();
+    temporary.push(".tmp");
+    fs::write(&temporary, bytes)?;
+    fs::rename(&temporary, path)
+}
+
+fn cipher() -> io::Result<ChaCha20Poly1305> {
+    KEY.get_or_init(|| {
+        keyring_key()
+            .inspect_err(|error| log::info(&format!("history key unavailable: {}", error)))
+            .ok()
+    })
+    .as_ref()
+    .map(ChaCha20Poly1305::new)
+    .ok_or_else(|| io::Error::new(io::ErrorKind::PermissionDenied, "history key unavailable"))
+}
+
+/// The key stored in the keyring through the Secret Service, made and stored on first
+/// use. Fails when the keyring can't be asked, and when the key is missing while files
+/// sealed with it exist, as a new key could never open them.
+fn keyring_key() -> io::Result<Key> {
+    let output = Command::new("secret-tool")
+        .arg("lookup")
+        .args(ATTRIBUTES)
+        .output()?;
+    let stored = String::from_utf8_lossy(&output.stdout);
+    if output.status.success() {
+        return from_hex(stored.trim())
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "malformed stored key"));
+    }
+    // A lookup that finds nothing fails silently, one that can't reach the keyring
+    // says why.
+    let error = String::from_utf8_lossy(&output.stderr);
+    if !error.trim().is_empty() || !stored.trim().is_empty() {
+        return Err(io::Error::other(format!("lookup failed: {}", error.trim())));
+    }
+    if sealed_files_exist() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            "the key of the sealed history is missing",
+        ));
+    }
+    let key = ChaCha20Poly1305::generate_key(&mut OsRng);
+    let mut child = Command::new("secret-tool")
+        .args(["store", "--label=launcher history key"])
+        .args(ATTRIBUTES)
+        .stdin(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(to_hex(&key).as_bytes())?;
+    }
+    if !child.wait()?.success() {
+        return Err(io::Error::other("storing the key failed"));
+    }
+    Ok(key)
+}
+
+/// Whether any file in the state or data dir was written encrypted.
+fn sealed_files_exist() -> bool {
+    [paths::state_dir(), paths::data_dir()]
+        .into_iter()
+        .flatten()
+        .flat_map(|dir| fs::read_dir(dir).into_iter().flatten().flatten())
+        .any(|file| {
+            let mut start = [0; MAGIC.len()];
+            fs::File::open(file.path())
+                .and_then(|mut file| file.read_exact(&mut start))
+                .is_ok_and(|()| start == MAGIC)
+        })
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn from_hex(text: &str) -> Option<Key> {
+    if text.len() != 64 {
+        return None;
+    }
+    let bytes = (0..text.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(text.get(i..i + 2)?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    Some(*Key::from_slice(&bytes))
+}
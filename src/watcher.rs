@@ -0,0 +1,179 @@
+use std::{
+    collections::HashMap,
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver},
+    thread,
+};
+
+use inotify::{EventMask, Inotify, WatchDescriptor, WatchMask, Watches};
+
+use crate::applications;
+
+/// Watches the application directories and their subdirectories and sends the path of
+/// every `.desktop` file that was created, modified, moved or deleted, and of every
+/// directory that was removed. A directory that doesn't exist yet is watched for from
+/// its closest existing parent.
+pub fn watch(dirs: &[PathBuf]) -> Option<Receiver<PathBuf>> {
+    let mut inotify = Inotify::init().ok()?;
+    let mut watcher = Watcher {
+        watches: inotify.watches(),
+        dirs: dirs.to_vec(),
+        watched: HashMap::new(),
+    };
+    for dir in dirs {
+        watcher.watch_app_dir(dir);
+    }
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        while let Ok(events) = inotify.read_events_blocking(&mut buffer) {
+            for event in events {
+                for path in watcher.handle(&event.wd, event.mask, event.name) {
+                    if sender.send(path).is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+    Some(receiver)
+}
+
+struct Watcher {
+    watches: Watches,
+    /// The application directories.
+    dirs: Vec<PathBuf>,
+    watched: HashMap<WatchDescriptor, PathBuf>,
+}
+
+impl Watcher {
+    /// Watches the application directory `dir` with its subdirectories, or else the
+    /// closest of its parents that exists. Returns whether `dir` itself is watched.
+    fn watch_app_dir(&mut self, dir: &Path) -> bool {
+        match dir.ancestors().find(|parent| parent.is_dir()) {
+            Some(parent) if parent == dir => {
+                self.watch_tree(dir);
+                true
+            }
+            Some(parent) => {
+                self.add(parent);
+                false
+            }
+            None => false,
+        }
+    }
+
+    fn watch_tree(&mut self, dir: &Path) {
+        self.add(dir);
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                self.watch_tree(&path);
+            }
+        }
+    }
+
+    fn add(&mut self, dir: &Path) {
+        let mask = WatchMask::CLOSE_WRITE
+            | WatchMask::CREATE
+            | WatchMask::DELETE
+            | WatchMask::MOVED_FROM
+            | WatchMask::MOVED_TO
+            | WatchMask::DELETE_SELF
+            | WatchMask::MOVE_SELF;
+        if let Ok(descriptor) = self.watches.add(dir, mask) {
+            self.watched.insert(descriptor, dir.to_path_buf());
+        }
+    }
+
+    /// Stops watching `dir` and its subdirectories.
+    fn remove(&mut self, dir: &Path) {
+        let gone = self
+            .watched
+            .iter()
+            .filter(|(_, watched)| watched.starts_with(dir))
+            .map(|(descriptor, _)| descriptor.clone())
+            .collect::<Vec<WatchDescriptor>>();
+        for descriptor in gone {
+            self.watched.remove(&descriptor);
+            let _ = self.watches.remove(descriptor);
+        }
+    }
+
+    fn in_app_dir(&self, path: &Path) -> bool {
+        self.dirs.iter().any(|dir| path.starts_with(dir))
+    }
+
+    /// Follows an event on the watched `descriptor` and returns the paths to report.
+    fn handle(
+        &mut self,
+        descriptor: &WatchDescriptor,
+        mask: EventMask,
+        name: Option<&OsStr>,
+    ) -> Vec<PathBuf> {
+        let Some(dir) = self.watched.get(descriptor).cloned() else {
+            return Vec::new();
+        };
+        if mask.intersects(EventMask::DELETE_SELF | EventMask::MOVE_SELF) {
+            self.remove(&dir);
+            // An application directory or a parent of one went away, so wait for it again.
+            let lost = self
+                .dirs
+                .iter()
+                .filter(|app_dir| app_dir.starts_with(&dir))
+                .cloned()
+                .collect::<Vec<PathBuf>>();
+            for app_dir in lost {
+                self.watch_app_dir(&app_dir);
+            }
+            return if self.in_app_dir(&dir) {
+                vec![dir]
+            } else {
+                Vec::new()
+            };
+        }
+        let Some(name) = name else {
+            return Vec::new();
+        };
+        let path = dir.join(name);
+        let appeared = mask.intersects(EventMask::CREATE | EventMask::MOVED_TO);
+        if !self.in_app_dir(&path) {
+            // In a parent watched until an application directory exists.
+            if !appeared || !mask.contains(EventMask::ISDIR) {
+                return Vec::new();
+            }
+            let waiting = self
+                .dirs
+                .iter()
+                .filter(|app_dir| app_dir.starts_with(&path))
+                .cloned()
+                .collect::<Vec<PathBuf>>();
+            let mut paths = Vec::new();
+            for app_dir in waiting {
+                if self.watch_app_dir(&app_dir) {
+                    paths.extend(applications::desktop_files(&app_dir));
+                }
+            }
+            return paths;
+        }
+        if mask.contains(EventMask::ISDIR) {
+            // Files may have been added before the watch was, so they're listed.
+            if appeared {
+                self.watch_tree(&path);
+                return applications::desktop_files(&path);
+            }
+            if mask.intersects(EventMask::DELETE | EventMask::MOVED_FROM) {
+                self.remove(&path);
+                return vec![path];
+            }
+            return Vec::new();
+        }
+        if path.extension().and_then(|s| s.to_str()) == Some("desktop") {
+            vec![path]
+        } else {
+            Vec::new()
+        }
+    }
+}